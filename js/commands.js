@@ -54,6 +54,8 @@ export class CommandProcessor {
             this.terminal.writeln(' connect_cable(cable_id,component_id1,component_id2) - Connect cable(id) to component id1&2')
             this.terminal.writeln(' get_connect_id1(cable_id) - Get Id from cable id\'s endpoint1')
             this.terminal.writeln(' get_connect_id2(cable_id) - Get Id from cable id\'s endpoint2')
+            this.terminal.writeln(' set_receive(cable_id,component_id) - Show frames arriving at component_id on the cable')
+            this.terminal.writeln(' transmit(cable_id,from_id) - Send a frame from from_id over the cable')
             this.terminal.writeln(' clear - Clear terminal');
             this.terminal.writeln(' help - Show this help message');
         },
@@ -306,6 +308,62 @@ export class CommandProcessor {
                 this.terminal.writeln(`Error: ${e.message}`);
             }
         },
+        // set_receiveコマンド - イーサーネットケーブルからcomponent_idに届いたフレームを表示する
+        set_receive: (cable_id = null,component_id = null) => {
+            try {
+                if(cable_id === null){
+                    this.terminal.writeln('ケーブルidが指定されていません');
+                }
+                if(component_id === null){
+                    this.terminal.writeln('受信するコンポーネントのidが指定されていません');
+                }
+                cable_id = cable_id.replace(/['"]/g, '');
+                component_id = component_id.replace(/['"]/g, '');
+                var message = "";
+
+                const cable = this.currentEthernetCable[cable_id]?.cable;
+                if(cable !== undefined){
+                    cable.set_receive_callback(component_id, (frame) => {
+                        this.terminal.writeln(`${component_id}がフレームを受信しました`);
+                        this.terminal.writeln(`${frame.to_string()}`);
+                    });
+                    message = `ケーブル:${cable.get_id()}の${component_id}側で受信を待ちます`
+                }else{
+                    message = `id:${cable_id}は無効なケーブルIdです。`;
+                }
+                this.terminal.writeln(message);
+                
+            } catch (e) {
+                this.terminal.writeln(`Error: ${e.message}`);
+            }
+        },
+        // transmitコマンド - イーサーネットケーブルのfrom_id側からフレームを送信する
+        transmit: (cable_id = null,from_id = null) => {
+            try {
+                if(cable_id === null){
+                    this.terminal.writeln('ケーブルidが指定されていません');
+                }
+                if(from_id === null){
+                    this.terminal.writeln('送信するコンポーネントのidが指定されていません');
+                }
+                cable_id = cable_id.replace(/['"]/g, '');
+                from_id = from_id.replace(/['"]/g, '');
+                var message = "";
+
+                const cable = this.currentEthernetCable[cable_id]?.cable;
+                if(cable !== undefined){
+                    let frame = new WasmEthernetFrame(new WasmMacAddress(),new WasmMacAddress(),'0x0800',[]);
+                    cable.transmit(from_id, new WasmPhysicalLayerFrame(frame));
+                    message = `ケーブル:${cable.get_id()}の${from_id}側からフレームを送信しました`
+                }else{
+                    message = `id:${cable_id}は無効なケーブルIdです。`;
+                }
+                this.terminal.writeln(message);
+                
+            } catch (e) {
+                this.terminal.writeln(`Error: ${e.message}`);
+            }
+        },
         // clear - 画面のクリアー
         clear: () => {
             this.terminal.clear();
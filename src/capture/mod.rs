@@ -0,0 +1,3 @@
+pub(crate) mod pcap;
+
+pub use pcap::PcapWriter;
@@ -0,0 +1,128 @@
+use crate::layer1::packets::PhysicalLayerFrame;
+use crate::layer2::packets::EthernetFrame;
+
+/// キャプチャしたフレームをWiresharkで開けるpcap形式(libpcapの従来の形式)で書き出す
+///
+/// ファイルの構成
+/// - グローバルヘッダ (24バイト): マジックナンバー、バージョン、スナップ長、リンクタイプなど
+/// - パケットごとに、レコードヘッダ (16バイト: 時刻(秒) + 時刻(マイクロ秒) + 保存した長さ + 元の長さ) + フレームのバイト列
+///
+/// 数値はすべてリトルエンディアンで書き込む(読む側はマジックナンバーの並びでバイトオーダーを判断する)
+#[derive(Clone, Debug)]
+pub struct PcapWriter {
+    buffer: Vec<u8>,
+    frame_count: usize,
+}
+
+impl Default for PcapWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PcapWriter {
+    /// マジックナンバー (マイクロ秒の時刻)
+    pub const MAGIC: u32 = 0xA1B2C3D4;
+    pub const VERSION_MAJOR: u16 = 2;
+    pub const VERSION_MINOR: u16 = 4;
+    /// 1つのパケットとして保存する最大のバイト数
+    pub const SNAPLEN: u32 = 65535;
+    /// リンクタイプ 1はイーサネット
+    pub const LINKTYPE_ETHERNET: u32 = 1;
+    /// グローバルヘッダの長さ(バイト)
+    pub const GLOBAL_HEADER_LENGTH: usize = 24;
+    /// レコードヘッダの長さ(バイト)
+    pub const RECORD_HEADER_LENGTH: usize = 16;
+
+    /// グローバルヘッダだけを書き込んだ状態で生成する
+    pub fn new() -> Self {
+        let mut buffer = Vec::with_capacity(Self::GLOBAL_HEADER_LENGTH);
+        buffer.extend_from_slice(&Self::MAGIC.to_le_bytes());
+        buffer.extend_from_slice(&Self::VERSION_MAJOR.to_le_bytes());
+        buffer.extend_from_slice(&Self::VERSION_MINOR.to_le_bytes());
+        buffer.extend_from_slice(&0i32.to_le_bytes()); // thiszone: 時刻はUTC
+        buffer.extend_from_slice(&0u32.to_le_bytes()); // sigfigs: 0にするのが慣例
+        buffer.extend_from_slice(&Self::SNAPLEN.to_le_bytes());
+        buffer.extend_from_slice(&Self::LINKTYPE_ETHERNET.to_le_bytes());
+        Self {
+            buffer,
+            frame_count: 0,
+        }
+    }
+
+    /// イーサネットフレームを1つ書き込む
+    /// 回線上を流れるバイト列から、プリアンブル/SFD/FCSを除いたもの(宛先MACから始まる)を書き込む
+    pub fn write_frame(&mut self, ts_secs: u32, ts_usecs: u32, frame: &EthernetFrame) {
+        let bytes = frame.to_bytes();
+        let orig_len = bytes.len() as u32;
+        let incl_len = orig_len.min(Self::SNAPLEN);
+
+        self.buffer.reserve(Self::RECORD_HEADER_LENGTH + incl_len as usize);
+        self.buffer.extend_from_slice(&ts_secs.to_le_bytes());
+        self.buffer.extend_from_slice(&ts_usecs.to_le_bytes());
+        self.buffer.extend_from_slice(&incl_len.to_le_bytes());
+        self.buffer.extend_from_slice(&orig_len.to_le_bytes());
+        self.buffer.extend_from_slice(&bytes[..incl_len as usize]);
+        self.frame_count += 1;
+    }
+
+    /// 物理層フレームを1つ書き込む
+    /// pcapにはプリアンブル/SFDは含めないので、中のイーサネットフレームを書き込む
+    pub fn write_physical_frame(&mut self, ts_secs: u32, ts_usecs: u32, frame: &PhysicalLayerFrame) {
+        self.write_frame(ts_secs, ts_usecs, &frame.ethernet_frame);
+    }
+
+    /// 書き込んだフレームの数
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// ここまでに書き込んだpcapファイルの内容
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// 書き込みを終えて、pcapファイルの内容を取得する
+    pub fn finish(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer2::address::MacAddress;
+
+    #[test]
+    fn global_header_matches_the_libpcap_layout() {
+        let header = PcapWriter::new().finish();
+        assert_eq!(header.len(), PcapWriter::GLOBAL_HEADER_LENGTH);
+        assert_eq!(header[0..4], [0xD4, 0xC3, 0xB2, 0xA1]); // magic (リトルエンディアン)
+        assert_eq!(header[4..6], [2, 0]);                   // version_major
+        assert_eq!(header[6..8], [4, 0]);                   // version_minor
+        assert_eq!(header[8..16], [0; 8]);                  // thiszone, sigfigs
+        assert_eq!(header[16..20], [0xFF, 0xFF, 0, 0]);     // snaplen 65535
+        assert_eq!(header[20..24], [1, 0, 0, 0]);           // linktype イーサネット
+    }
+
+    #[test]
+    fn records_wrap_the_frame_bytes_without_preamble() {
+        let frame = EthernetFrame::new(
+            Some(MacAddress::get_broadcast_mac_addr()),
+            Some(MacAddress::from_array([0x02, 0, 0, 0, 0, 1])),
+            Some(0x0806),
+            Some(vec![0xAB; 46]),
+        );
+        let mut writer = PcapWriter::new();
+        writer.write_physical_frame(10, 500, &PhysicalLayerFrame::new(Some(frame.clone())));
+        assert_eq!(writer.frame_count(), 1);
+
+        let bytes = writer.finish();
+        let record = &bytes[PcapWriter::GLOBAL_HEADER_LENGTH..];
+        assert_eq!(record[0..4], 10u32.to_le_bytes());
+        assert_eq!(record[4..8], 500u32.to_le_bytes());
+        assert_eq!(record[8..12], 60u32.to_le_bytes());
+        assert_eq!(record[12..16], 60u32.to_le_bytes());
+        assert_eq!(record[PcapWriter::RECORD_HEADER_LENGTH..], frame.to_bytes()[..]);
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+
+use wasm_bindgen::JsValue;
+
+/// アドレスなどを文字列から読み取る時のエラー
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// 区切り文字で分けたグループの数が正しくない (例: MACアドレスのオクテットが5つしかない)
+    WrongSegmentCount { expected: usize, found: usize },
+    /// バイト列の長さが正しくない (例: MACアドレスに3バイトしか渡されなかった)
+    WrongLength { expected: usize, found: usize },
+    /// 16進数として読めない文字を含んでいる
+    InvalidHex,
+    /// 1つのグループの桁数が多すぎる (例: IPv6アドレスのグループが5桁以上ある)
+    TooManyDigits { max: usize },
+    /// 値が範囲外 (例: IPv4アドレスのオクテットが255を超える、プレフィックス長が32を超える)
+    OutOfRange,
+    /// それ以外の形式の誤り
+    InvalidFormat(&'static str),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::WrongSegmentCount { expected, found } => {
+                write!(f, "Wrong number of segments: expected {}, found {}", expected, found)
+            }
+            ParseError::WrongLength { expected, found } => {
+                write!(f, "Wrong number of bytes: expected {}, found {}", expected, found)
+            }
+            ParseError::InvalidHex => write!(f, "Invalid hex digits"),
+            ParseError::TooManyDigits { max } => write!(f, "Too many digits in a segment: at most {} allowed", max),
+            ParseError::OutOfRange => write!(f, "Value is out of range"),
+            ParseError::InvalidFormat(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// JavaScriptにはエラーメッセージの文字列として渡す
+impl From<ParseError> for JsValue {
+    fn from(error: ParseError) -> Self {
+        JsValue::from_str(&error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_describes_each_variant() {
+        assert_eq!(
+            ParseError::WrongSegmentCount { expected: 6, found: 5 }.to_string(),
+            "Wrong number of segments: expected 6, found 5"
+        );
+        assert_eq!(
+            ParseError::WrongLength { expected: 4, found: 3 }.to_string(),
+            "Wrong number of bytes: expected 4, found 3"
+        );
+        assert_eq!(ParseError::InvalidHex.to_string(), "Invalid hex digits");
+        assert_eq!(ParseError::TooManyDigits { max: 4 }.to_string(), "Too many digits in a segment: at most 4 allowed");
+        assert_eq!(ParseError::OutOfRange.to_string(), "Value is out of range");
+        assert_eq!(ParseError::InvalidFormat("Bad input").to_string(), "Bad input");
+    }
+}
@@ -1,7 +1,44 @@
-use std::{fmt::{self, Debug, Formatter}, sync::{Arc, Mutex}};
+use std::{collections::VecDeque, fmt::{self, Debug}, sync::{Arc, Mutex, MutexGuard, Weak}};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use crate::{layer1::{packets::PhysicalLayerFrame, receive_callback::PhysicalLayerCallback}, showTerminal};
+use crate::{layer1::{packets::PhysicalLayerFrame, receive_callback::{CableEventCallback, ConnectionCallback, PhysicalLayerCallback, TapCallback}}, run_after_ms, showTerminal};
+use crate::layer2::{address::MacAddress, packets::EthernetFrame};
+use crate::util::{browser_clock, Clock};
+
+/// フレームが相手に届かずに破棄された理由
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropReason {
+    LinkNotReady, // 両端はつながったが、まだリンクアップしていない
+    Lost,         // loss_rateの確率でフレームが失われた
+    Corrupted,    // ビット誤りでVLANタグなどが壊れ、フレームとして読み取れなくなった
+    Collision,    // 半二重で、他のフレームが届く前に送信が重なり衝突した
+    Oversized,    // ペイロードがMTUより大きい
+}
+
+/// ケーブルで起きたことを通知するイベント
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CableEvent {
+    Connected,           // リンクアップして通信できるようになった
+    Dropped(DropReason), // フレームが破棄された
+}
+
+/// ケーブルの端
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    One, // endpoint1
+    Two, // endpoint2
+}
+
+impl Endpoint {
+    /// 反対側の端
+    pub fn opposite(self) -> Endpoint {
+        match self {
+            Endpoint::One => Endpoint::Two,
+            Endpoint::Two => Endpoint::One,
+        }
+    }
+}
 
 /// EthernetCableの本体
 #[derive(Clone)]
@@ -12,6 +49,92 @@ pub struct EthernetCableState {
     pub endpoint2_component_id : Option<String>,
     pub endpoint2_callback     : Option<PhysicalLayerCallback>,
     pub connected              : bool,
+    pub now_ms                 : f64,                  // シミュレーション上の現在時刻(ミリ秒)。tick()で進む
+    pub scheduled_frames       : Vec<ScheduledFrame>,  // 送信予定時刻順に並んだ送信待ちフレーム
+    pub link_up_delay_ms       : u32,                  // 両端がつながってからリンクアップするまでの時間(ミリ秒)
+    pub link_up_at_ms          : Option<f64>,          // リンクアップする予定の時刻(ミリ秒)
+    pub link_up                : bool,                 // リンクアップして実際に通信できる状態かどうか
+    pub event_callback         : Option<CableEventCallback>,
+    pub mirror_component_id    : Option<String>,                // SPANポートとしてフレームのコピーを受け取るコンポーネントのId
+    pub mirror_callback        : Option<PhysicalLayerCallback>, // SPANポートのコンポーネントのcallback
+    pub stats                  : CableStats,                    // 向きごとの送信フレーム数/バイト数
+    pub loss_rate              : f64,                           // フレームが失われる確率(0.0〜1.0)
+    pub length_meters          : f64,                           // ケーブルの長さ(メートル)。伝搬遅延の計算に使う
+    pub on_connection_change   : Option<ConnectionCallback>,    // connectedが変わった時に、新しい値で呼ばれるcallback
+    pub tap_callback           : Option<TapCallback>,           // ケーブルを流れる全てのフレームのコピーを受け取るcallback(盗聴用のタップ)
+    pub ber                    : f64,                           // ビット誤り率。1ビットごとにこの確率で反転する(0.0〜1.0)
+    pub loopback               : bool,                          // trueなら送ったフレームが送信元に折り返す(ループバックプラグ)
+    pub half_duplex            : bool,                          // trueなら半二重。送信中に別の送信が重なると衝突する(CSMA/CD)
+    pub in_flight              : Option<InFlightFrame>,         // 半二重で、伝搬遅延の後に届く予定のフレーム
+    pub mtu                    : usize,                         // 送れるペイロードの最大バイト数(MTU)
+    pub capture                : Option<VecDeque<PhysicalLayerFrame>>, // キャプチャ中なら、ケーブルに流れたフレームを古い順に記録する
+    pub capture_max_frames     : usize,                         // キャプチャで記録しておくフレームの最大数。超えたら古いものから捨てる
+    pub bandwidth_bps          : Option<u64>,                   // 帯域幅(ビット/秒)。Noneなら送り出すのに時間はかからない
+    pub last_transmit_delay_ms : f64,                           // 最後に送ったフレームが届くまでの時間(送出遅延+伝搬遅延、ミリ秒)
+    pub transmit_free_at_ms    : f64,                           // 最後に送ったフレームの送出とフレーム間ギャップが終わる時刻(ミリ秒)
+    pub deferred_frames        : VecDeque<(String, PhysicalLayerFrame)>, // フレーム間ギャップが終わるのを待っている送信元のIdとフレーム
+    pub clock                  : Clock,                         // フレーム間ギャップを測る時計
+}
+
+/// 半二重のケーブルを伝搬中のフレーム
+/// 衝突した時に、すでに数えた統計情報を取り消すために送信した時の情報を覚えておく
+#[derive(Clone, Copy, Debug)]
+pub struct InFlightFrame {
+    pub from_ep1  : bool, // endpoint1から送られたかどうか
+    pub bytes     : u64,  // 統計情報に数えたバイト数
+    pub corrupted : bool, // ビット誤りで壊れたとして数えたかどうか
+    pub collided  : bool, // 後から送られたフレームと衝突したかどうか。trueなら届けずに捨てる
+}
+
+/// 片方向に流れたフレームの数とバイト数
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectionStats {
+    pub frames : u64,
+    pub bytes  : u64,
+}
+
+/// ケーブルの統計情報
+/// 全二重なので、届けたフレームは向きごとに別々に数える
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CableStats {
+    pub ep1_to_ep2       : DirectionStats, // endpoint1からendpoint2へ届けたフレーム
+    pub ep2_to_ep1       : DirectionStats, // endpoint2からendpoint1へ届けたフレーム
+    pub frames_dropped   : u64,            // 相手に届かずに破棄されたフレームの数
+    pub frames_corrupted : u64,            // ビット誤りで壊れたまま届いたフレームの数
+    pub collisions       : u64,            // 半二重で送信が重なった回数
+    pub frames_oversized : u64,            // ペイロードがMTUより大きくて破棄されたフレームの数
+}
+
+/// ケーブルの両端に何がつながっているか
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionInfo {
+    pub endpoint1 : Option<String>, // endpoint1につながっているコンポーネントのId
+    pub endpoint2 : Option<String>, // endpoint2につながっているコンポーネントのId
+    pub connected : bool,           // 両端がつながっているかどうか
+}
+
+/// タップで受け取るフレームの条件
+/// 指定した条件を全て満たすフレームだけを受け取る。Noneの条件は見ない
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TapFilter {
+    pub ethertype : Option<u16>,        // イーサタイプ(VLANタグの内側のもの)
+    pub dst_mac   : Option<MacAddress>, // 宛先MACアドレス
+}
+
+impl TapFilter {
+    /// フレームが条件に合うかどうか
+    pub fn matches(&self, frame: &EthernetFrame) -> bool {
+        self.ethertype.is_none_or(|ethertype| frame.ethertype == ethertype)
+            && self.dst_mac.is_none_or(|dst_mac| frame.dst_mac == dst_mac)
+    }
+}
+
+/// schedule_transmit()で予約された送信待ちのフレーム
+#[derive(Clone)]
+pub struct ScheduledFrame {
+    pub at_ms   : f64,                // 送信する時刻(ミリ秒)
+    pub from_id : String,             // 送信元のコンポーネントId
+    pub frame   : PhysicalLayerFrame, // 送信するフレーム
 }
 /// Display
 /// ```rust
@@ -82,7 +205,24 @@ impl fmt::Display for EthernetCableState {
             #endpoint1_callback     : {}\n\
             #endpoint2_component_id : {:?}\n\
             #endpoint2_callback     : {}\n\
-            #connected              : {}\n",
+            #connected              : {}\n\
+            #now_ms                 : {}\n\
+            #scheduled_frames       : {}\n\
+            #link_up_delay_ms       : {}\n\
+            #link_up                : {}\n\
+            #mirror_component_id    : {:?}\n\
+            #loss_rate              : {}\n\
+            #length_meters          : {}\n\
+            #propagation_delay_ns   : {}\n\
+            #ber                    : {}\n\
+            #loopback               : {}\n\
+            #half_duplex            : {}\n\
+            #in_flight              : {}\n\
+            #mtu                    : {}\n\
+            #capture                : {}\n\
+            #bandwidth_bps          : {}\n\
+            #last_transmit_delay_ms : {}\n\
+            #deferred_frames        : {}\n",
             self.id,
             self.endpoint1_component_id,
             endpoint1_callback_ptr
@@ -93,6 +233,27 @@ impl fmt::Display for EthernetCableState {
                 .map(|ptr| format!("{:p}", ptr))
                 .unwrap_or_else(|| "None".to_string()),
             self.connected,
+            self.now_ms,
+            self.scheduled_frames.len(),
+            self.link_up_delay_ms,
+            self.link_up,
+            self.mirror_component_id,
+            self.loss_rate,
+            self.length_meters,
+            self.propagation_delay_ns(),
+            self.ber,
+            self.loopback,
+            self.half_duplex,
+            self.in_flight.is_some(),
+            self.mtu,
+            self.capture.as_ref()
+                .map(|capture| format!("{}/{} frames", capture.len(), self.capture_max_frames))
+                .unwrap_or_else(|| "off".to_string()),
+            self.bandwidth_bps
+                .map(|bps| bps.to_string())
+                .unwrap_or_else(|| "unlimited".to_string()),
+            self.last_transmit_delay_ms,
+            self.deferred_frames.len(),
         )
     }
 }
@@ -109,6 +270,135 @@ impl EthernetCableState {
             endpoint2_component_id : None,
             endpoint2_callback     : None,
             connected              : false,
+            now_ms                 : 0.0,
+            scheduled_frames       : Vec::new(),
+            link_up_delay_ms       : 0,
+            link_up_at_ms          : None,
+            link_up                : false,
+            event_callback         : None,
+            mirror_component_id    : None,
+            mirror_callback        : None,
+            stats                  : CableStats::default(),
+            loss_rate              : 0.0,
+            length_meters          : 0.0,
+            on_connection_change   : None,
+            tap_callback           : None,
+            ber                    : 0.0,
+            loopback               : false,
+            half_duplex            : false,
+            in_flight              : None,
+            mtu                    : Self::DEFAULT_MTU,
+            capture                : None,
+            capture_max_frames     : 0,
+            bandwidth_bps          : None,
+            last_transmit_delay_ms : 0.0,
+            transmit_free_at_ms    : 0.0,
+            deferred_frames        : VecDeque::new(),
+            clock                  : browser_clock(),
+        }
+    }
+
+    /// イーサネットの標準のMTU(バイト)
+    pub const DEFAULT_MTU: usize = 1500;
+
+    /// 銅線での1メートルあたりの伝搬遅延(ナノ秒)
+    /// 銅線の中を信号が進む速さは光速の2/3程度なので、1メートルあたり約5ナノ秒とする
+    const PROPAGATION_DELAY_NS_PER_METER: f64 = 5.0;
+
+    /// 信号がケーブルの端から端まで届くのにかかる時間(ナノ秒)
+    pub fn propagation_delay_ns(&self) -> f64 {
+        self.length_meters * Self::PROPAGATION_DELAY_NS_PER_METER
+    }
+
+    /// bytesバイトのフレームをケーブルに送り出すのにかかる時間(送出遅延、ミリ秒)
+    /// フレームのビット数を帯域幅で割った時間。帯域幅が設定されていなければ0
+    pub fn serialization_delay_ms(&self, bytes: usize) -> f64 {
+        match self.bandwidth_bps {
+            Some(bps) if bps > 0 => (bytes * 8) as f64 / bps as f64 * 1000.0,
+            _ => 0.0,
+        }
+    }
+
+    /// フレーム間ギャップのビット数
+    const INTERFRAME_GAP_BITS: usize = 96;
+
+    /// フレーム間ギャップ(ミリ秒)
+    /// 連続して送るフレームの間には、96ビットを送る時間だけ間を空けなければならない。帯域幅が設定されていなければ0
+    pub fn interframe_gap_ms(&self) -> f64 {
+        self.serialization_delay_ms(Self::INTERFRAME_GAP_BITS / 8)
+    }
+
+    /// component_idのコンポーネントがどちらの端につながっているか
+    /// どちらの端にもつながっていない場合と、両端が同じIdでどちらか決められない場合はNone
+    pub fn which_endpoint(&self, component_id: &str) -> Option<Endpoint> {
+        let on_endpoint1 = self.endpoint1_component_id.as_deref() == Some(component_id);
+        let on_endpoint2 = self.endpoint2_component_id.as_deref() == Some(component_id);
+        match (on_endpoint1, on_endpoint2) {
+            (true, false) => Some(Endpoint::One),
+            (false, true) => Some(Endpoint::Two),
+            _ => None,
+        }
+    }
+
+    /// 指定した端のcallback
+    fn endpoint_callback(&self, endpoint: Endpoint) -> Option<PhysicalLayerCallback> {
+        match endpoint {
+            Endpoint::One => self.endpoint1_callback.clone(),
+            Endpoint::Two => self.endpoint2_callback.clone(),
+        }
+    }
+
+    /// キャプチャ中なら、ケーブルに流れたフレームのコピーを記録する
+    /// 記録したフレームが最大数を超えたら、古いものから捨てる
+    fn record_capture(&mut self, frame: &PhysicalLayerFrame) {
+        let max_frames = self.capture_max_frames;
+        let Some(capture) = self.capture.as_mut() else {
+            return;
+        };
+        capture.push_back(frame.clone());
+        while capture.len() > max_frames {
+            capture.pop_front();
+        }
+    }
+
+    /// 両端がつながった時に呼ぶ
+    /// リンクアップまでの待ち時間がなければすぐにリンクアップしてtrueを返す
+    /// 待ち時間がある場合は、リンクアップする時刻を決めておき、tick()でその時刻になったらリンクアップする
+    fn mark_connected(&mut self) -> bool {
+        if self.connected {
+            return false;
+        }
+        self.connected = true;
+        if self.link_up_delay_ms == 0 {
+            self.link_up = true;
+            self.link_up_at_ms = None;
+            return true;
+        }
+        self.link_up_at_ms = Some(self.now_ms + self.link_up_delay_ms as f64);
+        false
+    }
+
+    /// 片方の端が外れた時に呼ぶ
+    /// リンクダウンし、リンクアップ待ちも取り消す。connectedが変わった場合はtrueを返す
+    fn mark_disconnected(&mut self) -> bool {
+        if !self.connected {
+            return false;
+        }
+        self.connected = false;
+        self.link_up = false;
+        self.link_up_at_ms = None;
+        true
+    }
+
+    /// リンクアップする時刻になっていたらリンクアップしてtrueを返す
+    fn try_link_up(&mut self) -> bool {
+        match self.link_up_at_ms {
+            Some(at_ms) if self.connected && !self.link_up && self.now_ms >= at_ms => {
+                self.link_up = true;
+                self.link_up_at_ms = None;
+                true
+            }
+            _ => false,
         }
     }
 }
@@ -143,108 +433,713 @@ impl EthernetCable {
     }
 
     /// ケーブルの接続どちらかの端がまずどちらかに繋がるのでOptionにしてコンポーネントのIdを渡す
-    pub fn connect(&self, ep1_connect_id: Option<String>, ep2_connect_id: Option<String>) {
+    /// 両端に同じコンポーネントのIdをつなぐことはできない。送ったフレームがどちらの端から来たのか決められないため
+    /// その場合は何も変えずにエラーを返す。自分に折り返したい時はset_loopbackを使う
+    pub fn connect(&self, ep1_connect_id: Option<String>, ep2_connect_id: Option<String>) -> Result<(), &'static str> {
         debug("EthernetCable::connect() called.");
+        if ep1_connect_id.is_some() && ep1_connect_id == ep2_connect_id {
+            debug("EthernetCable::connect() both endpoints have the same component id.");
+            return Err(Self::SAME_COMPONENT_ERROR);
+        }
         let mut state = self.state.lock().unwrap();
         
         state.endpoint1_component_id = ep1_connect_id;
 
         state.endpoint2_component_id = ep2_connect_id;
 
-        if state.endpoint1_component_id.is_some() && state.endpoint2_component_id.is_some() {
-            state.connected = true;
-        }
+        Self::update_connection(state);
+        Ok(())
     }
 
-    pub fn connect_endpoint1(&self, ep1_connect_id: Option<String>) {
+    /// 両端に同じコンポーネントのIdをつなごうとした時のエラー
+    const SAME_COMPONENT_ERROR: &'static str = "Cannot connect both endpoints of a cable to the same component";
+
+    /// endpoint1にコンポーネントをつなぐ
+    /// endpoint2と同じIdはつなげないので、何も変えずにエラーを返す
+    pub fn connect_endpoint1(&self, ep1_connect_id: Option<String>) -> Result<(), &'static str> {
         debug("EthernetCable::connect_endpoint1() called.");
         let mut state = self.state.lock().unwrap();
+        if ep1_connect_id.is_some() && ep1_connect_id == state.endpoint2_component_id {
+            debug("EthernetCable::connect_endpoint1() endpoint2 already has the same component id.");
+            return Err(Self::SAME_COMPONENT_ERROR);
+        }
         state.endpoint1_component_id = ep1_connect_id;
 
-        if state.endpoint1_component_id.is_some() && state.endpoint2_component_id.is_some() {
-            debug(&format!("EthernetCable({})::bothe connected.",state.id));
-            state.connected = true;
-        }
+        Self::update_connection(state);
+        Ok(())
     }
     pub fn get_endpoint1_component_id(&self) -> Option<String> {
         let state = self.state.lock().unwrap();
         state.endpoint1_component_id.clone()
     }
-    pub fn connect_endpoint2(&self, ep2_connect_id: Option<String>) {
+    /// endpoint2にコンポーネントをつなぐ
+    /// endpoint1と同じIdはつなげないので、何も変えずにエラーを返す
+    pub fn connect_endpoint2(&self, ep2_connect_id: Option<String>) -> Result<(), &'static str> {
         debug("EthernetCable::connect_endpoint2() called.");
         let mut state = self.state.lock().unwrap();
+        if ep2_connect_id.is_some() && ep2_connect_id == state.endpoint1_component_id {
+            debug("EthernetCable::connect_endpoint2() endpoint1 already has the same component id.");
+            return Err(Self::SAME_COMPONENT_ERROR);
+        }
         state.endpoint2_component_id = ep2_connect_id;
 
-        if state.endpoint1_component_id.is_some() && state.endpoint2_component_id.is_some() {
-            debug(&format!("EthernetCable({})::bothe connected.",state.id));
-            state.connected = true;
-        }
+        Self::update_connection(state);
+        Ok(())
     }
     pub fn get_endpoint2_component_id(&self) -> Option<String> {
         let state = self.state.lock().unwrap();
         state.endpoint2_component_id.clone()
     }
 
+    /// 両端につながっているコンポーネントのIdと、両端がつながっているかどうかをまとめて取得
+    pub fn get_connection_info(&self) -> ConnectionInfo {
+        let state = self.state.lock().unwrap();
+        ConnectionInfo {
+            endpoint1 : state.endpoint1_component_id.clone(),
+            endpoint2 : state.endpoint2_component_id.clone(),
+            connected : state.connected,
+        }
+    }
+
+    /// component_idのコンポーネントがどちらの端につながっているかを取得
+    /// どちらの端にもつながっていない場合と、両端が同じIdでどちらか決められない場合はNone
+    pub fn which_endpoint(&self, component_id: &str) -> Option<Endpoint> {
+        let state = self.state.lock().unwrap();
+        state.which_endpoint(component_id)
+    }
+
+    /// endpoint1からコンポーネントを外す(ケーブルを抜く)
+    /// コンポーネントのIdとcallbackを消し、connectedをfalseにする
+    pub fn disconnect_endpoint1(&self) {
+        debug("EthernetCable::disconnect_endpoint1() called.");
+        let mut state = self.state.lock().unwrap();
+        state.endpoint1_component_id = None;
+        state.endpoint1_callback = None;
+
+        Self::update_connection(state);
+    }
+
+    /// endpoint2からコンポーネントを外す(ケーブルを抜く)
+    /// コンポーネントのIdとcallbackを消し、connectedをfalseにする
+    pub fn disconnect_endpoint2(&self) {
+        debug("EthernetCable::disconnect_endpoint2() called.");
+        let mut state = self.state.lock().unwrap();
+        state.endpoint2_component_id = None;
+        state.endpoint2_callback = None;
+
+        Self::update_connection(state);
+    }
+
     /// ケーブル接続時に、データきたらここに渡してねというcallbackをsetする
     /// これをケーブルに伝えておくことで、データきた時イーサーネットケーブルは指定されている
     /// PhysicalLayerCallbackを呼び出す
-    pub fn set_callback(&self, id:String,callback:PhysicalLayerCallback){
+    /// idがどちらの端にもつながっていない場合は、callbackをセットせずにエラーを返す
+    pub fn set_callback(&self, id:String,callback:PhysicalLayerCallback) -> Result<(), &'static str> {
         debug("EthernetCable::set_callback() called.");
         let mut state = self.state.lock().unwrap();
-        
-
-        if state.endpoint1_component_id.is_none() && state.endpoint2_component_id.is_none() {
-            //両方ともまだつながっていないのでセットできません
-            return;
-        }
+        let mut matched = false;
 
         if let Some(ep1_id) = &state.endpoint1_component_id{
             if *ep1_id == id{
                 debug("EthernetCable::set_callback() set endpoint1 callback.");
                 state.endpoint1_callback = Some(callback.clone());
+                matched = true;
             }
         }
         if let Some(ep2_id) = &state.endpoint2_component_id{
             if *ep2_id == id{
                 debug("EthernetCable::set_callback() set endpoint2 callback.");
                 state.endpoint2_callback = Some(callback.clone());
+                matched = true;
             }
         }
+        if !matched {
+            //どちらの端にもつながっていないのでセットできません
+            debug(&format!("EthernetCable::set_callback() {} is not connected to this cable.",id));
+            return Err("Component is not connected to either endpoint of the cable");
+        }
+        Ok(())
+    }
+
+    /// コンポーネントをケーブルの端につなぎ、データが来た時に呼ぶcallbackをsetする
+    /// component_idがまだどちらの端にもつながっていなければ、空いている端(endpoint1が優先)につなぐ
+    /// 両端とも別のコンポーネントがつながっていて空きがない場合はfalseを返す
+    pub fn attach(&self, component_id: String, callback: PhysicalLayerCallback) -> bool {
+        let endpoint1 = self.get_endpoint1_component_id();
+        let endpoint2 = self.get_endpoint2_component_id();
+        let already_connected = endpoint1.as_ref() == Some(&component_id) || endpoint2.as_ref() == Some(&component_id);
+        if !already_connected {
+            let connected = if endpoint1.is_none() {
+                self.connect_endpoint1(Some(component_id.clone()))
+            } else if endpoint2.is_none() {
+                self.connect_endpoint2(Some(component_id.clone()))
+            } else {
+                debug("EthernetCable::attach() both endpoints are already in use.");
+                return false;
+            };
+            if connected.is_err() {
+                return false;
+            }
+        }
+        self.set_callback(component_id, callback).is_ok()
+    }
+
+    /// component_idのコンポーネントをケーブルの端から外す(ケーブルを抜く)
+    /// attachの逆で、つながっている端のIdとcallbackを消す
+    /// どちらの端にもつながっていない場合はfalseを返す
+    pub fn detach(&self, component_id: &str) -> bool {
+        match self.which_endpoint(component_id) {
+            Some(Endpoint::One) => self.disconnect_endpoint1(),
+            Some(Endpoint::Two) => self.disconnect_endpoint2(),
+            None => return false,
+        }
+        true
     }
 
     /// データを送信する。上位層から呼ばれる関数。このケーブルにPacketを流したい上位層のコンポーネントから
     /// この関数を呼び出すことで、 ケーブルの先に電気信号を流す
+    /// 帯域幅が設定されている時は、前のフレームの送出とフレーム間ギャップが終わるまでフレームを待たせ、順番に送る
     pub fn transmit_signal(&self, from_id:String, frame: PhysicalLayerFrame) {
         debug("EthernetCable::transmit_signal() called.");
         debug(&format!("EthernetCable::transmit_signal() frame={:?}",frame));
 
-        let state = self.state.lock().unwrap();
-        // 両端がつながっていなかったら終了
-        if !state.connected && !state.endpoint1_callback.is_none() && !state.endpoint2_callback.is_none(){
-            debug(("both endpoint not connected."));
+        let mut state = self.state.lock().unwrap();
+        if !state.loopback && (!state.deferred_frames.is_empty() || state.bandwidth_bps.is_some()) {
+            let now_ms = (state.clock)();
+            // 先に待っているフレームがあれば、追い越さないように後ろに並ぶ
+            if now_ms < state.transmit_free_at_ms || !state.deferred_frames.is_empty() {
+                debug(&format!("EthernetCable::transmit_signal() deferred until interframe gap elapses. free_at_ms={}",state.transmit_free_at_ms));
+                state.deferred_frames.push_back((from_id, frame));
+                if state.deferred_frames.len() == 1 {
+                    Self::schedule_deferred(&self.state, state.transmit_free_at_ms - now_ms);
+                }
+                return;
+            }
+        }
+        drop(state);
+        self.send_on_wire(from_id, frame);
+    }
+
+    /// フレームをケーブルに流す
+    /// ループバック、MTU、loss_rate/ber、半二重の衝突、伝搬遅延などを適用して相手に届ける
+    fn send_on_wire(&self, from_id:String, frame: PhysicalLayerFrame) {
+        let mut state = self.state.lock().unwrap();
+        // ループバックの時は、相手がいなくても送信元にそのまま折り返す
+        if state.loopback {
+            Self::loop_back(state, from_id, frame);
+            return;
+        }
+        // 両端がつながっていないか、どちらかの端にcallbackが設定されていなかったら終了
+        if !state.connected || state.endpoint1_callback.is_none() || state.endpoint2_callback.is_none() {
+            debug("EthernetCable::transmit_signal() both endpoint not connected.");
+            return;
+        }
+        // リンクアップ前（オートネゴシエーション中）に送られたフレームは破棄する
+        if !state.link_up {
+            debug("EthernetCable::transmit_signal() link is not ready yet.");
+            Self::drop_frame(state, DropReason::LinkNotReady);
+            return;
+        }
+        // MTUより大きいペイロードは送れないので破棄する。ケーブルには流れないのでtapにも渡さない
+        if frame.ethernet_frame.data.len() > state.mtu {
+            debug(&format!("EthernetCable::transmit_signal() payload exceeds MTU. length={} mtu={}",frame.ethernet_frame.data.len(),state.mtu));
+            state.stats.frames_oversized += 1;
+            Self::drop_frame(state, DropReason::Oversized);
             return;
         }
         // 送られるデータはどちらのendpointから来たか探す
-        let ep1 = state.endpoint1_component_id.clone().unwrap();
-        let ep2 = state.endpoint2_component_id.clone().unwrap();
         debug(&format!("EthernetCable::transmit_signal() from_id={:?}",from_id));
-        debug(&format!("EthernetCable::transmit_signal() ep1={:?}",ep1));
-        debug(&format!("EthernetCable::transmit_signal() ep2={:?}",ep2));
-        
-        let other_endpoint = if from_id == ep1 {
-            debug("from ep1 --> callback to ep2");
-            state.endpoint2_callback.clone().unwrap()
-        } else if from_id == ep2 {
-            debug("from ep2 --> callback to ep1");
-            state.endpoint1_callback.clone().unwrap()
-        } else {
-            // エラーハンドリング: どちらのエンドポイントにも一致しない場合
+        let Some(from) = state.which_endpoint(&from_id) else {
+            // エラーハンドリング: どちらのエンドポイントにも一致しない(または両端が同じIdの)場合
             debug("Unexpected endpoint ID");
             return;
         };
+        debug(&format!("from {:?} --> callback to {:?}",from,from.opposite()));
+        let from_ep1 = from == Endpoint::One;
+
+        // 媒体の不安定さを再現するため、loss_rateの確率でフレームを失わせる
+        let lost = state.loss_rate > 0.0 && rand::thread_rng().gen::<f64>() < state.loss_rate;
+        let Some(other_endpoint) = state.endpoint_callback(from.opposite()) else {
+            debug("EthernetCable::transmit_signal() callback of the other endpoint is not set.");
+            return;
+        };
+        // 半二重で、前のフレームがまだ届いていない時に送ると衝突する
+        if state.half_duplex {
+            if let Some(in_flight) = state.in_flight {
+                Self::collide(state, in_flight, from, frame);
+                return;
+            }
+        }
+        // 失われなかったフレームには、berの確率でビット誤りを起こす
+        let outcome = if lost { Err(DropReason::Lost) } else { Self::apply_bit_errors(frame.clone(), state.ber) };
+        match &outcome {
+            Ok((_, corrupted)) => {
+                let direction_stats = if from_ep1 { &mut state.stats.ep1_to_ep2 } else { &mut state.stats.ep2_to_ep1 };
+                direction_stats.frames += 1;
+                direction_stats.bytes += frame.total_length() as u64;
+                if *corrupted {
+                    debug("EthernetCable::transmit_signal() bit errors occurred.");
+                    state.stats.frames_corrupted += 1;
+                }
+            }
+            Err(reason) => {
+                debug(&format!("EthernetCable::transmit_signal() frame was dropped. reason={:?}",reason));
+                state.stats.frames_dropped += 1;
+            }
+        }
+        state.record_capture(&frame);
+        let tap = state.tap_callback.clone();
+        let event_callback = state.event_callback.clone();
+        let mirror = state.mirror_callback.clone();
+        // 送出遅延と伝搬遅延を足してミリ秒に丸める。1ミリ秒に満たない場合はすぐに届ける
+        let transmit_delay_ms = state.serialization_delay_ms(frame.total_length()) + state.propagation_delay_ns() / 1_000_000.0;
+        state.last_transmit_delay_ms = transmit_delay_ms;
+        if state.bandwidth_bps.is_some() {
+            // 次のフレームは、このフレームを送り出してさらにフレーム間ギャップが過ぎるまで送れない
+            state.transmit_free_at_ms = (state.clock)() + state.serialization_delay_ms(frame.total_length()) + state.interframe_gap_ms();
+        }
+        let delay_ms = transmit_delay_ms.round() as u32;
+        // 半二重で届くまでに時間がかかる場合は、届くまでの間ケーブルを使用中にする
+        let occupies_medium = state.half_duplex && delay_ms > 0 && outcome.is_ok();
+        if occupies_medium {
+            let corrupted = matches!(outcome, Ok((_, true)));
+            state.in_flight = Some(InFlightFrame { from_ep1, bytes: frame.total_length() as u64, corrupted, collided: false });
+        }
+        drop(state);
+        // tapは送信した瞬間に、相手に届ける(伝搬遅延の後)より先に、送信されたままのフレームを受け取る
+        // 信号はケーブルに流れているので、失われたり壊れたりするフレームもtapには渡す
+        if let Some(tap) = tap {
+            tap(frame, from);
+        }
+        let frame = match outcome {
+            Ok((delivered, _)) => delivered,
+            Err(reason) => {
+                // Droppedイベントはtapの後で通知する
+                if let Some(callback) = event_callback {
+                    callback(CableEvent::Dropped(reason));
+                }
+                return;
+            }
+        };
         // 送り先のデバイスのCallBackを呼び出し信号を送る
-        other_endpoint(frame);
+        let cable_state = Arc::downgrade(&self.state);
+        let deliver = move || {
+            // 伝搬中に衝突したフレームは届けない
+            if occupies_medium && Self::finish_in_flight(&cable_state) {
+                debug("EthernetCable::transmit_signal() frame was lost by collision.");
+                return;
+            }
+            match mirror {
+                Some(mirror) => {
+                    other_endpoint(frame.clone());
+                    // 本来の送り先に届けた後で、SPANポートにコピーを送る
+                    mirror(frame);
+                }
+                None => other_endpoint(frame),
+            }
+        };
+        if delay_ms == 0 {
+            deliver();
+        } else {
+            // ブラウザを止めないように、ブロックせずにsetTimeoutで後から届ける
+            debug(&format!("EthernetCable::transmit_signal() deliver after {}ms.",delay_ms));
+            run_after_ms(delay_ms, deliver);
+        }
+    }
+
+    /// 複数のフレームを順番に送信する
+    /// 1つずつtransmit_signalで送るので、帯域幅が設定されていればフレーム間ギャップを空けて順番に届く
+    ///
+    /// ### 戻り値
+    /// * 送信した(待ち行列に入れたものも含む)フレームの数
+    pub fn transmit_burst(&self, from_id: String, frames: Vec<PhysicalLayerFrame>) -> usize {
+        debug(&format!("EthernetCable::transmit_burst() called. frames={}",frames.len()));
+        let count = frames.len();
+        for frame in frames {
+            self.transmit_signal(from_id.clone(), frame);
+        }
+        count
+    }
+
+    /// wait_ms(ミリ秒)後に、待たせているフレームを1つ送るよう予約する
+    fn schedule_deferred(cable_state: &Arc<Mutex<EthernetCableState>>, wait_ms: f64) {
+        let cable_state = Arc::downgrade(cable_state);
+        run_after_ms(wait_ms.max(0.0).ceil() as u32, move || Self::flush_deferred(&cable_state));
+    }
+
+    /// 待たせているフレームを先頭から1つ送り、まだ残っていれば次を予約する
+    fn flush_deferred(cable_state: &Weak<Mutex<EthernetCableState>>) {
+        let Some(cable_state) = cable_state.upgrade() else {
+            return;
+        };
+        let cable = EthernetCable { state: cable_state };
+        let deferred = cable.state.lock().unwrap().deferred_frames.pop_front();
+        if let Some((from_id, frame)) = deferred {
+            cable.send_on_wire(from_id, frame);
+        }
+        let state = cable.state.lock().unwrap();
+        if !state.deferred_frames.is_empty() {
+            let wait_ms = state.transmit_free_at_ms - (state.clock)();
+            Self::schedule_deferred(&cable.state, wait_ms);
+        }
+    }
+
+    /// ループバックの時の送信処理
+    /// 送信元の端のcallbackにフレームを返す。tapやキャプチャにも渡すが、loss_rate/ber/伝搬遅延や統計情報は適用しない
+    fn loop_back(mut state: MutexGuard<'_, EthernetCableState>, from_id: String, frame: PhysicalLayerFrame) {
+        let own_endpoint = state.which_endpoint(&from_id).and_then(|endpoint| Some((endpoint, state.endpoint_callback(endpoint)?)));
+        if own_endpoint.is_some() {
+            state.record_capture(&frame);
+        }
+        let tap = state.tap_callback.clone();
+        drop(state);
+        let Some((from, own_endpoint)) = own_endpoint else {
+            debug("EthernetCable::transmit_signal() loopback: callback of the sender is not set.");
+            return;
+        };
+        debug("EthernetCable::transmit_signal() loopback --> callback to sender");
+        if let Some(tap) = tap {
+            tap(frame.clone(), from);
+        }
+        own_endpoint(frame);
+    }
+
+    /// 半二重で送信が重なった時の処理
+    /// 伝搬中のフレームと後から送られたフレームの両方を捨て、衝突の回数を1増やす
+    /// 伝搬中のフレームで数えた統計情報は取り消し、破棄したフレームとして数え直す
+    /// 後から送られたフレームも信号はケーブルに流れているのでtapに渡し、両方のフレームについてDroppedイベントを通知する
+    fn collide(mut state: MutexGuard<'_, EthernetCableState>, in_flight: InFlightFrame, from: Endpoint, frame: PhysicalLayerFrame) {
+        debug("EthernetCable::transmit_signal() collision detected.");
+        if !in_flight.collided {
+            let direction_stats = if in_flight.from_ep1 { &mut state.stats.ep1_to_ep2 } else { &mut state.stats.ep2_to_ep1 };
+            // 伝搬中にreset_stats()された場合に備えて、0より小さくしない
+            direction_stats.frames = direction_stats.frames.saturating_sub(1);
+            direction_stats.bytes = direction_stats.bytes.saturating_sub(in_flight.bytes);
+            if in_flight.corrupted {
+                state.stats.frames_corrupted = state.stats.frames_corrupted.saturating_sub(1);
+            }
+            state.stats.frames_dropped += 1;
+            state.in_flight = Some(InFlightFrame { collided: true, ..in_flight });
+        }
+        state.stats.frames_dropped += 1;
+        state.stats.collisions += 1;
+        state.record_capture(&frame);
+        let tap = state.tap_callback.clone();
+        let event_callback = state.event_callback.clone();
+        drop(state);
+        if let Some(tap) = tap {
+            tap(frame, from);
+        }
+        if let Some(callback) = event_callback {
+            if !in_flight.collided {
+                callback(CableEvent::Dropped(DropReason::Collision));
+            }
+            callback(CableEvent::Dropped(DropReason::Collision));
+        }
+    }
+
+    /// 伝搬中のフレームが届く時刻になった時に呼ぶ
+    /// ケーブルを空きに戻し、そのフレームが衝突していたかどうかを返す
+    fn finish_in_flight(state: &Weak<Mutex<EthernetCableState>>) -> bool {
+        let Some(state) = state.upgrade() else {
+            return false;
+        };
+        let mut state = state.lock().unwrap();
+        state.in_flight.take().is_some_and(|in_flight| in_flight.collided)
+    }
+
+    /// berの確率で1ビットずつ反転させたフレームと、1ビットでも反転したかどうかを返す
+    /// ビット誤りを起こすのはイーサネットフレームとFCSの部分だけで、プリアンブル/SFDは壊さない
+    /// 壊れたフレームも、PhysicalLayerFrame::from_bytes_with_fcsで読み取ったFCSを付けたまま届くので、受け取る側でfcs_validがfalseになる
+    /// イーサタイプが0x88A8に化けて内側のタグが読めないなど、フレームとして読み取れなくなった場合だけDropReason::Corruptedを返す
+    fn apply_bit_errors(frame: PhysicalLayerFrame, ber: f64) -> Result<(PhysicalLayerFrame, bool), DropReason> {
+        if ber <= 0.0 {
+            return Ok((frame, false));
+        }
+        let mut rng = rand::thread_rng();
+        let mut bytes = frame.to_bytes();
+        let mut corrupted = false;
+        for byte in bytes[8..].iter_mut() {
+            for bit in 0..8 {
+                if rng.gen::<f64>() < ber {
+                    *byte ^= 1 << bit;
+                    corrupted = true;
+                }
+            }
+        }
+        if !corrupted {
+            return Ok((frame, false));
+        }
+        PhysicalLayerFrame::from_bytes_with_fcs(&bytes)
+            .map(|corrupted_frame| (corrupted_frame, true))
+            .map_err(|_| DropReason::Corrupted)
+    }
+
+    /// MTU(送れるペイロードの最大バイト数)を設定する。初期値は1500
+    /// ペイロードがMTUより大きいフレームは破棄される。IPのフラグメンテーションが必要な理由を学ぶのに使う
+    pub fn set_mtu(&self, mtu: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.mtu = mtu;
+    }
+
+    /// 半二重モードを設定する
+    /// trueの間は、送ったフレームが伝搬遅延の後に届くまでケーブルを使用中とし、その間に別のフレームが送られると
+    /// 両方のフレームを捨てて衝突として数える(CSMA/CDの衝突検出)。伝搬遅延が1ミリ秒に満たない場合は衝突しない
+    pub fn set_half_duplex(&self, on: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.half_duplex = on;
+    }
+
+    /// ループバックモードを設定する
+    /// trueの間は、送ったフレームが相手ではなく送信元の端のcallbackに折り返す
+    /// 片方の端だけにつないだホストが、自分の送信/受信の処理を確認するのに使う
+    pub fn set_loopback(&self, on: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.loopback = on;
+    }
+
+    /// ビット誤り率を設定する
+    /// 1ビットごとにこの確率で反転する。0.0なら誤りは起きない。範囲外の値は0.0〜1.0に丸める
+    /// FCSを確認すると、壊れたフレームを見つけられることを学ぶのに使う
+    pub fn set_ber(&self, rate: f64) {
+        let mut state = self.state.lock().unwrap();
+        state.ber = if rate.is_nan() { 0.0 } else { rate.clamp(0.0, 1.0) };
+    }
+
+    /// フレームが失われる確率を設定する
+    /// 0.0なら失われず、1.0なら全て失われる。範囲外の値は0.0〜1.0に丸める
+    pub fn set_loss_rate(&self, rate: f64) {
+        let mut state = self.state.lock().unwrap();
+        state.loss_rate = if rate.is_nan() { 0.0 } else { rate.clamp(0.0, 1.0) };
+    }
+
+    /// ケーブルの長さ(メートル)を設定する
+    /// 長さに比例した伝搬遅延の後にフレームが届くようになる。負の値は0として扱う
+    pub fn set_length(&self, meters: f64) {
+        let mut state = self.state.lock().unwrap();
+        state.length_meters = if meters.is_nan() { 0.0 } else { meters.max(0.0) };
+    }
+
+    /// 帯域幅(ビット/秒)を設定する。0なら帯域幅の制限をなくす
+    /// フレームのビット数を帯域幅で割った送出遅延が伝搬遅延に加わり、その後にフレームが届くようになる
+    /// 大きいフレームほど届くのが遅くなることを学ぶのに使う
+    pub fn set_bandwidth(&self, bps: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.bandwidth_bps = if bps == 0 { None } else { Some(bps) };
+    }
+
+    /// 最後に送ったフレームが届くまでにかかった時間(送出遅延+伝搬遅延、ミリ秒)を取得
+    pub fn last_transmit_delay_ms(&self) -> f64 {
+        let state = self.state.lock().unwrap();
+        state.last_transmit_delay_ms
+    }
+
+    /// フレーム間ギャップが終わるのを待っているフレームの数を取得
+    pub fn get_queue_depth(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.deferred_frames.len()
+    }
+
+    /// 伝搬遅延(ナノ秒)を取得
+    pub fn propagation_delay_ns(&self) -> f64 {
+        let state = self.state.lock().unwrap();
+        state.propagation_delay_ns()
+    }
+
+    /// 向きごとに届けたフレーム数/バイト数と、破棄したフレーム数を取得
+    pub fn get_stats(&self) -> CableStats {
+        let state = self.state.lock().unwrap();
+        state.stats
+    }
+
+    /// 統計情報を0に戻す
+    pub fn reset_stats(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.stats = CableStats::default();
+    }
+
+    /// SPAN(ミラー)ポートを設定する
+    /// 両端の間で届けられたフレームは、どちらの向きのものも全てコピーが指定したコンポーネントのcallbackに送られる
+    /// 本来の送り先に届けた後にコピーを送るので、本来の通信の順番や内容には影響しない
+    pub fn set_mirror_to(&self, component_id: String, callback: PhysicalLayerCallback) {
+        debug(&format!("EthernetCable::set_mirror_to({}) called.",component_id));
+        let mut state = self.state.lock().unwrap();
+        state.mirror_component_id = Some(component_id);
+        state.mirror_callback = Some(callback);
+    }
+
+    /// SPAN(ミラー)ポートを外す
+    pub fn clear_mirror(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.mirror_component_id = None;
+        state.mirror_callback = None;
+    }
+
+    /// キャプチャを始める
+    /// これ以降ケーブルに流れたフレーム(tapに渡されるものと同じ)のコピーを、最新のmax_frames個まで記録する
+    /// すでにキャプチャ中の場合は、それまでに記録したフレームを捨てて記録し直す
+    pub fn start_capture(&self, max_frames: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.capture = Some(VecDeque::with_capacity(max_frames));
+        state.capture_max_frames = max_frames;
+    }
+
+    /// キャプチャで記録したフレームを古い順に取り出す
+    /// 取り出したフレームは記録から消えるが、キャプチャは続く。キャプチャしていない場合は空のVecを返す
+    pub fn take_capture(&self) -> Vec<PhysicalLayerFrame> {
+        let mut state = self.state.lock().unwrap();
+        state.capture.as_mut().map(|capture| capture.drain(..).collect()).unwrap_or_default()
+    }
+
+    /// タップ(盗聴用の分岐)を設定する
+    /// ケーブルの端点にならずに、両方向に流れる全てのフレームのコピーをcallbackで受け取る
+    ///
+    /// 呼ばれる順番
+    /// - transmit_signal()の中で、相手に届けるより先に呼ばれる(伝搬遅延がある場合も送信した瞬間に呼ばれる)
+    /// - loss_rateで失われるフレームも、Droppedイベントより先に呼ばれる
+    /// - 両端がつながっていない、リンクアップしていないなどで送信できなかったフレームは、ケーブルに流れていないので呼ばれない
+    ///
+    /// callbackにはフレームと、そのフレームを送った端が渡されるので、どちら向きに流れたフレームかがわかる
+    pub fn set_tap(&self, callback: TapCallback) {
+        let mut state = self.state.lock().unwrap();
+        state.tap_callback = Some(callback);
+    }
+
+    /// 条件に合うフレームだけを受け取るタップを設定する
+    /// 条件はRustの側で調べるので、混雑したケーブルでも条件に合わないフレームのためにcallbackが呼ばれることはない
+    /// 呼ばれる順番はset_tapと同じ
+    pub fn set_filtered_tap(&self, filter: TapFilter, callback: TapCallback) {
+        let filtered: TapCallback = Arc::new(move |frame: PhysicalLayerFrame, from: Endpoint| {
+            if filter.matches(&frame.ethernet_frame) {
+                callback(frame, from);
+            }
+        });
+        self.set_tap(filtered);
+    }
+
+    /// タップを外す
+    pub fn clear_tap(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.tap_callback = None;
+    }
+
+    /// SPAN(ミラー)ポートに繋がっているコンポーネントのIdを取得
+    pub fn get_mirror_component_id(&self) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        state.mirror_component_id.clone()
+    }
+
+    /// 指定した時刻(ミリ秒)にフレームを送信するよう予約する
+    /// 予約したフレームはすぐには送られず、tick()で時刻が進んだ時に予約時刻の順番で送信される
+    /// 同じ時刻に予約されたフレームは予約した順番で送信される
+    /// at_msがNaNや無限大の場合は、時刻順に並べられず後ろのフレームがいつまでも送信されなくなるので、予約せずにエラーを返す
+    pub fn schedule_transmit(&self, from_id:String, frame: PhysicalLayerFrame, at_ms: f64) -> Result<(), &'static str> {
+        debug(&format!("EthernetCable::schedule_transmit() called. at_ms={}",at_ms));
+        if !at_ms.is_finite() {
+            return Err("Scheduled time must be a finite number");
+        }
+        let mut state = self.state.lock().unwrap();
+        // 同じ時刻のものより後ろに入れることで予約順を保つ
+        let index = state.scheduled_frames.partition_point(|scheduled| scheduled.at_ms <= at_ms);
+        state.scheduled_frames.insert(index, ScheduledFrame { at_ms, from_id, frame });
+        Ok(())
+    }
+
+    /// シミュレーションの時刻を進め、予約時刻になったフレームを送信する
+    /// ホスト(JS側)が時刻を進めるたびに呼び出す
+    pub fn tick(&self, now_ms: f64) {
+        let mut state = self.state.lock().unwrap();
+        state.now_ms = now_ms;
+        let link_came_up = state.try_link_up();
+        let due_count = state.scheduled_frames.partition_point(|scheduled| scheduled.at_ms <= now_ms);
+        let due_frames: Vec<ScheduledFrame> = state.scheduled_frames.drain(..due_count).collect();
+        if link_came_up {
+            debug(&format!("EthernetCable({})::link up.",state.id));
+            Self::emit_event(state, CableEvent::Connected);
+        } else {
+            drop(state);
+        }
+        // transmit_signal()の中でロックを取るので、ロックを外してから送信する
+        for scheduled in due_frames {
+            debug(&format!("EthernetCable::tick() transmit scheduled frame. at_ms={}",scheduled.at_ms));
+            self.transmit_signal(scheduled.from_id, scheduled.frame);
+        }
+    }
+
+    /// 両端がつながってからリンクアップするまでの時間(ミリ秒)を設定する
+    /// 実際のリンクはオートネゴシエーションのため、つないですぐには通信できない
+    /// 次に両端がつながった時から適用され、その時刻からtick()で時間が経過するまで送信されたフレームは破棄される
+    pub fn set_link_up_delay_ms(&self, ms: u32) {
+        let mut state = self.state.lock().unwrap();
+        state.link_up_delay_ms = ms;
+    }
 
+    /// リンクアップして実際に通信できる状態かどうか
+    pub fn is_link_up(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.link_up
+    }
+
+    /// リンクアップやフレームの破棄などのイベントを受け取るcallbackをsetする
+    pub fn set_event_callback(&self, callback: CableEventCallback) {
+        let mut state = self.state.lock().unwrap();
+        state.event_callback = Some(callback);
+    }
+
+    /// connectedが変わった時に呼ばれるcallbackをsetする
+    /// リンクのランプを点けたり消したりするのに使う
+    pub fn set_connection_callback(&self, callback: ConnectionCallback) {
+        let mut state = self.state.lock().unwrap();
+        state.on_connection_change = Some(callback);
+    }
+
+    /// 両端にコンポーネントがつながっているかどうかに合わせてconnectedを更新する
+    /// connectedが変わった時はon_connection_changeを、すぐにリンクアップした時はCableEvent::Connectedを
+    /// この順番で、ロックを外してから呼び出す
+    fn update_connection(mut state: MutexGuard<'_, EthernetCableState>) {
+        let both_connected = state.endpoint1_component_id.is_some() && state.endpoint2_component_id.is_some();
+        if both_connected == state.connected {
+            return;
+        }
+        let link_came_up = if both_connected {
+            debug(&format!("EthernetCable({})::bothe connected.",state.id));
+            state.mark_connected()
+        } else {
+            debug(&format!("EthernetCable({})::disconnected.",state.id));
+            state.mark_disconnected();
+            false
+        };
+        let on_connection_change = state.on_connection_change.clone();
+        let event_callback = if link_came_up { state.event_callback.clone() } else { None };
+        drop(state);
+        if let Some(callback) = on_connection_change {
+            callback(both_connected);
+        }
+        if let Some(callback) = event_callback {
+            callback(CableEvent::Connected);
+        }
+    }
+
+    /// イベントを通知する
+    /// callbackの中からケーブルを操作できるように、ロックを外してからcallbackを呼び出す
+    fn emit_event(state: MutexGuard<'_, EthernetCableState>, event: CableEvent) {
+        let callback = state.event_callback.clone();
+        drop(state);
+        if let Some(callback) = callback {
+            callback(event);
+        }
+    }
+
+    /// フレームを破棄したことを統計情報に数え、イベントで通知する
+    fn drop_frame(mut state: MutexGuard<'_, EthernetCableState>, reason: DropReason) {
+        state.stats.frames_dropped += 1;
+        Self::emit_event(state, CableEvent::Dropped(reason));
+    }
+
+    /// 送信待ちになっているフレームの数を取得
+    pub fn get_scheduled_count(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.scheduled_frames.len()
     }
 }
 
@@ -252,4 +1147,534 @@ impl EthernetCable {
 pub fn debug(s: &str) {
     let message= format!("\r\n------------\r\n[Debug] {}\r\n------------\r\n",s);
     showTerminal(&message);
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Received = Arc<Mutex<Vec<PhysicalLayerFrame>>>;
+
+    /// ペイロードだけを指定した物理層フレームを作る
+    fn frame(payload: &[u8]) -> PhysicalLayerFrame {
+        PhysicalLayerFrame::new(Some(EthernetFrame::new(None, None, None, Some(payload.to_vec()))))
+    }
+
+    /// 届いたフレームをreceivedに記録するcallback
+    fn recorder(received: &Received) -> PhysicalLayerCallback {
+        let received = received.clone();
+        Arc::new(move |frame| received.lock().unwrap().push(frame))
+    }
+
+    /// 届いたフレームのペイロードを届いた順に取り出す
+    fn payloads(received: &Received) -> Vec<Vec<u8>> {
+        received.lock().unwrap().iter().map(|frame| frame.ethernet_frame.data.clone()).collect()
+    }
+
+    /// "pc-1"と"pc-2"を両端につないだケーブルと、それぞれの端に届いたフレームの記録
+    fn connected_cable() -> (EthernetCable, Received, Received) {
+        let cable = EthernetCable::new(Some("cable-1".to_string()));
+        cable.connect(Some("pc-1".to_string()), Some("pc-2".to_string())).unwrap();
+        let received1 = Received::default();
+        let received2 = Received::default();
+        cable.set_callback("pc-1".to_string(), recorder(&received1)).unwrap();
+        cable.set_callback("pc-2".to_string(), recorder(&received2)).unwrap();
+        (cable, received1, received2)
+    }
+
+    #[test]
+    fn scheduled_frames_are_sent_in_time_order_as_ticks_advance() {
+        let (cable, _, received2) = connected_cable();
+        cable.schedule_transmit("pc-1".to_string(), frame(&[2]), 200.0).unwrap();
+        cable.schedule_transmit("pc-1".to_string(), frame(&[1]), 100.0).unwrap();
+
+        cable.tick(50.0);
+        assert!(payloads(&received2).is_empty());
+        assert_eq!(cable.get_scheduled_count(), 2);
+
+        cable.tick(100.0);
+        assert_eq!(payloads(&received2), vec![vec![1]]);
+
+        cable.tick(250.0);
+        assert_eq!(payloads(&received2), vec![vec![1], vec![2]]);
+        assert_eq!(cable.get_scheduled_count(), 0);
+    }
+
+    #[test]
+    fn frames_are_dropped_until_the_link_comes_up() {
+        let cable = EthernetCable::new(Some("cable-1".to_string()));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let record = events.clone();
+        cable.set_event_callback(Arc::new(move |event| record.lock().unwrap().push(event)));
+        cable.set_link_up_delay_ms(100);
+        cable.connect(Some("pc-1".to_string()), Some("pc-2".to_string())).unwrap();
+        let received2 = Received::default();
+        cable.set_callback("pc-1".to_string(), recorder(&Received::default())).unwrap();
+        cable.set_callback("pc-2".to_string(), recorder(&received2)).unwrap();
+
+        // リンクアップを待っている間に送ったフレームは破棄される
+        cable.tick(50.0);
+        cable.transmit_signal("pc-1".to_string(), frame(&[1]));
+        assert!(!cable.is_link_up());
+        assert!(payloads(&received2).is_empty());
+        assert_eq!(*events.lock().unwrap(), vec![CableEvent::Dropped(DropReason::LinkNotReady)]);
+
+        // 待ち時間が過ぎるとリンクアップし、送ったフレームが届く
+        cable.tick(100.0);
+        cable.transmit_signal("pc-1".to_string(), frame(&[2]));
+        assert!(cable.is_link_up());
+        assert_eq!(payloads(&received2), vec![vec![2]]);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![CableEvent::Dropped(DropReason::LinkNotReady), CableEvent::Connected],
+        );
+    }
+
+    #[test]
+    fn mirror_sees_every_frame_in_both_directions() {
+        let (cable, received1, received2) = connected_cable();
+        let mirrored = Received::default();
+        cable.set_mirror_to("sniffer".to_string(), recorder(&mirrored));
+
+        cable.transmit_signal("pc-1".to_string(), frame(&[1]));
+        cable.transmit_signal("pc-2".to_string(), frame(&[2]));
+        cable.transmit_signal("pc-1".to_string(), frame(&[3]));
+
+        assert_eq!(payloads(&mirrored), vec![vec![1], vec![2], vec![3]]);
+        // 本来の送り先への配送は変わらない
+        assert_eq!(payloads(&received2), vec![vec![1], vec![3]]);
+        assert_eq!(payloads(&received1), vec![vec![2]]);
+    }
+
+    #[test]
+    fn schedule_transmit_rejects_non_finite_times() {
+        let (cable, _, _) = connected_cable();
+        assert!(cable.schedule_transmit("pc-1".to_string(), frame(&[1]), f64::NAN).is_err());
+        assert!(cable.schedule_transmit("pc-1".to_string(), frame(&[1]), f64::INFINITY).is_err());
+        assert_eq!(cable.get_scheduled_count(), 0);
+    }
+
+    #[test]
+    fn stats_are_counted_separately_for_each_direction() {
+        let (cable, _, _) = connected_cable();
+        for _ in 0..3 {
+            cable.transmit_signal("pc-1".to_string(), frame(&[0; 100]));
+        }
+        cable.transmit_signal("pc-2".to_string(), frame(&[0; 46]));
+
+        let stats = cable.get_stats();
+        let frame_length = |payload_length: usize| frame(&vec![0; payload_length]).total_length() as u64;
+        assert_eq!(stats.ep1_to_ep2, DirectionStats { frames: 3, bytes: 3 * frame_length(100) });
+        assert_eq!(stats.ep2_to_ep1, DirectionStats { frames: 1, bytes: frame_length(46) });
+    }
+
+    #[test]
+    fn tap_sees_frames_in_both_directions() {
+        let (cable, _, _) = connected_cable();
+        let tapped = Arc::new(Mutex::new(Vec::new()));
+        let record = tapped.clone();
+        cable.set_tap(Arc::new(move |frame: PhysicalLayerFrame, from: Endpoint| {
+            record.lock().unwrap().push((frame.ethernet_frame.data, from));
+        }));
+        cable.transmit_signal("pc-1".to_string(), frame(&[1]));
+        cable.transmit_signal("pc-2".to_string(), frame(&[2]));
+
+        assert_eq!(*tapped.lock().unwrap(), vec![(vec![1], Endpoint::One), (vec![2], Endpoint::Two)]);
+    }
+
+    #[test]
+    fn transmitting_on_a_half_connected_cable_does_not_panic() {
+        let cable = EthernetCable::new(Some("cable-1".to_string()));
+        cable.connect_endpoint1(Some("pc-1".to_string())).unwrap();
+        let received1 = Received::default();
+        cable.set_callback("pc-1".to_string(), recorder(&received1)).unwrap();
+
+        cable.transmit_signal("pc-1".to_string(), frame(&[1]));
+        cable.transmit_signal("pc-2".to_string(), frame(&[2]));
+        assert!(payloads(&received1).is_empty());
+        assert_eq!(cable.get_stats(), CableStats::default());
+    }
+
+    #[test]
+    fn about_half_of_the_frames_are_lost_at_a_loss_rate_of_one_half() {
+        let (cable, _, received2) = connected_cable();
+        cable.set_loss_rate(0.5);
+        for _ in 0..1000 {
+            cable.transmit_signal("pc-1".to_string(), frame(&[0]));
+        }
+        // 二項分布(n=1000, p=0.5)の標準偏差は約16なので、±100に収まらないことはまずない
+        let delivered = payloads(&received2).len();
+        assert!((400..=600).contains(&delivered), "delivered={}", delivered);
+        assert_eq!(cable.get_stats().frames_dropped as usize, 1000 - delivered);
+    }
+
+    #[test]
+    fn loss_rate_is_clamped_to_zero_and_one() {
+        let (cable, _, received2) = connected_cable();
+        cable.set_loss_rate(-1.0);
+        cable.transmit_signal("pc-1".to_string(), frame(&[1]));
+        cable.set_loss_rate(2.0);
+        cable.transmit_signal("pc-1".to_string(), frame(&[2]));
+        assert_eq!(payloads(&received2), vec![vec![1]]);
+    }
+
+    #[test]
+    fn frames_arrive_after_the_propagation_delay() {
+        let (cable, _, received2) = connected_cable();
+        // 1メートルあたり5ナノ秒なので、20kmで0.1ミリ秒、2000kmで10ミリ秒
+        cable.set_length(20_000.0);
+        assert_eq!(cable.propagation_delay_ns(), 100_000.0);
+        cable.set_length(2_000_000.0);
+        assert_eq!(cable.propagation_delay_ns(), 10_000_000.0);
+        assert!(cable.to_string().contains("#propagation_delay_ns   : 10000000"));
+
+        cable.transmit_signal("pc-1".to_string(), frame(&[1]));
+        assert!(payloads(&received2).is_empty());
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        assert_eq!(payloads(&received2), vec![vec![1]]);
+    }
+
+    #[test]
+    fn stats_count_delivered_and_dropped_frames_until_reset() {
+        let (cable, _, _) = connected_cable();
+        for _ in 0..2 {
+            cable.transmit_signal("pc-1".to_string(), frame(&[0; 46]));
+            cable.transmit_signal("pc-2".to_string(), frame(&[0; 46]));
+        }
+        cable.transmit_signal("pc-2".to_string(), frame(&[0; 46]));
+        cable.set_loss_rate(1.0);
+        cable.transmit_signal("pc-1".to_string(), frame(&[0; 46]));
+
+        let stats = cable.get_stats();
+        assert_eq!(stats.ep1_to_ep2.frames, 2);
+        assert_eq!(stats.ep2_to_ep1.frames, 3);
+        assert_eq!(stats.frames_dropped, 1);
+
+        cable.reset_stats();
+        assert_eq!(cable.get_stats(), CableStats::default());
+    }
+
+    #[test]
+    fn connection_callback_fires_once_when_both_endpoints_connect() {
+        let cable = EthernetCable::new(Some("cable-1".to_string()));
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let recorded = changes.clone();
+        cable.set_connection_callback(Arc::new(move |connected| recorded.lock().unwrap().push(connected)));
+
+        cable.connect_endpoint1(Some("pc-1".to_string())).unwrap();
+        assert!(changes.lock().unwrap().is_empty());
+        cable.connect_endpoint2(Some("pc-2".to_string())).unwrap();
+        assert_eq!(*changes.lock().unwrap(), vec![true]);
+
+        // 同じ状態のままつなぎ直しても呼ばれない
+        cable.connect(Some("pc-1".to_string()), Some("pc-2".to_string())).unwrap();
+        assert_eq!(*changes.lock().unwrap(), vec![true]);
+
+        cable.disconnect_endpoint2();
+        assert_eq!(*changes.lock().unwrap(), vec![true, false]);
+    }
+
+    #[test]
+    fn unplugging_one_end_disconnects_and_refuses_transmits() {
+        let (cable, received1, received2) = connected_cable();
+        cable.transmit_signal("pc-1".to_string(), frame(&[1]));
+        assert_eq!(payloads(&received2), vec![vec![1]]);
+
+        cable.disconnect_endpoint2();
+        let info = cable.get_connection_info();
+        assert!(!info.connected);
+        assert_eq!(info.endpoint2, None);
+        cable.transmit_signal("pc-1".to_string(), frame(&[2]));
+        assert_eq!(payloads(&received2), vec![vec![1]]);
+
+        // つなぎ直してもcallbackは消えているので、setし直すまで届かない
+        cable.connect_endpoint2(Some("pc-2".to_string())).unwrap();
+        assert!(cable.get_connection_info().connected);
+        cable.transmit_signal("pc-1".to_string(), frame(&[3]));
+        assert_eq!(payloads(&received2), vec![vec![1]]);
+        assert!(payloads(&received1).is_empty());
+    }
+
+    #[test]
+    fn connecting_none_recomputes_connected() {
+        let (cable, _, _) = connected_cable();
+        cable.connect_endpoint1(None).unwrap();
+        assert!(!cable.get_connection_info().connected);
+        cable.connect_endpoint1(Some("pc-1".to_string())).unwrap();
+        assert!(cable.get_connection_info().connected);
+    }
+
+    #[test]
+    fn tap_sees_frames_that_are_lost_on_the_way() {
+        let (cable, _, received2) = connected_cable();
+        let tapped = Arc::new(Mutex::new(0));
+        let count = tapped.clone();
+        cable.set_tap(Arc::new(move |_, _| *count.lock().unwrap() += 1));
+        cable.set_loss_rate(1.0);
+        cable.transmit_signal("pc-1".to_string(), frame(&[1]));
+
+        assert_eq!(*tapped.lock().unwrap(), 1);
+        assert!(payloads(&received2).is_empty());
+    }
+
+    #[test]
+    fn most_frames_fail_the_fcs_check_at_a_high_bit_error_rate() {
+        let (cable, _, received2) = connected_cable();
+        cable.set_ber(0.01);
+        for _ in 0..100 {
+            cable.transmit_signal("pc-1".to_string(), frame(&[0x5A; 46]));
+        }
+
+        // プリアンブル/SFDは壊さないので、壊れたフレームも破棄されずに全て届く
+        assert_eq!(cable.get_stats().frames_dropped, 0);
+        let received = received2.lock().unwrap();
+        assert_eq!(received.len(), 100);
+        let valid = received.iter().filter(|frame| EthernetFrame::verify_fcs(&frame.to_bytes()[8..])).count();
+        assert_eq!(valid, received.iter().filter(|frame| frame.fcs_valid()).count());
+        assert!(valid * 10 < received.len(), "{} of {} frames passed the FCS check", valid, received.len());
+    }
+
+    #[test]
+    fn loopback_returns_frames_to_the_sender_only() {
+        let (cable, received1, received2) = connected_cable();
+        cable.set_loopback(true);
+        cable.transmit_signal("pc-1".to_string(), frame(&[1]));
+
+        assert_eq!(payloads(&received1), vec![vec![1]]);
+        assert!(payloads(&received2).is_empty());
+    }
+
+    #[test]
+    fn overlapping_half_duplex_transmits_collide_and_both_are_dropped() {
+        let (cable, received1, received2) = connected_cable();
+        cable.set_half_duplex(true);
+        // 10ミリ秒かかるので、届く前にもう片方の端から送ると衝突する
+        cable.set_length(2_000_000.0);
+        let tapped = Arc::new(Mutex::new(0));
+        let count = tapped.clone();
+        cable.set_tap(Arc::new(move |_, _| *count.lock().unwrap() += 1));
+
+        cable.transmit_signal("pc-1".to_string(), frame(&[1]));
+        cable.transmit_signal("pc-2".to_string(), frame(&[2]));
+        std::thread::sleep(std::time::Duration::from_millis(200));
+
+        assert!(payloads(&received1).is_empty());
+        assert!(payloads(&received2).is_empty());
+        let stats = cable.get_stats();
+        assert_eq!(stats.collisions, 1);
+        assert_eq!(stats.frames_dropped, 2);
+        assert_eq!(stats.ep1_to_ep2.frames, 0);
+        assert_eq!(*tapped.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn payloads_larger_than_the_mtu_are_dropped() {
+        let (cable, _, received2) = connected_cable();
+        cable.transmit_signal("pc-1".to_string(), frame(&[0; 1501]));
+        assert!(payloads(&received2).is_empty());
+        assert_eq!(cable.get_stats().frames_oversized, 1);
+
+        cable.transmit_signal("pc-1".to_string(), frame(&[0; 1500]));
+        assert_eq!(payloads(&received2).len(), 1);
+
+        cable.set_mtu(576);
+        cable.transmit_signal("pc-1".to_string(), frame(&[0; 1500]));
+        assert_eq!(payloads(&received2).len(), 1);
+        assert_eq!(cable.get_stats().frames_oversized, 2);
+    }
+
+    #[test]
+    fn capture_keeps_only_the_newest_frames_past_the_cap() {
+        let (cable, _, _) = connected_cable();
+        // キャプチャを始める前のフレームは記録されない
+        cable.transmit_signal("pc-1".to_string(), frame(&[0]));
+        cable.start_capture(3);
+        for index in 1..=5 {
+            cable.transmit_signal("pc-1".to_string(), frame(&[index]));
+        }
+        let captured: Vec<Vec<u8>> = cable.take_capture().iter().map(|frame| frame.ethernet_frame.data.clone()).collect();
+        assert_eq!(captured, vec![vec![3], vec![4], vec![5]]);
+
+        // 取り出した後もキャプチャは続く
+        assert!(cable.take_capture().is_empty());
+        cable.transmit_signal("pc-2".to_string(), frame(&[6]));
+        assert_eq!(cable.take_capture().len(), 1);
+    }
+
+    #[test]
+    fn take_capture_is_empty_when_not_capturing() {
+        let (cable, _, _) = connected_cable();
+        cable.transmit_signal("pc-1".to_string(), frame(&[1]));
+        assert!(cable.take_capture().is_empty());
+    }
+
+    #[test]
+    fn connection_info_has_the_endpoint_ids_and_connected_flag() {
+        let (cable, _, _) = connected_cable();
+        assert_eq!(
+            serde_json::to_value(cable.get_connection_info()).unwrap(),
+            serde_json::json!({ "endpoint1": "pc-1", "endpoint2": "pc-2", "connected": true })
+        );
+
+        let half = EthernetCable::new(None);
+        half.connect_endpoint2(Some("pc-3".to_string())).unwrap();
+        assert_eq!(
+            serde_json::to_value(half.get_connection_info()).unwrap(),
+            serde_json::json!({ "endpoint1": null, "endpoint2": "pc-3", "connected": false })
+        );
+    }
+
+    #[test]
+    fn serialization_delay_of_a_1500_byte_frame_at_10_mbps_is_1_2_ms() {
+        let (cable, _, received2) = connected_cable();
+        cable.set_bandwidth(10_000_000);
+        assert!((cable.state.lock().unwrap().serialization_delay_ms(1500) - 1.2).abs() < 1e-9);
+
+        // プリアンブル + SFD(8) + ヘッダ(14) + FCS(4) + ペイロード(1474) = 1500バイト
+        let full = frame(&[0; 1474]);
+        assert_eq!(full.total_length(), 1500);
+        cable.transmit_signal("pc-1".to_string(), full);
+        assert!((cable.last_transmit_delay_ms() - 1.2).abs() < 1e-9);
+        assert!(payloads(&received2).is_empty());
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        assert_eq!(payloads(&received2).len(), 1);
+    }
+
+    #[test]
+    fn zero_bandwidth_is_instantaneous() {
+        let (cable, _, received2) = connected_cable();
+        cable.set_bandwidth(10_000_000);
+        cable.set_bandwidth(0);
+        cable.transmit_signal("pc-1".to_string(), frame(&[0; 1474]));
+        assert_eq!(cable.last_transmit_delay_ms(), 0.0);
+        assert_eq!(payloads(&received2).len(), 1);
+    }
+
+    #[test]
+    fn back_to_back_transmits_wait_for_the_interframe_gap() {
+        let (cable, _, received2) = connected_cable();
+        cable.set_bandwidth(10_000_000);
+        let sent_at = Arc::new(Mutex::new(Vec::new()));
+        let record = sent_at.clone();
+        cable.set_tap(Arc::new(move |_, _| record.lock().unwrap().push(std::time::Instant::now())));
+
+        cable.transmit_signal("pc-1".to_string(), frame(&[1; 1474]));
+        cable.transmit_signal("pc-1".to_string(), frame(&[2; 1474]));
+        // 2つ目は1つ目の送出とフレーム間ギャップが終わるまで待たされる
+        assert_eq!(cable.get_queue_depth(), 1);
+        assert_eq!(sent_at.lock().unwrap().len(), 1);
+
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        assert_eq!(cable.get_queue_depth(), 0);
+        let sent_at = sent_at.lock().unwrap();
+        assert_eq!(sent_at.len(), 2);
+        let state = cable.state.lock().unwrap();
+        let min_gap_ms = state.serialization_delay_ms(1500) + state.interframe_gap_ms();
+        assert!((sent_at[1] - sent_at[0]).as_secs_f64() * 1000.0 >= min_gap_ms);
+        let arrived: Vec<u8> = payloads(&received2).iter().map(|payload| payload[0]).collect();
+        assert_eq!(arrived, vec![1, 2]);
+    }
+
+    #[test]
+    fn burst_frames_arrive_in_order() {
+        let (cable, received1, received2) = connected_cable();
+        let sent = cable.transmit_burst("pc-1".to_string(), vec![frame(&[1]), frame(&[2]), frame(&[3])]);
+        assert_eq!(sent, 3);
+        assert_eq!(payloads(&received2), vec![vec![1], vec![2], vec![3]]);
+        assert!(payloads(&received1).is_empty());
+    }
+
+    #[test]
+    fn burst_frames_keep_their_order_when_throttled() {
+        let (cable, _, received2) = connected_cable();
+        cable.set_bandwidth(10_000_000);
+        let frames = (1..=3).map(|index| frame(&[index; 1474])).collect();
+        assert_eq!(cable.transmit_burst("pc-1".to_string(), frames), 3);
+        assert_eq!(cable.get_queue_depth(), 2);
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        let arrived: Vec<u8> = payloads(&received2).iter().map(|payload| payload[0]).collect();
+        assert_eq!(arrived, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn filtered_tap_sees_only_matching_frames() {
+        let (cable, _, received2) = connected_cable();
+        let tapped = Arc::new(Mutex::new(Vec::new()));
+        let record = tapped.clone();
+        let filter = TapFilter { ethertype: Some(0x0806), dst_mac: None };
+        cable.set_filtered_tap(filter, Arc::new(move |frame: PhysicalLayerFrame, _| {
+            record.lock().unwrap().push(frame.ethernet_frame.ethertype);
+        }));
+        for ethertype in [0x0800, 0x0806, 0x86DD, 0x0806] {
+            let frame = EthernetFrame::new(None, None, Some(ethertype), Some(vec![0; 46]));
+            cable.transmit_signal("pc-1".to_string(), PhysicalLayerFrame::new(Some(frame)));
+        }
+        assert_eq!(*tapped.lock().unwrap(), vec![0x0806, 0x0806]);
+        // 条件に合わないフレームも相手には届く
+        assert_eq!(received2.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn tap_filter_matches_every_given_condition() {
+        let target = MacAddress::from_array([0x02, 0x00, 0x00, 0x00, 0x00, 0x09]);
+        let frame = EthernetFrame::new(Some(target), None, Some(0x0800), None);
+        assert!(TapFilter::default().matches(&frame));
+        assert!(TapFilter { ethertype: Some(0x0800), dst_mac: Some(target) }.matches(&frame));
+        assert!(!TapFilter { ethertype: Some(0x0800), dst_mac: Some(MacAddress::get_broadcast_mac_addr()) }.matches(&frame));
+        assert!(!TapFilter { ethertype: Some(0x0806), dst_mac: Some(target) }.matches(&frame));
+    }
+
+    #[test]
+    fn set_callback_fails_for_an_unconnected_id() {
+        let cable = EthernetCable::new(None);
+        assert_eq!(
+            cable.set_callback("pc-1".to_string(), Arc::new(|_| {})),
+            Err("Component is not connected to either endpoint of the cable")
+        );
+        cable.connect_endpoint1(Some("pc-1".to_string())).unwrap();
+        assert!(cable.set_callback("pc-2".to_string(), Arc::new(|_| {})).is_err());
+        assert_eq!(cable.set_callback("pc-1".to_string(), Arc::new(|_| {})), Ok(()));
+    }
+
+    #[test]
+    fn which_endpoint_matches_each_end_and_rejects_others() {
+        let (cable, _, _) = connected_cable();
+        assert_eq!(cable.which_endpoint("pc-1"), Some(Endpoint::One));
+        assert_eq!(cable.which_endpoint("pc-2"), Some(Endpoint::Two));
+        assert_eq!(cable.which_endpoint("pc-3"), None);
+        assert_eq!(cable.which_endpoint(""), None);
+        assert_eq!(EthernetCable::new(None).which_endpoint("pc-1"), None);
+    }
+
+    #[test]
+    fn which_endpoint_is_none_when_both_ends_share_an_id() {
+        // connectでは同じIdをつなげないので、状態を直接書き換えて両端が同じIdのケーブルを作る
+        let cable = EthernetCable::new(None);
+        {
+            let mut state = cable.state.lock().unwrap();
+            state.endpoint1_component_id = Some("pc-1".to_string());
+            state.endpoint2_component_id = Some("pc-1".to_string());
+        }
+        assert_eq!(cable.which_endpoint("pc-1"), None);
+    }
+
+    #[test]
+    fn wiring_both_ends_to_the_same_id_is_rejected() {
+        let cable = EthernetCable::new(None);
+        assert_eq!(
+            cable.connect(Some("host-1".to_string()), Some("host-1".to_string())),
+            Err("Cannot connect both endpoints of a cable to the same component")
+        );
+        assert_eq!(cable.get_connection_info().endpoint1, None);
+
+        cable.connect_endpoint1(Some("host-1".to_string())).unwrap();
+        assert!(cable.connect_endpoint2(Some("host-1".to_string())).is_err());
+        assert_eq!(cable.get_endpoint2_component_id(), None);
+
+        let cable = EthernetCable::new(None);
+        cable.connect_endpoint2(Some("host-1".to_string())).unwrap();
+        assert!(cable.connect_endpoint1(Some("host-1".to_string())).is_err());
+        assert_eq!(cable.get_endpoint1_component_id(), None);
+        // 両端とも空ける(None)のは同じIdとはみなさない
+        assert!(cable.connect(None, None).is_ok());
+    }
+}
@@ -0,0 +1,240 @@
+use std::{collections::VecDeque, fmt, sync::{Arc, Mutex, Weak}};
+use rand::Rng;
+
+use crate::layer1::{component::ethernet_cable::{debug, EthernetCable}, packets::PhysicalLayerFrame, receive_callback::PhysicalLayerCallback};
+
+/// ハブのポート
+/// ポートごとにケーブルが1本つながる
+#[derive(Clone)]
+pub struct HubPort {
+    pub port_id : usize,         // ポート番号。ポートを外しても他のポートの番号は変わらない
+    pub cable   : EthernetCable, // このポートにつながっているケーブル
+}
+
+/// Hubの本体
+pub struct HubState {
+    pub id           : String,
+    pub ports        : Vec<HubPort>,
+    next_port_id     : usize,
+    repeating        : bool,                               // 届いたフレームを他のポートに送り出している最中かどうか
+    pending          : VecDeque<(usize, PhysicalLayerFrame)>, // 送り出している最中に届いた、届いたポートの番号とフレーム
+}
+
+impl fmt::Display for HubState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "###Hub= ")?;
+        writeln!(f, "#id                     : {}", self.id)?;
+        writeln!(f, "#ports                  : {}", self.ports.len())?;
+        for port in &self.ports {
+            writeln!(f, "#  port {:<3}             : {}", port.port_id, port.cable.get_id())?;
+        }
+        Ok(())
+    }
+}
+
+/// リピータハブ
+/// あるポートにフレームが届くと、そのまま他の全てのポートに送り出す
+/// ハブにつながっている全ての機器は1つのコリジョンドメインを共有する
+/// 
+/// あるフレームを他のポートに送り出している最中に別のフレームが届いた場合は、
+/// 同時に2つの信号が流れたこと(コリジョン)になる。今はログを出し、そのフレームは送り出し終わった後に順番に送る
+#[derive(Clone)]
+pub struct Hub {
+    state : Arc<Mutex<HubState>>,
+}
+
+impl fmt::Display for Hub {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        write!(f, "{}", *state)
+    }
+}
+
+impl Hub {
+    /// 新規にハブを配置したとき
+    pub fn new(id: Option<String>) -> Self {
+        debug("Hub::new([id]) called.");
+        let hub_id = id.unwrap_or_else(|| format!("hub-{}", rand::thread_rng().gen_range(9..9999)));
+        Hub {
+            state: Arc::new(Mutex::new(HubState {
+                id           : hub_id,
+                ports        : Vec::new(),
+                next_port_id : 0,
+                repeating    : false,
+                pending      : VecDeque::new(),
+            })),
+        }
+    }
+
+    /// そのハブのIdを取得
+    pub fn get_id(&self) -> String {
+        let state = self.state.lock().unwrap();
+        state.id.clone()
+    }
+
+    /// ポートの数を取得
+    pub fn get_port_count(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.ports.len()
+    }
+
+    /// ケーブルをつないでポートを追加する
+    /// ハブのIdでケーブルの端につなぎ、届いたフレームを受け取るcallbackを設定する
+    /// ケーブルの両端に別のコンポーネントがつながっていて空きがない場合はNone
+    /// 
+    /// ### 戻り値
+    /// * 追加したポートの番号
+    pub fn add_port(&self, cable: EthernetCable) -> Option<usize> {
+        let mut state = self.state.lock().unwrap();
+        let port_id = state.next_port_id;
+        let hub_id = state.id.clone();
+
+        // ケーブルがcallbackを持ち、ハブがケーブルを持つので、循環参照にならないようにWeakで持つ
+        let hub_state = Arc::downgrade(&self.state);
+        let callback: PhysicalLayerCallback = Arc::new(move |frame: PhysicalLayerFrame| {
+            Self::repeat(&hub_state, port_id, frame);
+        });
+        if !cable.attach(hub_id, callback) {
+            return None;
+        }
+
+        state.next_port_id += 1;
+        state.ports.push(HubPort { port_id, cable });
+        Some(port_id)
+    }
+
+    /// ポートを外す
+    /// ケーブルの端からもハブを外すので、ケーブルの端は空きになり、他のコンポーネントにつなげるようになる
+    /// 外したポートに届いていて、まだ送り出していないフレームは他のポートに送られない
+    /// 
+    /// ### 戻り値
+    /// * 指定したポートがあればtrue
+    pub fn remove_port(&self, port_id: usize) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let Some(index) = state.ports.iter().position(|port| port.port_id == port_id) else {
+            return false;
+        };
+        let removed = state.ports.remove(index);
+        let hub_id = state.id.clone();
+        // ケーブルの接続状態が変わるとcallbackが呼ばれることがあるので、ロックを外してから外す
+        drop(state);
+        removed.cable.detach(&hub_id);
+        true
+    }
+
+    /// in_portに届いたフレームを、他の全てのポートに送り出す
+    /// 送り出している最中に届いたフレームは待たせておき、届いた順に送り出す
+    fn repeat(hub_state: &Weak<Mutex<HubState>>, in_port: usize, frame: PhysicalLayerFrame) {
+        let Some(hub_state) = hub_state.upgrade() else {
+            return;
+        };
+        let mut state = hub_state.lock().unwrap();
+        if state.repeating {
+            debug(&format!("Hub({})::collision detected on port {}. frame is queued.", state.id, in_port));
+            state.pending.push_back((in_port, frame));
+            return;
+        }
+        state.repeating = true;
+        let mut next = Some((in_port, frame));
+        while let Some((in_port, frame)) = next {
+            let hub_id = state.id.clone();
+            let out_cables: Vec<EthernetCable> = if state.ports.iter().any(|port| port.port_id == in_port) {
+                state.ports
+                    .iter()
+                    .filter(|port| port.port_id != in_port)
+                    .map(|port| port.cable.clone())
+                    .collect()
+            } else {
+                debug(&format!("Hub({})::port {} is already removed.", hub_id, in_port));
+                Vec::new()
+            };
+            // 送り先でまたこのハブにフレームが届くことがあるので、ロックを外してから送る
+            drop(state);
+            for cable in out_cables {
+                cable.transmit_signal(hub_id.clone(), frame.clone());
+            }
+            state = hub_state.lock().unwrap();
+            next = state.pending.pop_front();
+        }
+        state.repeating = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer2::packets::EthernetFrame;
+
+    type Received = Arc<Mutex<Vec<Vec<u8>>>>;
+
+    /// ペイロードだけを指定した物理層フレームを作る
+    fn frame(payload: &[u8]) -> PhysicalLayerFrame {
+        PhysicalLayerFrame::new(Some(EthernetFrame::new(None, None, None, Some(payload.to_vec()))))
+    }
+
+    /// "pc-0", "pc-1", ...をつないだハブと、それぞれのケーブルと届いたペイロードの記録
+    fn hub_with_hosts(host_count: usize) -> (Hub, Vec<EthernetCable>, Vec<Received>) {
+        let hub = Hub::new(Some("hub-1".to_string()));
+        let mut cables = Vec::new();
+        let mut receiveds = Vec::new();
+        for port in 0..host_count {
+            let host_id = format!("pc-{}", port);
+            let cable = EthernetCable::new(Some(format!("cable-{}", port)));
+            cable.connect_endpoint1(Some(host_id.clone())).unwrap();
+            assert_eq!(hub.add_port(cable.clone()), Some(port));
+            let received = Received::default();
+            let record = received.clone();
+            cable.set_callback(host_id, Arc::new(move |frame: PhysicalLayerFrame| record.lock().unwrap().push(frame.ethernet_frame.data))).unwrap();
+            cables.push(cable);
+            receiveds.push(received);
+        }
+        (hub, cables, receiveds)
+    }
+
+    #[test]
+    fn frames_are_repeated_to_every_other_port() {
+        let (_hub, cables, receiveds) = hub_with_hosts(3);
+        cables[0].transmit_signal("pc-0".to_string(), frame(&[1]));
+
+        assert!(receiveds[0].lock().unwrap().is_empty());
+        assert_eq!(*receiveds[1].lock().unwrap(), vec![vec![1]]);
+        assert_eq!(*receiveds[2].lock().unwrap(), vec![vec![1]]);
+    }
+
+    #[test]
+    fn frames_arriving_while_repeating_are_queued_instead_of_dropped() {
+        let (_hub, cables, receiveds) = hub_with_hosts(3);
+        // pc-1はフレームを受け取ったその場で返事を送るので、ハブが送り出している最中に次のフレームが届く
+        let reply_cable = cables[1].clone();
+        let received1 = receiveds[1].clone();
+        cables[1].set_callback("pc-1".to_string(), Arc::new(move |received: PhysicalLayerFrame| {
+            received1.lock().unwrap().push(received.ethernet_frame.data.clone());
+            if received.ethernet_frame.data == [1] {
+                reply_cable.transmit_signal("pc-1".to_string(), frame(&[2]));
+            }
+        })).unwrap();
+
+        cables[0].transmit_signal("pc-0".to_string(), frame(&[1]));
+
+        assert_eq!(*receiveds[0].lock().unwrap(), vec![vec![2]]);
+        assert_eq!(*receiveds[1].lock().unwrap(), vec![vec![1]]);
+        assert_eq!(*receiveds[2].lock().unwrap(), vec![vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn remove_port_detaches_the_hub_from_the_cable() {
+        let (hub, cables, receiveds) = hub_with_hosts(3);
+        assert!(hub.remove_port(1));
+        assert!(!hub.remove_port(1));
+        assert_eq!(hub.get_port_count(), 2);
+
+        let info = cables[1].get_connection_info();
+        assert_eq!(info.endpoint1, Some("pc-1".to_string()));
+        assert_eq!(info.endpoint2, None);
+        assert!(!info.connected);
+
+        cables[0].transmit_signal("pc-0".to_string(), frame(&[1]));
+        assert!(receiveds[1].lock().unwrap().is_empty());
+        assert_eq!(*receiveds[2].lock().unwrap(), vec![vec![1]]);
+    }
+}
@@ -1,3 +1,5 @@
 pub(crate) mod ethernet_cable;
+pub(crate) mod hub;
 
 pub use ethernet_cable::EthernetCable;
+pub use hub::Hub;
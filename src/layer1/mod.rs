@@ -2,5 +2,6 @@ pub(crate) mod packets;
 pub(crate) mod component;
 pub(crate) mod receive_callback;
 
-pub use receive_callback::PhysicalLayerCallback;
-pub use component::EthernetCable;
\ No newline at end of file
+pub use receive_callback::{PhysicalLayerCallback, TapCallback};
+pub use component::EthernetCable;
+pub use component::Hub;
\ No newline at end of file
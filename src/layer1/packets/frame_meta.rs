@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+/// シミュレーターがフレームに付けておく情報
+/// 実際の信号には含まれないので、to_bytes()のバイト列には書き込まれず、from_bytes()では初期値になる
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FrameMeta {
+    pub trace_id : u64, // 1つの論理的なパケットを、ルーターでフレームが作り直された後も追いかけるためのId。0なら未設定
+}
@@ -1,3 +1,5 @@
+pub(crate) mod frame_meta;
 pub(crate) mod physical_layer_frame;
 
-pub use physical_layer_frame::PhysicalLayerFrame;
\ No newline at end of file
+pub use frame_meta::FrameMeta;
+pub use physical_layer_frame::PhysicalLayerFrame;
@@ -1,13 +1,47 @@
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::{fmt, hash::{Hash, Hasher}};
+use crate::layer1::packets::FrameMeta;
 use crate::layer2::packets::EthernetFrame;
 
 
-#[derive(Clone, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// 比較とハッシュは、バイト列から読み取った時のFCS(received_fcs)を見ない
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct PhysicalLayerFrame {
     pub preamble: [u8; 7],             // プリアンブル (7バイト)
     pub sfd: u8,                       // スタートフレームデリミタ (1バイト)
     pub ethernet_frame: EthernetFrame, // データリンク層のイーサネットフレーム
+    #[serde(skip)]
+    received_fcs: Option<ReceivedFcs>, // from_bytes_with_fcsで読み取ったFCS。それ以外で作ったフレームはNone
+    #[serde(default)]
+    pub meta: FrameMeta,               // シミュレーターが付けておく情報。バイト列には含まれない
+}
+
+/// バイト列から読み取ったFCS
+/// 読み取った時のイーサネットフレームのCRC-32も覚えておき、その後でethernet_frameが書き換えられたら読み取ったFCSは使わない
+#[derive(Clone, Copy, Debug)]
+struct ReceivedFcs {
+    fcs      : u32, // バイト列の末尾にあったFCS
+    computed : u32, // 読み取った時のイーサネットフレームのCRC-32
+}
+
+impl PartialEq for PhysicalLayerFrame {
+    fn eq(&self, other: &Self) -> bool {
+        self.preamble == other.preamble
+            && self.sfd == other.sfd
+            && self.ethernet_frame == other.ethernet_frame
+            && self.meta == other.meta
+    }
+}
+
+impl Eq for PhysicalLayerFrame {}
+
+impl Hash for PhysicalLayerFrame {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.preamble.hash(state);
+        self.sfd.hash(state);
+        self.ethernet_frame.hash(state);
+        self.meta.hash(state);
+    }
 }
 
 impl fmt::Display for PhysicalLayerFrame {
@@ -37,6 +71,8 @@ impl PhysicalLayerFrame {
             preamble: [0xAA; 7],
             sfd: 0xAB,
             ethernet_frame: frame.unwrap_or_else(EthernetFrame::default),
+            received_fcs: None,
+            meta: FrameMeta::default(),
         }
     }
 
@@ -50,23 +86,227 @@ impl PhysicalLayerFrame {
             preamble,
             sfd,
             ethernet_frame,
+            received_fcs: None,
+            meta: FrameMeta::default(),
+        }
+    }
+
+    /// バイト配列からPhysicalLayerFrameを構築
+    /// 先頭7バイトをプリアンブル、次の1バイトをSFDとして読み取り、残りをイーサネットフレームとする
+    /// 末尾4バイトが正しいFCSになっている場合は、FCSを取り除いてからイーサネットフレームとして読み取る
+    /// プリアンブルが0xAAの繰り返し、SFDが0xABでない場合はエラー
+    pub fn from_bytes(bytes: &[u8]) -> Result<PhysicalLayerFrame, &'static str> {
+        if bytes.len() < 8 + 14 {
+            return Err("Physical layer frame must be at least 22 bytes");
+        }
+        let (preamble, sfd) = Self::read_preamble_and_sfd(bytes)?;
+
+        let mut frame_bytes = &bytes[8..];
+        if frame_bytes.len() >= 14 + 4 && EthernetFrame::verify_fcs(frame_bytes) {
+            frame_bytes = &frame_bytes[..frame_bytes.len() - 4];
+        }
+        let ethernet_frame = EthernetFrame::from_bytes(frame_bytes)?;
+
+        Ok(Self::from_raw(preamble, sfd, ethernet_frame))
+    }
+
+    /// 末尾4バイトに必ずFCSが付いているバイト配列からPhysicalLayerFrameを構築
+    /// from_bytesと違い、FCSが合っているかどうかに関係なく末尾4バイトを取り除き、読み取ったFCSを覚えておく
+    /// ビット誤りで壊れたフレームでも、to_bytesで同じFCSを書き出し、fcs_validでFCSが合わないことを確認できる
+    pub fn from_bytes_with_fcs(bytes: &[u8]) -> Result<PhysicalLayerFrame, &'static str> {
+        if bytes.len() < 8 + 14 + 4 {
+            return Err("Physical layer frame with an FCS must be at least 26 bytes");
         }
+        let (preamble, sfd) = Self::read_preamble_and_sfd(bytes)?;
+
+        let (frame_bytes, fcs) = bytes[8..].split_at(bytes.len() - 8 - 4);
+        let ethernet_frame = EthernetFrame::from_bytes(frame_bytes)?;
+        let received_fcs = ReceivedFcs {
+            fcs      : u32::from_be_bytes([fcs[0], fcs[1], fcs[2], fcs[3]]),
+            computed : ethernet_frame.compute_fcs(),
+        };
+
+        Ok(Self {
+            received_fcs: Some(received_fcs),
+            ..Self::from_raw(preamble, sfd, ethernet_frame)
+        })
+    }
+
+    /// 先頭8バイトをプリアンブルとSFDとして読み取る
+    /// プリアンブルが0xAAの繰り返し、SFDが0xABでない場合はエラー
+    fn read_preamble_and_sfd(bytes: &[u8]) -> Result<([u8; 7], u8), &'static str> {
+        let mut preamble = [0u8; 7];
+        preamble.copy_from_slice(&bytes[0..7]);
+        if preamble.iter().any(|&byte| byte != 0xAA) {
+            return Err("Invalid preamble in physical layer frame");
+        }
+        let sfd = bytes[7];
+        if sfd != 0xAB {
+            return Err("Invalid SFD in physical layer frame");
+        }
+        Ok((preamble, sfd))
+    }
+
+    /// 送る時に付けるFCS
+    /// from_bytes_with_fcsで読み取ったまま書き換えられていないフレームは読み取ったFCS、それ以外は計算し直したFCS
+    fn fcs(&self) -> u32 {
+        let computed = self.ethernet_frame.compute_fcs();
+        match self.received_fcs {
+            Some(received) if received.computed == computed => received.fcs,
+            _ => computed,
+        }
+    }
+
+    /// FCSが正しいかを確認する
+    /// from_bytes_with_fcsで読み取ったフレームは、読み取ったFCSがイーサネットフレームのCRC-32と一致すればtrue
+    /// それ以外のフレームと、読み取った後でethernet_frameを書き換えたフレームは、送る時に正しいFCSを付けるので常にtrue
+    pub fn fcs_valid(&self) -> bool {
+        self.fcs() == self.ethernet_frame.compute_fcs()
     }
 
     /// フレーム全体のバイト長を計算する
     pub fn total_length(&self) -> usize {
-        8 + self.ethernet_frame.total_length() // プリアンブル + SFD + イーサネットフレーム長
+        8 + self.ethernet_frame.total_length() + 4 // プリアンブル + SFD + イーサネットフレーム長 + FCS
     }
 
-    /// バイト配列に変換
+    /// バイト配列に変換（プリアンブル + SFD + イーサネットフレーム + FCS）
+    /// ethertypeはネットワークバイトオーダー(ビッグエンディアン)で書き込む
+    /// 例: 0x0806(ARP)は 08 06、0x86DD(IPv6)は 86 DD の順に並ぶ
+    /// FCSはイーサネットフレームのCRC-32をビッグエンディアンの4バイトで末尾に付ける
+    /// from_bytes_with_fcsで読み取ったフレームは、読み取ったFCSをそのまま付ける(ハブが壊れたフレームを中継しても壊れたままになる)
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
+        let mut bytes = Vec::with_capacity(self.total_length());
         bytes.extend_from_slice(&self.preamble);
         bytes.push(self.sfd);
-        bytes.extend_from_slice(&self.ethernet_frame.dst_mac.to_array());
-        bytes.extend_from_slice(&self.ethernet_frame.src_mac.to_array());
-        bytes.extend_from_slice(&self.ethernet_frame.ethertype.to_be_bytes());
-        bytes.extend_from_slice(&self.ethernet_frame.data);
+        bytes.extend_from_slice(&self.ethernet_frame.to_bytes());
+        bytes.extend_from_slice(&self.fcs().to_be_bytes());
         bytes
     }
+
+    /// イーサネットフレームを物理層フレームに包んでバイト列にし、読み取り直して元のフレームに戻るかを確認する
+    /// バイト列への変換と読み取りのどこかで情報が失われていないかを調べるためのテスト用の関数
+    #[cfg(test)]
+    pub fn roundtrip_check(frame: &EthernetFrame) -> bool {
+        let bytes = PhysicalLayerFrame::new(Some(frame.clone())).to_bytes();
+        match PhysicalLayerFrame::from_bytes(&bytes) {
+            Ok(decoded) => decoded.ethernet_frame == *frame,
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer2::address::MacAddress;
+
+    fn frame(ethertype: u16) -> PhysicalLayerFrame {
+        PhysicalLayerFrame::new(Some(EthernetFrame::new(None, None, Some(ethertype), Some(vec![0; 46]))))
+    }
+
+    #[test]
+    fn ethertype_round_trips_big_endian() {
+        for (ethertype, expected) in [(0x0806, [0x08, 0x06]), (0x86DD, [0x86, 0xDD])] {
+            let bytes = frame(ethertype).to_bytes();
+            // プリアンブル+SFD(8バイト)、宛先/送信元MACアドレス(12バイト)の後ろにethertypeが続く
+            assert_eq!(bytes[20..22], expected);
+            assert_eq!(PhysicalLayerFrame::from_bytes(&bytes).unwrap().ethernet_frame.ethertype, ethertype);
+        }
+    }
+
+    #[test]
+    fn frames_survive_the_roundtrip() {
+        let mac = |last: u8| MacAddress::from_array([0x02, 0x00, 0x00, 0x00, 0x00, last]);
+        let frames = [
+            EthernetFrame::new(Some(mac(1)), Some(mac(2)), Some(0x0800), Some(Vec::new())),
+            EthernetFrame::new(Some(mac(1)), Some(mac(2)), Some(0x0800), Some((0..1500).map(|i| i as u8).collect())),
+            EthernetFrame::new(Some(mac(1)), Some(mac(2)), Some(0x0806), Some(vec![0x5A; 46])).with_vlan(100, 5).unwrap(),
+            EthernetFrame::new(Some(mac(1)), Some(mac(2)), Some(0x86DD), Some(vec![0xFF; 64])).with_qinq(10, 20).unwrap(),
+        ];
+        for frame in &frames {
+            assert!(PhysicalLayerFrame::roundtrip_check(frame), "{:?}", frame);
+        }
+    }
+
+    #[test]
+    fn to_bytes_length_matches_total_length_including_the_fcs() {
+        let physical_frame = frame(0x0800);
+        let bytes = physical_frame.to_bytes();
+        assert_eq!(bytes.len(), physical_frame.total_length());
+        assert_eq!(bytes.len(), 8 + 14 + 46 + 4);
+        assert!(EthernetFrame::verify_fcs(&bytes[8..]));
+    }
+
+    #[test]
+    fn from_bytes_strips_the_fcs_and_reads_the_ethernet_frame() {
+        let physical_frame = frame(0x0806);
+        let decoded = PhysicalLayerFrame::from_bytes(&physical_frame.to_bytes()).unwrap();
+        assert_eq!(decoded, physical_frame);
+    }
+
+    #[test]
+    fn from_bytes_keeps_bytes_without_an_fcs() {
+        let physical_frame = frame(0x0800);
+        let bytes = physical_frame.to_bytes();
+        // FCSのないバイト列は、末尾まで全てイーサネットフレームとして読む
+        let without_fcs = &bytes[..bytes.len() - 4];
+        assert_eq!(PhysicalLayerFrame::from_bytes(without_fcs).unwrap(), physical_frame);
+        let header_only = PhysicalLayerFrame::from_bytes(&bytes[..22]).unwrap();
+        assert!(header_only.ethernet_frame.data.is_empty());
+    }
+
+    #[test]
+    fn from_bytes_rejects_short_buffers_and_a_broken_preamble_or_sfd() {
+        let bytes = frame(0x0800).to_bytes();
+        assert!(PhysicalLayerFrame::from_bytes(&bytes[..21]).is_err());
+
+        let mut broken_preamble = bytes.clone();
+        broken_preamble[3] = 0x55;
+        assert!(PhysicalLayerFrame::from_bytes(&broken_preamble).is_err());
+
+        let mut broken_sfd = bytes.clone();
+        broken_sfd[7] = 0xAA;
+        assert!(PhysicalLayerFrame::from_bytes(&broken_sfd).is_err());
+    }
+
+    #[test]
+    fn from_bytes_with_fcs_keeps_a_wrong_fcs_and_marks_the_frame_invalid() {
+        let physical_frame = frame(0x0800);
+        let decoded = PhysicalLayerFrame::from_bytes_with_fcs(&physical_frame.to_bytes()).unwrap();
+        assert_eq!(decoded, physical_frame);
+        assert!(decoded.fcs_valid());
+
+        let mut bytes = physical_frame.to_bytes();
+        bytes[30] ^= 0x01;
+        let corrupted = PhysicalLayerFrame::from_bytes_with_fcs(&bytes).unwrap();
+        assert_eq!(corrupted.ethernet_frame.data.len(), 46);
+        assert!(!corrupted.fcs_valid());
+        // 壊れたFCSのまま送り直すので、読み取り直しても壊れたまま
+        assert_eq!(corrupted.to_bytes(), bytes);
+        assert!(!EthernetFrame::verify_fcs(&corrupted.to_bytes()[8..]));
+        // 比較には読み取ったFCSを含めない
+        assert_eq!(corrupted, PhysicalLayerFrame::from_bytes_with_fcs(&bytes).unwrap());
+        assert!(PhysicalLayerFrame::from_bytes_with_fcs(&bytes[..25]).is_err());
+    }
+
+    #[test]
+    fn editing_a_received_frame_recomputes_the_fcs() {
+        let mut bytes = frame(0x0800).to_bytes();
+        bytes[30] ^= 0x01;
+        let mut corrupted = PhysicalLayerFrame::from_bytes_with_fcs(&bytes).unwrap();
+        corrupted.ethernet_frame.data[0] = 0xFF;
+        assert!(corrupted.fcs_valid());
+        assert!(EthernetFrame::verify_fcs(&corrupted.to_bytes()[8..]));
+    }
+
+    #[test]
+    fn serializes_with_the_struct_field_names_and_round_trips() {
+        let original = frame(0x0800);
+        let value = serde_json::to_value(&original).unwrap();
+        for field in ["preamble", "sfd", "ethernet_frame", "meta"] {
+            assert!(value.get(field).is_some(), "{}", field);
+        }
+        assert!(value.get("received_fcs").is_none());
+        assert_eq!(serde_json::from_value::<PhysicalLayerFrame>(value).unwrap(), original);
+    }
 }
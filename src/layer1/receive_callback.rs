@@ -1,5 +1,9 @@
 use std::sync::Arc;
 use crate::PhysicalLayerFrame;
+use crate::layer1::component::ethernet_cable::{CableEvent, Endpoint};
 
 // Callback function type -------------------------------------
-pub type PhysicalLayerCallback    = Arc<dyn Fn(PhysicalLayerFrame) + Send + Sync>;
\ No newline at end of file
+pub type PhysicalLayerCallback    = Arc<dyn Fn(PhysicalLayerFrame) + Send + Sync>;
+pub type CableEventCallback       = Arc<dyn Fn(CableEvent) + Send + Sync>;
+pub type ConnectionCallback       = Arc<dyn Fn(bool) + Send + Sync>;
+pub type TapCallback              = Arc<dyn Fn(PhysicalLayerFrame, Endpoint) + Send + Sync>; // フレームと、それを送った端
@@ -2,7 +2,11 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+use crate::error::ParseError;
+use crate::layer2::address::oui::lookup_vendor;
+use crate::layer3::address::IPv6Address;
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct MacAddress(pub [u8; 6]);
 
 impl fmt::Display for MacAddress {
@@ -29,26 +33,96 @@ impl MacAddress {
 
         MacAddress(addr)
     }
+    /// 先頭3オクテットを指定したOUIにして、残りの3オクテットをランダムに生成する
+    /// 特定のベンダーのNICを再現するためのものなので、newと違ってU/Lビットは書き換えず、OUIをそのまま使う
+    pub fn new_with_oui(oui: [u8; 3]) -> Self {
+        let mut rng = rand::thread_rng();
+        let nic: [u8; 3] = rng.gen();
+        MacAddress([oui[0], oui[1], oui[2], nic[0], nic[1], nic[2]])
+    }
     /// ":"区切りの文字列からMACアドレスを生成する関数
-    pub fn from_string(mac_str: &str) -> Result<MacAddress, &'static str> {
-        let bytes: Vec<u8> = mac_str.split(':')
-                                    .map(|s| u8::from_str_radix(s, 16))
-                                    .collect::<Result<Vec<u8>, _>>()
-                                    .map_err(|_| "Invalid MAC address format")?;
-    
-        if bytes.len() == 6 {
-            let mut mac_array = [0u8; 6];
-            mac_array.copy_from_slice(&bytes);
-            Ok(MacAddress(mac_array))
-        } else {
-            Err("MAC address must contain exactly 6 bytes")
+    /// 区切り文字のない12桁の16進数("001122334455")も受け付ける
+    pub fn from_string(mac_str: &str) -> Result<MacAddress, ParseError> {
+        if !mac_str.contains([':', '-', '.']) {
+            return Self::from_hex_digits(mac_str);
+        }
+        let segments: Vec<&str> = mac_str.split(':').collect();
+        if segments.len() != 6 {
+            return Err(ParseError::WrongSegmentCount { expected: 6, found: segments.len() });
+        }
+
+        let mut mac_array = [0u8; 6];
+        for (byte, segment) in mac_array.iter_mut().zip(segments) {
+            *byte = Self::parse_octet(segment)?;
         }
+        Ok(MacAddress(mac_array))
     }
+    /// いろいろな表記のMACアドレスを受け付けて生成する関数
+    /// - "00:11:22:33:44:55" (":"区切り)
+    /// - "00-11-22-33-44-55" ("-"区切り、Windowsの表記)
+    /// - "0011.2233.4455" ("."区切りの4桁ずつ、Ciscoの表記)
+    /// - "001122334455" (区切りなし)
+    ///
+    /// 区切り文字が混ざっているものはエラーにする
+    pub fn from_string_flexible(mac_str: &str) -> Result<MacAddress, ParseError> {
+        let separators: Vec<char> = [':', '-', '.']
+            .into_iter()
+            .filter(|sep| mac_str.contains(*sep))
+            .collect();
+        match separators.as_slice() {
+            [] | [':'] => Self::from_string(mac_str),
+            ['-'] => Self::from_string(&mac_str.replace('-', ":")),
+            ['.'] => {
+                let groups: Vec<&str> = mac_str.split('.').collect();
+                if groups.len() != 3 {
+                    return Err(ParseError::WrongSegmentCount { expected: 3, found: groups.len() });
+                }
+                if groups.iter().any(|group| group.len() != 4) {
+                    return Err(ParseError::InvalidFormat("Dot-separated MAC address must be groups of 4 hex digits"));
+                }
+                Self::from_hex_digits(&groups.concat())
+            }
+            _ => Err(ParseError::InvalidFormat("MAC address must not mix different separators")),
+        }
+    }
+    /// 1〜2桁の16進数を1オクテットとして読み取る
+    /// 空のもの、3桁以上のもの、16進数以外の文字("+"の符号も含む)を含むものはエラー
+    fn parse_octet(segment: &str) -> Result<u8, ParseError> {
+        if segment.is_empty() || segment.len() > 2 || !segment.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ParseError::InvalidHex);
+        }
+        u8::from_str_radix(segment, 16).map_err(|_| ParseError::InvalidHex)
+    }
+    /// 区切り文字のない12桁の16進数の文字列からMACアドレスを生成する関数
+    fn from_hex_digits(mac_str: &str) -> Result<MacAddress, ParseError> {
+        if mac_str.len() != 12 {
+            return Err(ParseError::InvalidFormat("MAC address without separators must be exactly 12 hex digits"));
+        }
+        if !mac_str.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ParseError::InvalidHex);
+        }
+        let mut mac_array = [0u8; 6];
+        for (i, byte) in mac_array.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&mac_str[i * 2..i * 2 + 2], 16)
+                .map_err(|_| ParseError::InvalidHex)?;
+        }
+        Ok(MacAddress(mac_array))
+    }
+
     /// バイト配列からMACアドレスを生成する関数
     pub fn from_array(bytes: [u8; 6]) -> Self {
         MacAddress(bytes)
     }
 
+    /// バイトスライスからMACアドレスを生成する関数
+    /// 長さが6バイトでない場合はParseError::WrongLengthを返す
+    pub fn from_slice(data: &[u8]) -> Result<MacAddress, ParseError> {
+        let array: [u8; 6] = data
+            .try_into()
+            .map_err(|_| ParseError::WrongLength { expected: 6, found: data.len() })?;
+        Ok(MacAddress(array))
+    }
+
     /// MACアドレスをバイトスライスとして取得
     pub fn as_slice(&self) -> &[u8] {
         &self.0
@@ -58,6 +132,39 @@ impl MacAddress {
         self.0
     }
 
+    /// MACアドレスの6バイトを下位48ビットに詰めたu64に変換
+    /// 大小関係はMacAddressのOrd(バイト配列の辞書順)と同じになるので、並べ替えのキーに使える
+    pub fn to_u64(self) -> u64 {
+        let mut bytes = [0u8; 8];
+        bytes[2..].copy_from_slice(&self.0);
+        u64::from_be_bytes(bytes)
+    }
+
+    /// セパレータを指定してMACアドレスを文字列に変換
+    /// 各オクテットは大文字の2桁の16進数にする。セパレータに'\0'を指定するとセパレータなしで続けて書く
+    /// 
+    /// ```rust
+    /// mac.to_string_with_separator(':');  // "00:11:22:33:44:55"
+    /// mac.to_string_with_separator('-');  // "00-11-22-33-44-55"
+    /// mac.to_string_with_separator('\0'); // "001122334455"
+    /// ```
+    pub fn to_string_with_separator(self, separator: char) -> String {
+        let separator = if separator == '\0' { String::new() } else { separator.to_string() };
+        self.0.map(|byte| format!("{:02X}", byte)).join(&separator)
+    }
+
+    /// OUI(先頭3オクテット)を取得
+    /// IEEEがベンダーごとに割り当てている部分
+    pub fn oui(&self) -> [u8; 3] {
+        [self.0[0], self.0[1], self.0[2]]
+    }
+
+    /// OUIからベンダー名を取得
+    /// 組み込みの表にないOUIの場合はNone
+    pub fn vendor_name(&self) -> Option<&'static str> {
+        lookup_vendor(self.oui())
+    }
+
     /// broadcast用のMAC Addressを取得する関数
     pub fn get_broadcast_mac_addr() -> MacAddress {
         // IPv4では全てFFにすることでブロードキャストアドレスになる
@@ -71,6 +178,222 @@ impl MacAddress {
         let mac:MacAddress = MacAddress([0x00;6]);
         mac
     }
-    
 
+    /// ブロードキャストアドレス(FF:FF:FF:FF:FF:FF)かどうか
+    pub fn is_broadcast(&self) -> bool {
+        self.0 == [0xFF; 6]
+    }
+
+    /// マルチキャストアドレスかどうか
+    /// 先頭オクテットの最下位ビット(I/Gビット)が1ならマルチキャスト。ブロードキャストもマルチキャストに含まれる
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+
+    /// ユニキャストアドレスかどうか (I/Gビットが0)
+    pub fn is_unicast(&self) -> bool {
+        !self.is_multicast()
+    }
+
+    /// ローカル管理アドレスかどうか
+    /// 先頭オクテットの0x02(U/Lビット)が1ならローカル管理、0ならベンダーが割り当てたグローバルアドレス
+    pub fn is_locally_administered(&self) -> bool {
+        self.0[0] & 0x02 != 0
+    }
+
+    /// MACアドレスからEUI-64形式のインターフェースIDを生成する関数
+    /// 真ん中にFF:FEを挟み、U/Lビット(先頭オクテットの0x02)を反転させる
+    pub fn to_eui64(self) -> [u8; 8] {
+        [
+            self.0[0] ^ 0x02,
+            self.0[1],
+            self.0[2],
+            0xFF,
+            0xFE,
+            self.0[3],
+            self.0[4],
+            self.0[5],
+        ]
+    }
+
+    /// IPv6マルチキャストアドレスに対応するMACアドレスを取得する関数
+    /// 33:33の後ろにIPv6アドレスの下位32ビットを並べる
+    pub fn from_ipv6_multicast(ip: &IPv6Address) -> MacAddress {
+        let addr = ip.to_array();
+        MacAddress([0x33, 0x33, addr[12], addr[13], addr[14], addr[15]])
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAC: MacAddress = MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+
+    #[test]
+    fn to_eui64_inserts_fffe_and_flips_the_ul_bit() {
+        assert_eq!(MAC.to_eui64(), [0x02, 0x11, 0x22, 0xFF, 0xFE, 0x33, 0x44, 0x55]);
+    }
+
+    #[test]
+    fn from_ipv6_multicast_uses_3333_and_the_low_32_bits() {
+        let ip = IPv6Address::from_string("ff02::1:ff33:4455").unwrap();
+        assert_eq!(MacAddress::from_ipv6_multicast(&ip), MacAddress([0x33, 0x33, 0xFF, 0x33, 0x44, 0x55]));
+    }
+
+    #[test]
+    fn from_string_accepts_twelve_hex_digits_without_separators() {
+        assert_eq!(MacAddress::from_string("001122334455"), Ok(MAC));
+    }
+
+    #[test]
+    fn from_string_rejects_bare_digits_of_the_wrong_length() {
+        assert!(matches!(MacAddress::from_string("0011223344"), Err(ParseError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn from_string_still_accepts_colon_separated_addresses() {
+        assert_eq!(MacAddress::from_string("00:11:22:33:44:55"), Ok(MAC));
+        assert_eq!(MacAddress::from_string_flexible("00-11-22-33-44-55"), Ok(MAC));
+    }
+
+    #[test]
+    fn from_slice_requires_exactly_6_bytes() {
+        assert_eq!(MacAddress::from_slice(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]), Ok(MAC));
+        assert_eq!(
+            MacAddress::from_slice(&[0x00, 0x11, 0x22]),
+            Err(ParseError::WrongLength { expected: 6, found: 3 })
+        );
+        assert!(MacAddress::from_slice(&[0; 7]).is_err());
+    }
+
+    #[test]
+    fn classifies_broadcast_multicast_and_unicast_addresses() {
+        let broadcast = MacAddress::get_broadcast_mac_addr();
+        assert!(broadcast.is_broadcast());
+        assert!(broadcast.is_multicast());
+        assert!(!broadcast.is_unicast());
+
+        let multicast = MacAddress([0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB]);
+        assert!(!multicast.is_broadcast());
+        assert!(multicast.is_multicast());
+        assert!(!multicast.is_unicast());
+        assert!(!multicast.is_locally_administered());
+
+        assert!(MAC.is_unicast());
+        assert!(!MAC.is_locally_administered());
+    }
+
+    #[test]
+    fn generated_addresses_are_locally_administered() {
+        for _ in 0..100 {
+            let mac = MacAddress::new();
+            assert!(mac.is_locally_administered());
+            assert_eq!(mac.is_unicast(), !mac.is_multicast());
+        }
+    }
+
+    #[test]
+    fn from_string_rejects_the_wrong_number_of_octets() {
+        assert_eq!(
+            MacAddress::from_string("00:11:22:33:44"),
+            Err(ParseError::WrongSegmentCount { expected: 6, found: 5 })
+        );
+        assert_eq!(
+            MacAddress::from_string("00:11:22:33:44:55:66"),
+            Err(ParseError::WrongSegmentCount { expected: 6, found: 7 })
+        );
+    }
+
+    #[test]
+    fn from_string_rejects_non_hex_and_empty_octets() {
+        assert_eq!(MacAddress::from_string("gg:11:22:33:44:55"), Err(ParseError::InvalidHex));
+        assert_eq!(MacAddress::from_string("00:11::33:44:55"), Err(ParseError::InvalidHex));
+        assert_eq!(MacAddress::from_string("00:11:22:33:44:555"), Err(ParseError::InvalidHex));
+        assert_eq!(MacAddress::from_string("0:1:2:3:4:5"), Ok(MacAddress([0, 1, 2, 3, 4, 5])));
+    }
+
+    #[test]
+    fn from_string_flexible_accepts_colon_dash_and_cisco_formats() {
+        for input in ["00:11:22:33:44:55", "00-11-22-33-44-55", "0011.2233.4455"] {
+            assert_eq!(MacAddress::from_string_flexible(input), Ok(MAC), "{}", input);
+        }
+    }
+
+    #[test]
+    fn from_string_flexible_rejects_mixed_or_malformed_separators() {
+        assert!(matches!(MacAddress::from_string_flexible("00-11:22-33:44-55"), Err(ParseError::InvalidFormat(_))));
+        assert!(matches!(MacAddress::from_string_flexible("001.12233.4455"), Err(ParseError::InvalidFormat(_))));
+        assert_eq!(
+            MacAddress::from_string_flexible("0011.2233"),
+            Err(ParseError::WrongSegmentCount { expected: 3, found: 2 })
+        );
+    }
+
+    #[test]
+    fn vendor_name_resolves_known_ouis_only() {
+        let vmware = MacAddress([0x00, 0x50, 0x56, 0x12, 0x34, 0x56]);
+        assert_eq!(vmware.oui(), [0x00, 0x50, 0x56]);
+        assert_eq!(vmware.vendor_name(), Some("VMware"));
+        assert_eq!(MAC.vendor_name(), None);
+    }
+
+    #[test]
+    fn new_with_oui_keeps_the_oui_and_randomizes_the_nic_part() {
+        let oui = [0x00, 0x50, 0x56];
+        let macs: Vec<MacAddress> = (0..10).map(|_| MacAddress::new_with_oui(oui)).collect();
+        assert!(macs.iter().all(|mac| mac.oui() == oui));
+        assert!(macs.iter().any(|mac| mac.0[3..] != macs[0].0[3..]));
+    }
+
+    #[test]
+    fn to_string_with_separator_uses_uppercase_two_digit_octets() {
+        let mac = MacAddress::from_array([0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x0E]);
+        assert_eq!(mac.to_string_with_separator(':'), "00:1A:2B:3C:4D:0E");
+        assert_eq!(mac.to_string_with_separator('-'), "00-1A-2B-3C-4D-0E");
+        assert_eq!(mac.to_string_with_separator('\0'), "001A2B3C4D0E");
+    }
+
+    #[test]
+    fn sorts_lexicographically_over_the_bytes() {
+        let mut macs = [
+            MacAddress::from_array([0x02, 0x00, 0x00, 0x00, 0x00, 0x01]),
+            MacAddress::from_array([0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]),
+            MacAddress::get_broadcast_mac_addr(),
+            MacAddress::from_array([0x02, 0x00, 0x00, 0x00, 0x01, 0x00]),
+            MacAddress::from_array([0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+        ];
+        macs.sort();
+        let sorted: Vec<String> = macs.iter().map(|mac| mac.to_string_with_separator(':')).collect();
+        assert_eq!(sorted, [
+            "00:00:00:00:00:00",
+            "00:FF:FF:FF:FF:FF",
+            "02:00:00:00:00:01",
+            "02:00:00:00:01:00",
+            "FF:FF:FF:FF:FF:FF",
+        ]);
+        assert!(macs.windows(2).all(|pair| pair[0].to_u64() < pair[1].to_u64()));
+    }
+
+    #[test]
+    fn to_u64_packs_the_bytes_big_endian() {
+        assert_eq!(MacAddress::from_array([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]).to_u64(), 0x0011_2233_4455);
+        assert_eq!(MacAddress::get_broadcast_mac_addr().to_u64(), 0xFFFF_FFFF_FFFF);
+    }
+
+    #[test]
+    fn random_addresses_round_trip_through_arrays_and_slices() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let mac = MacAddress::from_array(rng.gen());
+            assert_eq!(MacAddress::from_array(mac.to_array()), mac);
+            // WasmMacAddressのto_bytes/from_bytesと同じ変換
+            assert_eq!(MacAddress::from_slice(mac.as_slice()), Ok(mac));
+
+            let generated = MacAddress::new();
+            assert_eq!(MacAddress::from_array(generated.to_array()), generated);
+            assert_eq!(MacAddress::from_slice(generated.as_slice()), Ok(generated));
+        }
+    }
 }
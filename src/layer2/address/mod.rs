@@ -1,3 +1,4 @@
 pub(crate) mod mac_address;
+pub(crate) mod oui;
 
 pub use mac_address::MacAddress;
\ No newline at end of file
@@ -0,0 +1,58 @@
+/// よく知られたベンダーのOUI(MACアドレスの先頭3オクテット)と名前の表
+/// WASMのサイズが大きくならないように、教材でよく見かけるものだけにしている
+/// OUIの順に並べておく
+pub(crate) const OUI_VENDORS: &[([u8; 3], &str)] = &[
+    ([0x00, 0x00, 0x0C], "Cisco"),
+    ([0x00, 0x00, 0x5E], "IANA"),
+    ([0x00, 0x00, 0xAA], "Xerox"),
+    ([0x00, 0x00, 0xF0], "Samsung"),
+    ([0x00, 0x02, 0xB3], "Intel"),
+    ([0x00, 0x03, 0x93], "Apple"),
+    ([0x00, 0x04, 0x4B], "NVIDIA"),
+    ([0x00, 0x05, 0x02], "Apple"),
+    ([0x00, 0x05, 0x69], "VMware"),
+    ([0x00, 0x05, 0x85], "Juniper Networks"),
+    ([0x00, 0x09, 0x0F], "Fortinet"),
+    ([0x00, 0x0A, 0x95], "Apple"),
+    ([0x00, 0x0B, 0x86], "Aruba Networks"),
+    ([0x00, 0x0C, 0x29], "VMware"),
+    ([0x00, 0x0D, 0x3A], "Microsoft"),
+    ([0x00, 0x10, 0x18], "Broadcom"),
+    ([0x00, 0x14, 0x22], "Dell"),
+    ([0x00, 0x15, 0x5D], "Microsoft (Hyper-V)"),
+    ([0x00, 0x16, 0x3E], "Xen"),
+    ([0x00, 0x18, 0x0A], "Cisco Meraki"),
+    ([0x00, 0x1A, 0x11], "Google"),
+    ([0x00, 0x1B, 0x17], "Palo Alto Networks"),
+    ([0x00, 0x1B, 0x21], "Intel"),
+    ([0x00, 0x1C, 0x42], "Parallels"),
+    ([0x00, 0x25, 0x90], "Super Micro"),
+    ([0x00, 0x50, 0x56], "VMware"),
+    ([0x00, 0x50, 0xF2], "Microsoft"),
+    ([0x00, 0xE0, 0x4C], "Realtek"),
+    ([0x01, 0x00, 0x5E], "IPv4 Multicast"),
+    ([0x02, 0x42, 0xAC], "Docker"),
+    ([0x08, 0x00, 0x27], "VirtualBox"),
+    ([0x52, 0x54, 0x00], "QEMU/KVM"),
+    ([0xB8, 0x27, 0xEB], "Raspberry Pi"),
+    ([0xDC, 0xA6, 0x32], "Raspberry Pi"),
+    ([0xE4, 0x5F, 0x01], "Raspberry Pi"),
+];
+
+/// OUIからベンダー名を探す
+pub(crate) fn lookup_vendor(oui: [u8; 3]) -> Option<&'static str> {
+    OUI_VENDORS
+        .binary_search_by(|(entry, _)| entry.cmp(&oui))
+        .ok()
+        .map(|index| OUI_VENDORS[index].1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_sorted_by_oui_for_binary_search() {
+        assert!(OUI_VENDORS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+}
@@ -0,0 +1,223 @@
+use std::{collections::HashMap, fmt, sync::{Arc, Mutex, Weak}};
+use rand::Rng;
+
+use crate::layer1::{component::ethernet_cable::debug, packets::PhysicalLayerFrame, receive_callback::PhysicalLayerCallback, EthernetCable};
+use crate::layer2::address::MacAddress;
+
+/// ブリッジのポート(セグメント)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BridgePort {
+    A, // ポートA側のセグメント
+    B, // ポートB側のセグメント
+}
+
+impl BridgePort {
+    /// 反対側のポート
+    pub fn opposite(self) -> BridgePort {
+        match self {
+            BridgePort::A => BridgePort::B,
+            BridgePort::B => BridgePort::A,
+        }
+    }
+}
+
+/// Bridgeの本体
+pub struct BridgeState {
+    pub id        : String,
+    pub port_a    : Option<EthernetCable>,            // ポートAにつながっているケーブル
+    pub port_b    : Option<EthernetCable>,            // ポートBにつながっているケーブル
+    pub mac_table : HashMap<MacAddress, BridgePort>,  // 送信元MACアドレスから学習した、その機器がいる側
+}
+
+impl fmt::Display for BridgeState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cable_id = |cable: &Option<EthernetCable>| cable.as_ref().map(|cable| cable.get_id()).unwrap_or_else(|| "-".to_string());
+        writeln!(f, "###Bridge= ")?;
+        writeln!(f, "#id                     : {}", self.id)?;
+        writeln!(f, "#port_a                 : {}", cable_id(&self.port_a))?;
+        writeln!(f, "#port_b                 : {}", cable_id(&self.port_b))?;
+        writeln!(f, "#mac_table              : {}", self.mac_table.len())?;
+        // ポート、MACアドレスの順に並べて表示する
+        let mut entries: Vec<(BridgePort, MacAddress)> = self.mac_table
+            .iter()
+            .map(|(mac, port)| (*port, *mac))
+            .collect();
+        entries.sort();
+        for (port, mac) in entries {
+            writeln!(f, "#  {}     : port {:?}", mac.to_string_with_separator(':'), port)?;
+        }
+        Ok(())
+    }
+}
+
+impl BridgeState {
+    /// 指定したポートにつながっているケーブル
+    fn cable(&self, port: BridgePort) -> &Option<EthernetCable> {
+        match port {
+            BridgePort::A => &self.port_a,
+            BridgePort::B => &self.port_b,
+        }
+    }
+}
+
+/// 2つのセグメントをつなぐ透過ブリッジ
+/// ポートに届いたフレームの送信元MACアドレスを学習し、宛先が同じ側にいるとわかっているフレームは反対側に送らない(フィルタリング)
+/// 宛先が反対側にいるか、まだわからないか、ブロードキャストの場合は反対側に送る
+/// ポートが2つしかないので、スイッチと違って送り先は「反対側に送るか、送らないか」だけになる
+#[derive(Clone)]
+pub struct Bridge {
+    state : Arc<Mutex<BridgeState>>,
+}
+
+impl fmt::Display for Bridge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        write!(f, "{}", *state)
+    }
+}
+
+impl Bridge {
+    /// 新規にブリッジを配置したとき
+    pub fn new(id: Option<String>) -> Self {
+        debug("Bridge::new([id]) called.");
+        let bridge_id = id.unwrap_or_else(|| format!("bridge-{}", rand::thread_rng().gen_range(9..9999)));
+        Bridge {
+            state: Arc::new(Mutex::new(BridgeState {
+                id        : bridge_id,
+                port_a    : None,
+                port_b    : None,
+                mac_table : HashMap::new(),
+            })),
+        }
+    }
+
+    /// そのブリッジのIdを取得
+    pub fn get_id(&self) -> String {
+        let state = self.state.lock().unwrap();
+        state.id.clone()
+    }
+
+    /// ケーブルがつながっていないポートを取得。A、Bの順に探し、両方使われていればNone
+    pub fn free_port(&self) -> Option<BridgePort> {
+        let state = self.state.lock().unwrap();
+        [BridgePort::A, BridgePort::B].into_iter().find(|port| state.cable(*port).is_none())
+    }
+
+    /// 指定したポートにケーブルをつなぐ
+    /// ブリッジのIdでケーブルの端につなぎ、届いたフレームを受け取るcallbackを設定する
+    pub fn connect_cable(&self, port: BridgePort, cable: EthernetCable) -> Result<(), &'static str> {
+        let mut state = self.state.lock().unwrap();
+        if state.cable(port).is_some() {
+            return Err("Port is already in use");
+        }
+        let bridge_id = state.id.clone();
+
+        // ケーブルがcallbackを持ち、ブリッジがケーブルを持つので、循環参照にならないようにWeakで持つ
+        let bridge_state = Arc::downgrade(&self.state);
+        let callback: PhysicalLayerCallback = Arc::new(move |frame: PhysicalLayerFrame| {
+            Self::forward(&bridge_state, port, frame);
+        });
+        if !cable.attach(bridge_id, callback) {
+            return Err("Both endpoints of the cable are already in use");
+        }
+        match port {
+            BridgePort::A => state.port_a = Some(cable),
+            BridgePort::B => state.port_b = Some(cable),
+        }
+        Ok(())
+    }
+
+    /// in_portに届いたフレームの送信元を学習し、必要な場合だけ反対側に送る
+    fn forward(bridge_state: &Weak<Mutex<BridgeState>>, in_port: BridgePort, frame: PhysicalLayerFrame) {
+        let Some(bridge_state) = bridge_state.upgrade() else {
+            return;
+        };
+        let mut state = bridge_state.lock().unwrap();
+        let src_mac = frame.ethernet_frame.src_mac;
+        let dst_mac = frame.ethernet_frame.dst_mac;
+
+        // 送信元MACアドレスは、届いたポートの側にいると学習する
+        state.mac_table.insert(src_mac, in_port);
+
+        // 宛先が同じ側にいるなら、反対側に送る必要はない
+        if !dst_mac.is_multicast() && state.mac_table.get(&dst_mac) == Some(&in_port) {
+            debug(&format!("Bridge({})::frame within the same segment is filtered.", state.id));
+            return;
+        }
+        let out_port = in_port.opposite();
+        let Some(out_cable) = state.cable(out_port).clone() else {
+            return;
+        };
+        let bridge_id = state.id.clone();
+        // 送り先でまたこのブリッジにフレームが届くことがあるので、ロックを外してから送る
+        drop(state);
+        out_cable.transmit_signal(bridge_id, frame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer2::packets::EthernetFrame;
+
+    type Received = Arc<Mutex<Vec<PhysicalLayerFrame>>>;
+
+    /// 末尾のバイトだけが違うMACアドレス
+    fn mac(last: u8) -> MacAddress {
+        MacAddress::from_array([0x02, 0x00, 0x00, 0x00, 0x00, last])
+    }
+
+    /// 宛先と送信元を指定した物理層フレームを作る
+    fn frame(dst_mac: MacAddress, src_mac: MacAddress) -> PhysicalLayerFrame {
+        PhysicalLayerFrame::new(Some(EthernetFrame::new(Some(dst_mac), Some(src_mac), Some(0x0800), Some(vec![0; 46]))))
+    }
+
+    /// ポートA、Bにそれぞれ"segment-a", "segment-b"をつないだブリッジを作り、各ケーブルと届いたフレームの記録を返す
+    /// セグメント側のケーブルの端から送るフレームは、送信元MACアドレスを変えればそのセグメントにいる別々の機器から届いたことになる
+    fn bridge_with_segments() -> (Bridge, [EthernetCable; 2], [Received; 2]) {
+        let bridge = Bridge::new(Some("bridge-1".to_string()));
+        let mut cables = Vec::new();
+        let mut receiveds = Vec::new();
+        for (port, segment_id) in [(BridgePort::A, "segment-a"), (BridgePort::B, "segment-b")] {
+            let cable = EthernetCable::new(None);
+            cable.connect_endpoint1(Some(segment_id.to_string())).unwrap();
+            bridge.connect_cable(port, cable.clone()).unwrap();
+            let received = Received::default();
+            let record = received.clone();
+            cable.set_callback(segment_id.to_string(), Arc::new(move |frame| record.lock().unwrap().push(frame))).unwrap();
+            cables.push(cable);
+            receiveds.push(received);
+        }
+        (bridge, [cables[0].clone(), cables[1].clone()], [receiveds[0].clone(), receiveds[1].clone()])
+    }
+
+    #[test]
+    fn frame_within_the_same_segment_is_filtered() {
+        let (_bridge, cables, receiveds) = bridge_with_segments();
+        // mac(2)はA側にいると学習させる。宛先がまだわからないのでB側にも送られる
+        cables[0].transmit_signal("segment-a".to_string(), frame(mac(1), mac(2)));
+        assert_eq!(receiveds[1].lock().unwrap().len(), 1);
+
+        // A側のmac(1)からA側のmac(2)へのフレームは、B側に送らない
+        cables[0].transmit_signal("segment-a".to_string(), frame(mac(2), mac(1)));
+        assert_eq!(receiveds[1].lock().unwrap().len(), 1);
+        assert!(receiveds[0].lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn frame_across_segments_is_forwarded() {
+        let (bridge, cables, receiveds) = bridge_with_segments();
+        cables[1].transmit_signal("segment-b".to_string(), frame(MacAddress::get_broadcast_mac_addr(), mac(3)));
+        assert_eq!(receiveds[0].lock().unwrap().len(), 1);
+
+        cables[0].transmit_signal("segment-a".to_string(), frame(mac(3), mac(1)));
+        let forwarded = receiveds[1].lock().unwrap();
+        assert_eq!(forwarded.len(), 1);
+        assert_eq!(forwarded[0].ethernet_frame.src_mac, mac(1));
+        assert_eq!(forwarded[0].ethernet_frame.dst_mac, mac(3));
+
+        let state = bridge.state.lock().unwrap();
+        assert_eq!(state.mac_table.get(&mac(1)), Some(&BridgePort::A));
+        assert_eq!(state.mac_table.get(&mac(3)), Some(&BridgePort::B));
+    }
+}
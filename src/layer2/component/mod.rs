@@ -0,0 +1,7 @@
+pub(crate) mod bridge;
+pub(crate) mod nic;
+pub(crate) mod switch;
+
+pub use bridge::Bridge;
+pub use nic::NetworkInterface;
+pub use switch::Switch;
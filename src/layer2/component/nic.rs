@@ -0,0 +1,208 @@
+use std::{fmt, sync::{Arc, Mutex, Weak}};
+use rand::Rng;
+
+use crate::layer1::{component::ethernet_cable::debug, packets::PhysicalLayerFrame, receive_callback::PhysicalLayerCallback, EthernetCable};
+use crate::layer2::{address::MacAddress, packets::EthernetFrame};
+
+/// NICが受け取ったフレームを上位層に渡すcallback
+pub type EthernetFrameCallback = Arc<dyn Fn(EthernetFrame) + Send + Sync>;
+
+/// NetworkInterfaceの本体
+pub struct NetworkInterfaceState {
+    pub id               : String,
+    pub mac              : MacAddress,                    // このNICのMACアドレス
+    pub cable            : Option<EthernetCable>,         // つながっているケーブル
+    pub promiscuous      : bool,                          // trueなら宛先に関係なく全てのフレームを受け取る(プロミスキャスモード)
+    pub receive_callback : Option<EthernetFrameCallback>, // 受け取ったフレームを渡す上位層のcallback
+}
+
+impl fmt::Display for NetworkInterfaceState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cable_id = self.cable.as_ref().map(|cable| cable.get_id()).unwrap_or_else(|| "-".to_string());
+        writeln!(f, "###NetworkInterface= ")?;
+        writeln!(f, "#id                     : {}", self.id)?;
+        writeln!(f, "#mac                    : {}", self.mac.to_string_with_separator(':'))?;
+        writeln!(f, "#cable                  : {}", cable_id)?;
+        writeln!(f, "#promiscuous            : {}", self.promiscuous)?;
+        writeln!(f, "#receive_callback       : {}", self.receive_callback.is_some())?;
+        Ok(())
+    }
+}
+
+impl NetworkInterfaceState {
+    /// 宛先MACアドレスを見て、そのフレームを受け取るかどうか
+    /// 自分宛て、ブロードキャスト、マルチキャストのフレームだけを受け取る。プロミスキャスモードなら全て受け取る
+    fn accepts(&self, dst_mac: &MacAddress) -> bool {
+        self.promiscuous || *dst_mac == self.mac || dst_mac.is_broadcast() || dst_mac.is_multicast()
+    }
+}
+
+/// ネットワークインターフェースカード(NIC)
+/// ホストがケーブルにつながるための端点。自分のMACアドレスを持ち、届いたフレームを宛先MACアドレスで選り分けて上位層に渡す
+#[derive(Clone)]
+pub struct NetworkInterface {
+    state : Arc<Mutex<NetworkInterfaceState>>,
+}
+
+impl fmt::Display for NetworkInterface {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        write!(f, "{}", *state)
+    }
+}
+
+impl NetworkInterface {
+    /// 新規にNICを配置したとき
+    pub fn new(id: Option<String>, mac: MacAddress) -> Self {
+        debug("NetworkInterface::new([id], mac) called.");
+        let nic_id = id.unwrap_or_else(|| format!("nic-{}", rand::thread_rng().gen_range(9..9999)));
+        NetworkInterface {
+            state: Arc::new(Mutex::new(NetworkInterfaceState {
+                id               : nic_id,
+                mac,
+                cable            : None,
+                promiscuous      : false,
+                receive_callback : None,
+            })),
+        }
+    }
+
+    /// そのNICのIdを取得
+    pub fn get_id(&self) -> String {
+        let state = self.state.lock().unwrap();
+        state.id.clone()
+    }
+
+    /// そのNICのMACアドレスを取得
+    pub fn get_mac(&self) -> MacAddress {
+        let state = self.state.lock().unwrap();
+        state.mac
+    }
+
+    /// ケーブルがつながっているかどうか
+    pub fn is_connected(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.cable.is_some()
+    }
+
+    /// ケーブルをつなぐ
+    /// NICのIdでケーブルの端につなぎ、届いたフレームを受け取るcallbackを設定する
+    pub fn connect_cable(&self, cable: EthernetCable) -> Result<(), &'static str> {
+        let mut state = self.state.lock().unwrap();
+        if state.cable.is_some() {
+            return Err("Cable is already connected");
+        }
+        let nic_id = state.id.clone();
+
+        // ケーブルがcallbackを持ち、NICがケーブルを持つので、循環参照にならないようにWeakで持つ
+        let nic_state = Arc::downgrade(&self.state);
+        let callback: PhysicalLayerCallback = Arc::new(move |frame: PhysicalLayerFrame| {
+            Self::receive(&nic_state, frame.ethernet_frame);
+        });
+        if !cable.attach(nic_id, callback) {
+            return Err("Both endpoints of the cable are already in use");
+        }
+        state.cable = Some(cable);
+        Ok(())
+    }
+
+    /// プロミスキャスモードを設定する
+    /// trueの間は、自分宛てでないユニキャストのフレームも受け取る。パケットキャプチャを作る時などに使う
+    pub fn set_promiscuous(&self, on: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.promiscuous = on;
+    }
+
+    /// 受け取ったフレームを渡す上位層のcallbackを設定する
+    pub fn set_receive_callback(&self, callback: EthernetFrameCallback) {
+        let mut state = self.state.lock().unwrap();
+        state.receive_callback = Some(callback);
+    }
+
+    /// つながっているケーブルにフレームを送り出す
+    pub fn send(&self, frame: EthernetFrame) -> Result<(), &'static str> {
+        let state = self.state.lock().unwrap();
+        let Some(cable) = state.cable.clone() else {
+            return Err("Cable is not connected");
+        };
+        let nic_id = state.id.clone();
+        // 相手から返ってきたフレームがまたこのNICに届くことがあるので、ロックを外してから送る
+        drop(state);
+        cable.transmit_signal(nic_id, PhysicalLayerFrame::new(Some(frame)));
+        Ok(())
+    }
+
+    /// ケーブルから届いたフレームを、受け取るものだけ上位層に渡す
+    fn receive(nic_state: &Weak<Mutex<NetworkInterfaceState>>, frame: EthernetFrame) {
+        let Some(nic_state) = nic_state.upgrade() else {
+            return;
+        };
+        let state = nic_state.lock().unwrap();
+        if !state.accepts(&frame.dst_mac) {
+            // 自分宛てでないフレームは受け取らない
+            debug(&format!("NetworkInterface({})::frame to other host is filtered out.", state.id));
+            return;
+        }
+        let callback = state.receive_callback.clone();
+        // callbackの中でこのNICから送ることがあるので、ロックを外してから呼ぶ
+        drop(state);
+        if let Some(callback) = callback {
+            callback(frame);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Received = Arc<Mutex<Vec<EthernetFrame>>>;
+
+    const OWN_MAC: MacAddress = MacAddress([0x02, 0x00, 0x00, 0x00, 0x00, 0x01]);
+    const OTHER_MAC: MacAddress = MacAddress([0x02, 0x00, 0x00, 0x00, 0x00, 0x02]);
+
+    /// "nic-1"と"peer"をケーブルでつなぎ、NICが上位層に渡したフレームの記録を返す
+    fn connected_nic() -> (NetworkInterface, EthernetCable, Received) {
+        let nic = NetworkInterface::new(Some("nic-1".to_string()), OWN_MAC);
+        let cable = EthernetCable::new(Some("cable-1".to_string()));
+        cable.connect_endpoint2(Some("peer".to_string())).unwrap();
+        cable.set_callback("peer".to_string(), Arc::new(|_| {})).unwrap();
+        nic.connect_cable(cable.clone()).unwrap();
+        let received = Received::default();
+        let record = received.clone();
+        nic.set_receive_callback(Arc::new(move |frame| record.lock().unwrap().push(frame)));
+        (nic, cable, received)
+    }
+
+    /// peerからdst_mac宛てのフレームを送る
+    fn send_to(cable: &EthernetCable, dst_mac: MacAddress) {
+        let frame = EthernetFrame::new(Some(dst_mac), Some(OTHER_MAC), Some(0x0800), Some(vec![0; 46]));
+        cable.transmit_signal("peer".to_string(), PhysicalLayerFrame::new(Some(frame)));
+    }
+
+    #[test]
+    fn accepts_frames_to_its_own_mac() {
+        let (_nic, cable, received) = connected_nic();
+        send_to(&cable, OWN_MAC);
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn accepts_broadcast_and_multicast_frames() {
+        let (_nic, cable, received) = connected_nic();
+        send_to(&cable, MacAddress::get_broadcast_mac_addr());
+        send_to(&cable, MacAddress([0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB]));
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn filters_out_frames_to_other_hosts_unless_promiscuous() {
+        let (nic, cable, received) = connected_nic();
+        send_to(&cable, OTHER_MAC);
+        assert!(received.lock().unwrap().is_empty());
+
+        nic.set_promiscuous(true);
+        send_to(&cable, OTHER_MAC);
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,407 @@
+use std::{collections::HashMap, fmt, sync::{Arc, Mutex, Weak}};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::layer1::{component::ethernet_cable::debug, packets::PhysicalLayerFrame, receive_callback::PhysicalLayerCallback, EthernetCable};
+use crate::layer2::address::MacAddress;
+use crate::util::{browser_clock, Clock};
+
+/// スイッチのポート番号
+pub type PortId = usize;
+
+/// MACアドレステーブルで学習した内容
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LearnedPort {
+    pub port         : PortId, // そのMACアドレスの機器がつながっているポート
+    pub last_seen_ms : f64,    // 最後にそのMACアドレスからフレームが届いた時刻(ミリ秒)
+}
+
+/// MACアドレステーブルの1行
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MacTableEntry {
+    pub mac  : String, // 学習したMACアドレス
+    pub port : PortId, // そのMACアドレスの機器がつながっているポート
+}
+
+/// スイッチで起きたことを通知するイベント
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwitchEvent {
+    StormControlDrop { port: PortId }, // ブロードキャスト/マルチキャストがレート制限を超えたので、portに届いたフレームを破棄した
+}
+
+/// スイッチのイベントを受け取るcallback
+pub type SwitchEventCallback = Arc<dyn Fn(SwitchEvent) + Send + Sync>;
+
+/// ストームコントロールで、1秒ごとにポートに届いたブロードキャスト/マルチキャストを数える
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BroadcastWindow {
+    pub started_at_ms : f64, // 数え始めた時刻(ミリ秒)
+    pub count         : u32, // 数え始めてから届いたブロードキャスト/マルチキャストの数
+}
+
+/// Switchの本体
+pub struct SwitchState {
+    pub id                   : String,
+    pub ports                : Vec<Option<EthernetCable>>,       // ポートごとにつながっているケーブル
+    pub mac_table            : HashMap<MacAddress, LearnedPort>, // 送信元MACアドレスから学習したMACアドレステーブル
+    pub aging_time_secs      : u32,                              // この秒数の間フレームが届かなかったMACアドレスはテーブルから消す。0なら消さない
+    pub broadcast_rate_limit : Option<u32>,                      // 1つのポートが1秒間に受け取るブロードキャスト/マルチキャストの上限。Noneなら制限しない
+    pub broadcast_windows    : Vec<BroadcastWindow>,             // ポートごとのブロードキャスト/マルチキャストの数
+    pub event_callback       : Option<SwitchEventCallback>,
+    clock                    : Clock,                            // エージングとストームコントロールに使う時計
+}
+
+impl fmt::Display for SwitchState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "###Switch= ")?;
+        writeln!(f, "#id                     : {}", self.id)?;
+        writeln!(f, "#ports                  : {}", self.ports.len())?;
+        for (port, cable) in self.ports.iter().enumerate() {
+            let cable_id = cable.as_ref().map(|cable| cable.get_id()).unwrap_or_else(|| "-".to_string());
+            writeln!(f, "#  port {:<3}             : {}", port, cable_id)?;
+        }
+        writeln!(f, "#aging_time_secs        : {}", self.aging_time_secs)?;
+        let rate_limit = self.broadcast_rate_limit.map(|pps| format!("{} pps", pps)).unwrap_or_else(|| "off".to_string());
+        writeln!(f, "#broadcast_rate_limit   : {}", rate_limit)?;
+        writeln!(f, "#mac_table              : {}", self.mac_table.len())?;
+        for entry in self.mac_table_entries() {
+            writeln!(f, "#  {}     : port {}", entry.mac, entry.port)?;
+        }
+        Ok(())
+    }
+}
+
+impl SwitchState {
+    /// MACアドレステーブルをポート番号、MACアドレスの順に並べて取得
+    fn mac_table_entries(&self) -> Vec<MacTableEntry> {
+        let mut learned: Vec<(PortId, MacAddress)> = self.mac_table
+            .iter()
+            .map(|(mac, learned)| (learned.port, *mac))
+            .collect();
+        learned.sort();
+        learned
+            .into_iter()
+            .map(|(port, mac)| MacTableEntry {
+                mac  : mac.to_string_with_separator(':'),
+                port,
+            })
+            .collect()
+    }
+
+    /// ストームコントロールでin_portに届いたブロードキャスト/マルチキャストを数え、上限を超えたらtrueを返す
+    /// 1秒ごとに数え直す
+    fn exceeds_broadcast_rate(&mut self, in_port: PortId, now_ms: f64) -> bool {
+        let Some(limit) = self.broadcast_rate_limit else {
+            return false;
+        };
+        let window = &mut self.broadcast_windows[in_port];
+        if now_ms - window.started_at_ms >= 1000.0 {
+            *window = BroadcastWindow { started_at_ms: now_ms, count: 0 };
+        }
+        window.count = window.count.saturating_add(1);
+        window.count > limit
+    }
+}
+
+/// ラーニングスイッチ
+/// ポートに届いたフレームの送信元MACアドレスを学習し、
+/// 宛先MACアドレスを学習済みならそのポートだけに、未学習かブロードキャストなら他の全てのポートに送り出す(フラッディング)
+#[derive(Clone)]
+pub struct Switch {
+    state : Arc<Mutex<SwitchState>>,
+}
+
+impl fmt::Display for Switch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        write!(f, "{}", *state)
+    }
+}
+
+impl Switch {
+    /// MACアドレステーブルのエージングタイムの初期値(秒)
+    pub const DEFAULT_AGING_TIME_SECS: u32 = 300;
+
+    /// 新規にスイッチを配置したとき
+    pub fn new(id: Option<String>, port_count: usize) -> Self {
+        debug("Switch::new([id], port_count) called.");
+        let switch_id = id.unwrap_or_else(|| format!("switch-{}", rand::thread_rng().gen_range(9..9999)));
+        Switch {
+            state: Arc::new(Mutex::new(SwitchState {
+                id                   : switch_id,
+                ports                : vec![None; port_count],
+                mac_table            : HashMap::new(),
+                aging_time_secs      : Self::DEFAULT_AGING_TIME_SECS,
+                broadcast_rate_limit : None,
+                broadcast_windows    : vec![BroadcastWindow::default(); port_count],
+                event_callback       : None,
+                clock                : browser_clock(),
+            })),
+        }
+    }
+
+    /// そのスイッチのIdを取得
+    pub fn get_id(&self) -> String {
+        let state = self.state.lock().unwrap();
+        state.id.clone()
+    }
+
+    /// ポートの数を取得
+    pub fn get_port_count(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.ports.len()
+    }
+
+    /// ケーブルがつながっていない最初のポートの番号を取得
+    /// 全てのポートが使われていればNone
+    pub fn free_port(&self) -> Option<PortId> {
+        let state = self.state.lock().unwrap();
+        state.ports.iter().position(|port| port.is_none())
+    }
+
+    /// 指定したポートにケーブルをつなぐ
+    /// スイッチのIdでケーブルの端につなぎ、届いたフレームを受け取るcallbackを設定する
+    pub fn connect_cable(&self, port: PortId, cable: EthernetCable) -> Result<(), &'static str> {
+        let mut state = self.state.lock().unwrap();
+        match state.ports.get(port) {
+            None => return Err("Port number is out of range"),
+            Some(Some(_)) => return Err("Port is already in use"),
+            Some(None) => {}
+        }
+        let switch_id = state.id.clone();
+
+        // ケーブルがcallbackを持ち、スイッチがケーブルを持つので、循環参照にならないようにWeakで持つ
+        let switch_state = Arc::downgrade(&self.state);
+        let callback: PhysicalLayerCallback = Arc::new(move |frame: PhysicalLayerFrame| {
+            Self::forward(&switch_state, port, frame);
+        });
+        if !cable.attach(switch_id, callback) {
+            return Err("Both endpoints of the cable are already in use");
+        }
+        state.ports[port] = Some(cable);
+        Ok(())
+    }
+
+    /// MACアドレステーブルのエージングタイム(秒)を設定する
+    /// 0にするとテーブルから消さなくなる
+    pub fn set_aging_time(&self, secs: u32) {
+        let mut state = self.state.lock().unwrap();
+        state.aging_time_secs = secs;
+    }
+
+    /// ストームコントロールの上限を設定する
+    /// 1つのポートに1秒間にpps個を超えて届いたブロードキャスト/マルチキャストのフレームは転送せずに破棄し、
+    /// StormControlDropイベントで通知する。ユニキャストのフレームは制限しない。0にすると制限をなくす
+    pub fn set_broadcast_rate_limit(&self, pps: u32) {
+        let mut state = self.state.lock().unwrap();
+        state.broadcast_rate_limit = if pps == 0 { None } else { Some(pps) };
+        let port_count = state.ports.len();
+        state.broadcast_windows = vec![BroadcastWindow::default(); port_count];
+    }
+
+    /// ストームコントロールなどのイベントを受け取るcallbackをsetする
+    pub fn set_event_callback(&self, callback: SwitchEventCallback) {
+        let mut state = self.state.lock().unwrap();
+        state.event_callback = Some(callback);
+    }
+
+    /// エージングタイムより長くフレームが届いていないMACアドレスをテーブルから消す
+    /// 
+    /// ### 戻り値
+    /// * 消したMACアドレスの数
+    pub fn age_entries(&self) -> usize {
+        let mut state = self.state.lock().unwrap();
+        if state.aging_time_secs == 0 {
+            return 0;
+        }
+        let now_ms = (state.clock)();
+        let aging_time_ms = state.aging_time_secs as f64 * 1000.0;
+        let entry_count = state.mac_table.len();
+        state.mac_table.retain(|_, learned| now_ms - learned.last_seen_ms < aging_time_ms);
+        entry_count - state.mac_table.len()
+    }
+
+    /// 学習したMACアドレステーブルを取得
+    pub fn get_mac_table(&self) -> Vec<MacTableEntry> {
+        let state = self.state.lock().unwrap();
+        state.mac_table_entries()
+    }
+
+    /// in_portに届いたフレームの送信元を学習し、宛先に応じて送り出す
+    fn forward(switch_state: &Weak<Mutex<SwitchState>>, in_port: PortId, frame: PhysicalLayerFrame) {
+        let Some(switch_state) = switch_state.upgrade() else {
+            return;
+        };
+        let mut state = switch_state.lock().unwrap();
+        let src_mac = frame.ethernet_frame.src_mac;
+        let dst_mac = frame.ethernet_frame.dst_mac;
+
+        // 送信元MACアドレスは、届いたポートの先にいると学習する
+        // マルチキャスト/ブロードキャストのアドレスはどこか1つのポートの先にいるわけではないので学習しない
+        let last_seen_ms = (state.clock)();
+        if !src_mac.is_multicast() {
+            state.mac_table.insert(src_mac, LearnedPort { port: in_port, last_seen_ms });
+        }
+
+        // ブロードキャスト/マルチキャストが上限を超えて届いたら、フラッディングせずに破棄する
+        if dst_mac.is_multicast() && state.exceeds_broadcast_rate(in_port, last_seen_ms) {
+            debug(&format!("Switch({})::storm control dropped a frame on port {}.", state.id, in_port));
+            let event_callback = state.event_callback.clone();
+            drop(state);
+            if let Some(callback) = event_callback {
+                callback(SwitchEvent::StormControlDrop { port: in_port });
+            }
+            return;
+        }
+
+        let flood = || (0..state.ports.len()).filter(|&port| port != in_port).collect();
+        let out_ports: Vec<PortId> = if dst_mac.is_multicast() {
+            // マルチキャスト/ブロードキャストは、テーブルを見ずに届いたポート以外の全てのポートに送り出す
+            flood()
+        } else {
+            match state.mac_table.get(&dst_mac).map(|learned| learned.port) {
+                // 宛先が届いたポートと同じ側にいるなら、送り出す必要はない
+                Some(port) if port == in_port => Vec::new(),
+                Some(port) => vec![port],
+                // 未学習の宛先は、届いたポート以外の全てのポートに送り出す
+                None => flood(),
+            }
+        };
+        let switch_id = state.id.clone();
+        let out_cables: Vec<EthernetCable> = out_ports
+            .iter()
+            .filter_map(|&port| state.ports[port].clone())
+            .collect();
+        // 送り先でまたこのスイッチにフレームが届くことがあるので、ロックを外してから送る
+        drop(state);
+        for cable in out_cables {
+            cable.transmit_signal(switch_id.clone(), frame.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer2::packets::EthernetFrame;
+
+    type Received = Arc<Mutex<Vec<PhysicalLayerFrame>>>;
+
+    /// 末尾のバイトだけが違うMACアドレス
+    fn mac(last: u8) -> MacAddress {
+        MacAddress::from_array([0x02, 0x00, 0x00, 0x00, 0x00, last])
+    }
+
+    /// 宛先と送信元を指定した物理層フレームを作る
+    fn frame(dst_mac: MacAddress, src_mac: MacAddress) -> PhysicalLayerFrame {
+        PhysicalLayerFrame::new(Some(EthernetFrame::new(Some(dst_mac), Some(src_mac), Some(0x0800), Some(vec![0; 46]))))
+    }
+
+    /// 時計を止めたスイッチに"pc-0", "pc-1", ...をつなぎ、それぞれのケーブルと届いたフレームの記録を返す
+    fn switch_with_hosts(host_count: usize) -> (Switch, Vec<EthernetCable>, Vec<Received>) {
+        let switch = Switch::new(Some("switch-1".to_string()), host_count);
+        switch.state.lock().unwrap().clock = Arc::new(|| 0.0);
+        let mut cables = Vec::new();
+        let mut receiveds = Vec::new();
+        for port in 0..host_count {
+            let host_id = format!("pc-{}", port);
+            let cable = EthernetCable::new(Some(format!("cable-{}", port)));
+            cable.connect_endpoint1(Some(host_id.clone())).unwrap();
+            switch.connect_cable(port, cable.clone()).unwrap();
+            let received = Received::default();
+            let record = received.clone();
+            cable.set_callback(host_id, Arc::new(move |frame| record.lock().unwrap().push(frame))).unwrap();
+            cables.push(cable);
+            receiveds.push(received);
+        }
+        (switch, cables, receiveds)
+    }
+
+    #[test]
+    fn storm_control_drops_excess_broadcasts_but_not_unicast() {
+        let (switch, cables, receiveds) = switch_with_hosts(3);
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let record = events.clone();
+        switch.set_event_callback(Arc::new(move |event| record.lock().unwrap().push(event)));
+        switch.set_broadcast_rate_limit(5);
+
+        // pc-1を学習させておく
+        cables[1].transmit_signal("pc-1".to_string(), frame(mac(0), mac(1)));
+        receiveds[0].lock().unwrap().clear();
+        receiveds[2].lock().unwrap().clear();
+
+        for _ in 0..20 {
+            cables[0].transmit_signal("pc-0".to_string(), frame(MacAddress::get_broadcast_mac_addr(), mac(0)));
+        }
+        assert_eq!(receiveds[1].lock().unwrap().len(), 5);
+        assert_eq!(receiveds[2].lock().unwrap().len(), 5);
+        assert_eq!(events.lock().unwrap().len(), 15);
+        assert!(events.lock().unwrap().iter().all(|event| *event == SwitchEvent::StormControlDrop { port: 0 }));
+
+        // 上限に達したポートからでもユニキャストは転送される
+        for _ in 0..20 {
+            cables[0].transmit_signal("pc-0".to_string(), frame(mac(1), mac(0)));
+        }
+        assert_eq!(receiveds[1].lock().unwrap().len(), 25);
+        assert_eq!(receiveds[2].lock().unwrap().len(), 5);
+        assert_eq!(events.lock().unwrap().len(), 15);
+
+        // 1秒経てば数え直す
+        switch.state.lock().unwrap().clock = Arc::new(|| 1000.0);
+        cables[0].transmit_signal("pc-0".to_string(), frame(MacAddress::get_broadcast_mac_addr(), mac(0)));
+        assert_eq!(receiveds[2].lock().unwrap().len(), 6);
+    }
+
+    #[test]
+    fn unknown_destinations_are_flooded() {
+        let (switch, cables, receiveds) = switch_with_hosts(3);
+        cables[0].transmit_signal("pc-0".to_string(), frame(mac(9), mac(0)));
+
+        assert!(receiveds[0].lock().unwrap().is_empty());
+        assert_eq!(receiveds[1].lock().unwrap().len(), 1);
+        assert_eq!(receiveds[2].lock().unwrap().len(), 1);
+        assert_eq!(switch.get_mac_table(), vec![MacTableEntry { mac: mac(0).to_string_with_separator(':'), port: 0 }]);
+    }
+
+    #[test]
+    fn learned_destinations_are_delivered_to_their_port_only() {
+        let (_switch, cables, receiveds) = switch_with_hosts(3);
+        cables[2].transmit_signal("pc-2".to_string(), frame(mac(0), mac(2)));
+        receiveds.iter().for_each(|received| received.lock().unwrap().clear());
+
+        cables[0].transmit_signal("pc-0".to_string(), frame(mac(2), mac(0)));
+        assert!(receiveds[1].lock().unwrap().is_empty());
+        assert_eq!(receiveds[2].lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn multicast_is_flooded_and_never_learned() {
+        let (switch, cables, receiveds) = switch_with_hosts(3);
+        let multicast = MacAddress::from_array([0x01, 0x00, 0x5E, 0x00, 0x00, 0x01]);
+        // マルチキャストのアドレスを送信元にしたフレームが届いても学習しない
+        cables[1].transmit_signal("pc-1".to_string(), frame(mac(0), multicast));
+        assert!(switch.get_mac_table().is_empty());
+        receiveds.iter().for_each(|received| received.lock().unwrap().clear());
+
+        cables[0].transmit_signal("pc-0".to_string(), frame(multicast, mac(0)));
+        assert_eq!(receiveds[1].lock().unwrap().len(), 1);
+        assert_eq!(receiveds[2].lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn entries_older_than_the_aging_time_are_purged() {
+        let (switch, cables, _) = switch_with_hosts(2);
+        assert_eq!(switch.state.lock().unwrap().aging_time_secs, Switch::DEFAULT_AGING_TIME_SECS);
+        switch.set_aging_time(10);
+        cables[0].transmit_signal("pc-0".to_string(), frame(mac(1), mac(0)));
+        assert_eq!(switch.get_mac_table().len(), 1);
+
+        switch.state.lock().unwrap().clock = Arc::new(|| 9_999.0);
+        assert_eq!(switch.age_entries(), 0);
+        assert_eq!(switch.get_mac_table().len(), 1);
+
+        switch.state.lock().unwrap().clock = Arc::new(|| 10_000.0);
+        assert_eq!(switch.age_entries(), 1);
+        assert!(switch.get_mac_table().is_empty());
+    }
+}
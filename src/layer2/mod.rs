@@ -1,5 +1,7 @@
 pub(crate) mod address;
+pub(crate) mod component;
 pub(crate) mod packets;
+pub(crate) mod protocols;
 
 pub use address::MacAddress;
 pub use packets::EthernetFrame;
@@ -0,0 +1,86 @@
+use std::fmt;
+
+/// イーサネットフレームのイーサタイプ
+/// フレームに格納しているのはu16のままで、表示や判定をする時にこの型に変換して使う
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EtherType {
+    IPv4,         // 0x0800
+    Arp,          // 0x0806
+    IPv6,         // 0x86DD
+    Vlan,         // 0x8100 (802.1Q)
+    Unknown(u16), // 上記以外
+}
+
+impl fmt::Display for EtherType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl From<u16> for EtherType {
+    fn from(value: u16) -> Self {
+        Self::from_u16(value)
+    }
+}
+
+impl From<EtherType> for u16 {
+    fn from(ethertype: EtherType) -> Self {
+        ethertype.to_u16()
+    }
+}
+
+impl EtherType {
+    /// u16の値から変換する。知らない値はUnknownになる
+    pub fn from_u16(value: u16) -> EtherType {
+        match value {
+            0x0800 => EtherType::IPv4,
+            0x0806 => EtherType::Arp,
+            0x86DD => EtherType::IPv6,
+            0x8100 => EtherType::Vlan,
+            other => EtherType::Unknown(other),
+        }
+    }
+
+    /// u16の値に変換する
+    pub fn to_u16(self) -> u16 {
+        match self {
+            EtherType::IPv4 => 0x0800,
+            EtherType::Arp => 0x0806,
+            EtherType::IPv6 => 0x86DD,
+            EtherType::Vlan => 0x8100,
+            EtherType::Unknown(value) => value,
+        }
+    }
+
+    /// 表示用の名前 (例: "IPv4", "ARP")
+    pub fn name(&self) -> &'static str {
+        match self {
+            EtherType::IPv4 => "IPv4",
+            EtherType::Arp => "ARP",
+            EtherType::IPv6 => "IPv6",
+            EtherType::Vlan => "802.1Q",
+            EtherType::Unknown(_) => "Unknown",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_values_round_trip_with_names() {
+        for (value, name) in [(0x0800, "IPv4"), (0x0806, "ARP"), (0x86DD, "IPv6"), (0x8100, "802.1Q")] {
+            let ethertype = EtherType::from_u16(value);
+            assert_eq!(ethertype.to_u16(), value);
+            assert_eq!(ethertype.name(), name);
+        }
+    }
+
+    #[test]
+    fn unknown_values_are_kept() {
+        assert_eq!(EtherType::from(0x88CC), EtherType::Unknown(0x88CC));
+        assert_eq!(u16::from(EtherType::Unknown(0x88CC)), 0x88CC);
+        assert_eq!(EtherType::Unknown(0x88CC).name(), "Unknown");
+    }
+}
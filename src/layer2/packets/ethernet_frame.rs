@@ -1,35 +1,54 @@
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::{fmt, hash::{Hash, Hasher}};
 
 use crate::layer2::address::mac_address::MacAddress;
+use crate::layer2::packets::{EtherType, EthernetFrameBuilder};
+use crate::layer2::packets::VlanTag;
+use crate::util::crc32;
 
-#[derive(Clone, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// イーサネットフレーム
+/// Default::default()はMACアドレスが全て0、イーサタイプが0x0000の空のフレームになる
+/// そのまま送れるフレームが欲しい時はnew_broadcastかnewを使う
+/// 比較とハッシュはjumboを見ない
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct EthernetFrame {
     pub dst_mac: MacAddress,  // 宛先MACアドレス (6バイト)
     pub src_mac: MacAddress,  // 送信元MACアドレス (6バイト)
-    pub ethertype: u16,       // イーサータイプ (2バイト)
+    pub outer_vlan: Option<VlanTag>, // 802.1ad(QinQ)の外側のサービスタグ (あれば4バイト)
+    pub vlan: Option<VlanTag>, // 802.1QのVLANタグ。QinQでは内側のカスタマータグ (あれば4バイト)
+    pub ethertype: u16,       // イーサータイプ (2バイト) バイト列ではビッグエンディアン(ネットワークバイトオーダー)
     pub data: Vec<u8>,        // データリンク層のペイロード
+    #[serde(skip)]
+    pub jumbo: bool,          // trueならジャンボフレーム(ペイロード9000バイトまで)を許す。バイト列には含まれない
+}
+
+impl PartialEq for EthernetFrame {
+    fn eq(&self, other: &Self) -> bool {
+        self.dst_mac == other.dst_mac
+            && self.src_mac == other.src_mac
+            && self.outer_vlan == other.outer_vlan
+            && self.vlan == other.vlan
+            && self.ethertype == other.ethertype
+            && self.data == other.data
+    }
+}
+
+impl Eq for EthernetFrame {}
+
+impl Hash for EthernetFrame {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.dst_mac.hash(state);
+        self.src_mac.hash(state);
+        self.outer_vlan.hash(state);
+        self.vlan.hash(state);
+        self.ethertype.hash(state);
+        self.data.hash(state);
+    }
 }
 
 impl fmt::Display for EthernetFrame {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let hex_bytes: Vec<String> = self
-            .data
-            .iter()
-            .map(|byte| format!("{:02X}", byte))
-            .collect();
-        let formatted_data = hex_bytes.join(" ");
-        write!(
-            f,
-            "#dst_mac     : {}\n\
-             #src_mac     : {}\n\
-             #ethertype   : {:04X}\n\
-             #data        : [{}]\n",
-            self.dst_mac,
-            self.src_mac,
-            self.ethertype,
-            formatted_data,
-        )
+        self.write_frame(f, self.data.len())
     }
 }
 
@@ -43,11 +62,26 @@ impl EthernetFrame {
         Self {
             dst_mac: dst_mac.unwrap_or_else(|| MacAddress::get_broadcast_mac_addr()),
             src_mac: src_mac.unwrap_or_else(|| MacAddress::new()),
+            outer_vlan: None,
+            vlan: None,
             ethertype: ethertype.unwrap_or(0x0800), // デフォルトはIPv4
             data: data.unwrap_or_default(),
+            jumbo: false,
         }
     }
 
+    /// ブロードキャストのフレームを生成する
+    /// 宛先はブロードキャストアドレス(FF:FF:FF:FF:FF:FF)、送信元はランダムなローカル管理アドレスになる
+    /// ARPの要求のように、相手のMACアドレスがわからない時に送るフレームを作るのに使う
+    pub fn new_broadcast(ethertype: u16, data: Vec<u8>) -> Self {
+        Self::new(Some(MacAddress::get_broadcast_mac_addr()), None, Some(ethertype), Some(data))
+    }
+
+    /// フィールドを1つずつ指定してフレームを組み立てるビルダーを取得する
+    pub fn builder() -> EthernetFrameBuilder {
+        EthernetFrameBuilder::new()
+    }
+
     /// 指定したデータからフレームを生成する
     pub fn from_raw(
         dst_mac: [u8; 6],
@@ -58,13 +92,520 @@ impl EthernetFrame {
         Self {
             dst_mac: MacAddress(dst_mac),
             src_mac: MacAddress(src_mac),
+            outer_vlan: None,
+            vlan: None,
             ethertype,
             data,
+            jumbo: false,
         }
     }
+    /// VLANタグを付けたフレームを返す
+    /// vidは12ビット(0〜4095)、pcpは3ビット(0〜7)に収まらない場合はエラー
+    pub fn with_vlan(mut self, vid: u16, pcp: u8) -> Result<Self, &'static str> {
+        self.vlan = Some(VlanTag::new(vid, pcp)?);
+        Ok(self)
+    }
+
+    /// 802.1ad(QinQ)の2重のVLANタグを付けたフレームを返す
+    /// 外側にs_vidのサービスタグ(TPID 0x88A8)、内側にc_vidのカスタマータグ(TPID 0x8100)を付ける。優先度はどちらも0
+    /// s_vid、c_vidが12ビット(0〜4095)に収まらない場合はエラー
+    pub fn with_qinq(mut self, s_vid: u16, c_vid: u16) -> Result<Self, &'static str> {
+        let service_tag = VlanTag { tpid: VlanTag::TPID_8021AD, ..VlanTag::new(s_vid, 0)? };
+        self.vlan = Some(VlanTag::new(c_vid, 0)?);
+        self.outer_vlan = Some(service_tag);
+        Ok(self)
+    }
+
+    /// ペイロードを置き換える
+    /// フレームを作り直さずに中身だけ書き換えたい時に使う
+    pub fn set_payload(&mut self, data: Vec<u8>) {
+        self.data = data;
+    }
+
+    /// ペイロードの末尾にバイト列を追加する
+    pub fn append_payload(&mut self, more: &[u8]) {
+        self.data.extend_from_slice(more);
+    }
+
     /// フレーム全体のバイト長を計算する
     pub fn total_length(&self) -> usize {
-        14 + self.data.len() // 14バイト(=dst_mac+src_mac+ethertype) + ペイロード長
+        let vlan_length = [self.outer_vlan, self.vlan].iter().flatten().count() * VlanTag::LENGTH;
+        14 + vlan_length + self.data.len() // 14バイト(=dst_mac+src_mac+ethertype) + VLANタグ + ペイロード長
+    }
+
+    /// Ethernetの最小フレーム長(FCSを除く)
+    pub const MIN_LENGTH: usize = 60;
+
+    /// フレーム長が最小フレーム長に満たないかを確認する
+    pub fn needs_padding(&self) -> bool {
+        self.total_length() < Self::MIN_LENGTH
+    }
+
+    /// pad_to_minimumで追加される0のバイト数
+    /// フレームは変更しない。すでに最小フレーム長以上あれば0
+    pub fn padding_needed(&self) -> usize {
+        Self::MIN_LENGTH.saturating_sub(self.total_length())
+    }
+
+    /// フレーム長が最小フレーム長(60バイト)になるまで、ペイロードの末尾に0を追加する
+    /// すでに60バイト以上あれば何もしないので、何度呼んでも結果は同じ
+    pub fn pad_to_minimum(&mut self) {
+        let padding = self.padding_needed();
+        self.data.resize(self.data.len() + padding, 0);
+    }
+
+    /// 標準のEthernetの最大ペイロード長(MTU)
+    pub const MAX_PAYLOAD: usize = 1500;
+    /// ジャンボフレームを許す場合の最大ペイロード長
+    pub const JUMBO_MAX_PAYLOAD: usize = 9000;
+
+    /// ジャンボフレームを許すかどうかを設定する
+    /// trueにすると、validateで許すペイロード長の上限が1500バイトから9000バイトになる
+    pub fn set_jumbo(&mut self, allow: bool) {
+        self.jumbo = allow;
+    }
+
+    /// フレームの長さが正しいかを確認する
+    /// 最小フレーム長(60バイト。VLANタグがなければペイロード46バイト)に満たなければrunt、
+    /// ペイロードが上限(1500バイト。ジャンボフレームを許すなら9000バイト)を超えていればoversizedのエラー
+    /// pad_to_minimumで埋めた後のフレームはruntにならない
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.needs_padding() {
+            return Err("Runt frame: shorter than the minimum frame length");
+        }
+        let max_payload = if self.jumbo { Self::JUMBO_MAX_PAYLOAD } else { Self::MAX_PAYLOAD };
+        if self.data.len() > max_payload {
+            return Err("Oversized frame: payload exceeds the maximum length");
+        }
+        Ok(())
+    }
+
+    /// バイト配列に変換（宛先MAC + 送信元MAC + [外側のVLANタグ] + [VLANタグ] + イーサタイプ + データ）
+    /// ethertypeはネットワークバイトオーダー(ビッグエンディアン)で書き込む
+    /// FCSは含まない
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.total_length());
+        bytes.extend_from_slice(&self.dst_mac.to_array());
+        bytes.extend_from_slice(&self.src_mac.to_array());
+        if let Some(outer_vlan) = self.outer_vlan {
+            bytes.extend_from_slice(&outer_vlan.to_bytes());
+        }
+        if let Some(vlan) = self.vlan {
+            bytes.extend_from_slice(&vlan.to_bytes());
+        }
+        bytes.extend_from_slice(&self.ethertype.to_be_bytes());
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+
+    /// バイト配列からフレームを生成する
+    /// 先頭から 宛先MAC(6バイト) + 送信元MAC(6バイト) + イーサタイプ(2バイト、ビッグエンディアン) として読み取り、
+    /// 残りをすべてペイロードとする。FCSは含まないものとして扱う
+    /// 送信元MACの後ろが0x8100の場合は802.1QのVLANタグとして読み取る
+    /// 0x88A8の場合は802.1ad(QinQ)のサービスタグとして読み取り、その後ろに0x8100のカスタマータグが続くものとする
+    pub fn from_bytes(bytes: &[u8]) -> Result<EthernetFrame, &'static str> {
+        if bytes.len() < 14 {
+            return Err("Ethernet frame must be at least 14 bytes");
+        }
+
+        let mut dst_mac = [0u8; 6];
+        let mut src_mac = [0u8; 6];
+        dst_mac.copy_from_slice(&bytes[0..6]);
+        src_mac.copy_from_slice(&bytes[6..12]);
+        let mut ethertype = u16::from_be_bytes([bytes[12], bytes[13]]);
+        let mut payload_start = 14;
+
+        let mut outer_vlan = None;
+        if ethertype == VlanTag::TPID_8021AD {
+            if bytes.len() < 22 {
+                return Err("QinQ tagged Ethernet frame must be at least 22 bytes");
+            }
+            let tci = u16::from_be_bytes([bytes[14], bytes[15]]);
+            outer_vlan = Some(VlanTag::from_tpid_tci(ethertype, tci));
+            ethertype = u16::from_be_bytes([bytes[16], bytes[17]]);
+            if ethertype != VlanTag::TPID_8021Q {
+                return Err("QinQ tagged Ethernet frame must have an inner 802.1Q tag");
+            }
+            payload_start = 18;
+        }
+
+        let mut vlan = None;
+        if ethertype == VlanTag::TPID_8021Q {
+            if bytes.len() < payload_start + 4 {
+                return Err("VLAN tagged Ethernet frame must be at least 18 bytes");
+            }
+            let tci = u16::from_be_bytes([bytes[payload_start], bytes[payload_start + 1]]);
+            vlan = Some(VlanTag::from_tpid_tci(ethertype, tci));
+            ethertype = u16::from_be_bytes([bytes[payload_start + 2], bytes[payload_start + 3]]);
+            payload_start += 4;
+        }
+
+        let mut frame = Self::from_raw(dst_mac, src_mac, ethertype, bytes[payload_start..].to_vec());
+        frame.outer_vlan = outer_vlan;
+        frame.vlan = vlan;
+        Ok(frame)
+    }
+
+    /// FCS(Frame Check Sequence)を計算する
+    /// 宛先MAC + 送信元MAC + [外側のVLANタグ] + [VLANタグ] + イーサタイプ + データに対するIEEE 802.3のCRC-32
+    pub fn compute_fcs(&self) -> u32 {
+        crc32(&self.to_bytes())
+    }
+
+    /// FCSを末尾に付けたバイト配列に変換
+    /// FCSはビッグエンディアンの4バイトで書き込む
+    pub fn to_bytes_with_fcs(&self) -> Vec<u8> {
+        let mut bytes = self.to_bytes();
+        let fcs = crc32(&bytes);
+        bytes.extend_from_slice(&fcs.to_be_bytes());
+        bytes
+    }
+
+    /// 末尾4バイトのFCSが、それより前のバイト列のCRC-32と一致するかを確認する
+    /// to_bytes_with_fcsで作ったバイト列ならtrueになる
+    /// 4バイトに満たない場合はfalse
+    pub fn verify_fcs(bytes: &[u8]) -> bool {
+        if bytes.len() < 4 {
+            return false;
+        }
+        let (body, fcs) = bytes.split_at(bytes.len() - 4);
+        crc32(body) == u32::from_be_bytes([fcs[0], fcs[1], fcs[2], fcs[3]])
+    }
+
+    /// イーサタイプを列挙型として取得する
+    pub fn ethertype_kind(&self) -> EtherType {
+        EtherType::from_u16(self.ethertype)
+    }
+
+    /// ARPフレームとして最低限正しい形をしているかを確認する
+    /// ethertypeが0x0806で、ペイロードが28バイト以上あり、
+    /// ハードウェアタイプが1(Ethernet)、プロトコルタイプが0x0800(IPv4)、
+    /// ハードウェアアドレス長/プロトコルアドレス長が6/4であればtrue
+    pub fn is_valid_arp(&self) -> bool {
+        if self.ethertype_kind() != EtherType::Arp || self.data.len() < 28 {
+            return false;
+        }
+        let hardware_type = u16::from_be_bytes([self.data[0], self.data[1]]);
+        let protocol_type = u16::from_be_bytes([self.data[2], self.data[3]]);
+        let hardware_len = self.data[4];
+        let protocol_len = self.data[5];
+
+        hardware_type == 1 && protocol_type == 0x0800 && hardware_len == 6 && protocol_len == 4
+    }
+
+    /// ペイロードを先頭のmax_bytesバイトだけ表示する文字列に変換
+    /// ジャンボフレームのような大きなフレームを表示する時に使う
+    /// 省略したバイト数は "... (N more bytes)" として表示する
+    pub fn to_string_truncated(&self, max_bytes: usize) -> String {
+        let mut text = String::new();
+        // Stringへの書き込みは失敗しない
+        let _ = self.write_frame(&mut text, max_bytes);
+        text
+    }
+
+    /// フレームの内容を書き込む。ペイロードはmax_bytesバイトまで表示する
+    fn write_frame(&self, f: &mut dyn fmt::Write, max_bytes: usize) -> fmt::Result {
+        let shown_len = self.data.len().min(max_bytes);
+        let hex_bytes: Vec<String> = self.data[..shown_len]
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect();
+        let mut formatted_data = hex_bytes.join(" ");
+        if shown_len < self.data.len() {
+            formatted_data.push_str(&format!(" ... ({} more bytes)", self.data.len() - shown_len));
+        }
+        write!(
+            f,
+            "#dst_mac     : {}\n\
+             #src_mac     : {}\n",
+            self.dst_mac,
+            self.src_mac,
+        )?;
+        if let Some(outer_vlan) = &self.outer_vlan {
+            writeln!(f, "#outer_vlan  : {}", outer_vlan)?;
+        }
+        if let Some(vlan) = &self.vlan {
+            writeln!(f, "#vlan        : {}", vlan)?;
+        }
+        write!(
+            f,
+            "#ethertype   : {:04X} ({})\n\
+             #data        : [{}]\n",
+            self.ethertype,
+            self.ethertype_kind(),
+            formatted_data,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// イーサネット/IPv4のARPリクエストのペイロード(28バイト)
+    fn arp_payload() -> Vec<u8> {
+        let mut payload = vec![0x00, 0x01, 0x08, 0x00, 6, 4, 0x00, 0x01];
+        payload.extend_from_slice(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 192, 168, 1, 10]);
+        payload.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 192, 168, 1, 1]);
+        payload
+    }
+
+    fn frame(ethertype: u16, data: Vec<u8>) -> EthernetFrame {
+        EthernetFrame::new(None, None, Some(ethertype), Some(data))
+    }
+
+    #[test]
+    fn is_valid_arp_accepts_an_ethernet_ipv4_arp_payload() {
+        assert!(frame(0x0806, arp_payload()).is_valid_arp());
+    }
+
+    #[test]
+    fn is_valid_arp_rejects_malformed_payloads() {
+        let with_byte = |index: usize, value: u8| {
+            let mut payload = arp_payload();
+            payload[index] = value;
+            payload
+        };
+        assert!(!frame(0x0800, arp_payload()).is_valid_arp(), "wrong ethertype");
+        assert!(!frame(0x0806, arp_payload()[..27].to_vec()).is_valid_arp(), "too short");
+        assert!(!frame(0x0806, with_byte(1, 6)).is_valid_arp(), "hardware type is not Ethernet");
+        assert!(!frame(0x0806, with_byte(2, 0x86)).is_valid_arp(), "protocol type is not IPv4");
+        assert!(!frame(0x0806, with_byte(4, 8)).is_valid_arp(), "hardware length is not 6");
+        assert!(!frame(0x0806, with_byte(5, 16)).is_valid_arp(), "protocol length is not 4");
+    }
+
+    #[test]
+    fn ethertype_is_written_and_read_big_endian() {
+        for (ethertype, expected) in [(0x0806, [0x08, 0x06]), (0x86DD, [0x86, 0xDD])] {
+            let bytes = frame(ethertype, vec![0; 46]).to_bytes();
+            assert_eq!(bytes[12..14], expected);
+            assert_eq!(EthernetFrame::from_bytes(&bytes).unwrap().ethertype, ethertype);
+        }
+    }
+
+    #[test]
+    fn to_string_truncated_shows_the_ellipsis_and_remaining_count() {
+        let text = frame(0x0800, (0..100).collect()).to_string_truncated(16);
+        assert!(text.contains("00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F ... (84 more bytes)"), "{}", text);
+        assert!(!text.contains(" 10 "), "{}", text);
+    }
+
+    #[test]
+    fn to_string_truncated_keeps_small_payloads_whole() {
+        let frame = frame(0x0800, vec![0xAB; 4]);
+        assert_eq!(frame.to_string_truncated(16), frame.to_string());
+    }
+
+    #[test]
+    fn fcs_matches_a_known_crc32_and_is_appended_big_endian() {
+        let frame = EthernetFrame::from_raw([0xFF; 6], [0x02, 0x00, 0x00, 0x00, 0x00, 0x01], 0x0800, vec![0; 46]);
+        assert_eq!(frame.compute_fcs(), 0xF82D_88C1);
+
+        let bytes = frame.to_bytes_with_fcs();
+        assert_eq!(bytes[bytes.len() - 4..], [0xF8, 0x2D, 0x88, 0xC1]);
+        assert!(EthernetFrame::verify_fcs(&bytes));
+
+        let mut corrupted = bytes.clone();
+        corrupted[20] ^= 0x01;
+        assert!(!EthernetFrame::verify_fcs(&corrupted));
+        assert!(!EthernetFrame::verify_fcs(&[0x00, 0x01, 0x02]));
+    }
+
+    #[test]
+    fn from_bytes_reads_the_header_and_payload() {
+        let mut bytes = vec![0xFF; 6];
+        bytes.extend_from_slice(&[0x02, 0x00, 0x00, 0x00, 0x00, 0x01]);
+        bytes.extend_from_slice(&[0x86, 0xDD]);
+        bytes.extend_from_slice(&[1, 2, 3]);
+
+        let frame = EthernetFrame::from_bytes(&bytes).unwrap();
+        assert_eq!(frame.dst_mac, MacAddress::get_broadcast_mac_addr());
+        assert_eq!(frame.src_mac, MacAddress::from_array([0x02, 0x00, 0x00, 0x00, 0x00, 0x01]));
+        assert_eq!(frame.ethertype, 0x86DD);
+        assert_eq!(frame.data, vec![1, 2, 3]);
+        assert_eq!(frame.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn from_bytes_rejects_buffers_shorter_than_the_header() {
+        assert!(EthernetFrame::from_bytes(&[0; 13]).is_err());
+        assert_eq!(EthernetFrame::from_bytes(&[0; 14]).unwrap().data, Vec::<u8>::new());
+    }
+
+    #[test]
+    fn vlan_tag_is_serialized_between_src_mac_and_ethertype() {
+        let frame = EthernetFrame::from_raw([0xFF; 6], [0x02, 0x00, 0x00, 0x00, 0x00, 0x01], 0x0800, vec![1, 2]).with_vlan(100, 5).unwrap();
+        assert_eq!(frame.total_length(), 14 + 4 + 2);
+
+        let bytes = frame.to_bytes();
+        // TPID 0x8100、TCI = PCP(3ビット) + DEI(1ビット) + VID(12ビット) = 5 << 13 | 100
+        assert_eq!(bytes[12..18], [0x81, 0x00, 0xA0, 0x64, 0x08, 0x00]);
+        assert_eq!(EthernetFrame::from_bytes(&bytes).unwrap(), frame);
+        assert!(frame.to_string().contains("TPID=8100 PCP=5 DEI=0 VID=100"));
+    }
+
+    #[test]
+    fn with_vlan_rejects_out_of_range_values() {
+        assert!(frame(0x0800, Vec::new()).with_vlan(4096, 0).is_err());
+        assert!(frame(0x0800, Vec::new()).with_vlan(4095, 8).is_err());
+        assert!(frame(0x0800, Vec::new()).with_vlan(4095, 7).is_ok());
+    }
+
+    #[test]
+    fn display_shows_the_ethertype_name_next_to_the_hex_value() {
+        assert!(frame(0x0806, Vec::new()).to_string().contains("#ethertype   : 0806 (ARP)"));
+        assert!(frame(0x88CC, Vec::new()).to_string().contains("#ethertype   : 88CC (Unknown)"));
+    }
+
+    #[test]
+    fn pads_a_10_byte_payload_to_the_minimum_length() {
+        let mut short = frame(0x0800, vec![0xAB; 10]);
+        assert!(short.needs_padding());
+        short.pad_to_minimum();
+        assert_eq!(short.total_length(), EthernetFrame::MIN_LENGTH);
+        assert_eq!(short.data.len(), 46);
+        assert_eq!(short.data[..10], [0xAB; 10]);
+        assert!(short.data[10..].iter().all(|&byte| byte == 0));
+
+        // 何度呼んでも結果は同じ
+        short.pad_to_minimum();
+        assert_eq!(short.data.len(), 46);
+    }
+
+    #[test]
+    fn does_not_pad_a_100_byte_payload() {
+        let mut long = frame(0x0800, vec![0xAB; 100]);
+        assert!(!long.needs_padding());
+        long.pad_to_minimum();
+        assert_eq!(long.data, vec![0xAB; 100]);
+    }
+
+    #[test]
+    fn serializes_with_the_struct_field_names_and_round_trips() {
+        let original = frame(0x0806, vec![1, 2, 3]).with_vlan(100, 5).unwrap();
+        let value = serde_json::to_value(&original).unwrap();
+        for field in ["dst_mac", "src_mac", "outer_vlan", "vlan", "ethertype", "data"] {
+            assert!(value.get(field).is_some(), "{}", field);
+        }
+        assert!(value.get("jumbo").is_none());
+        assert_eq!(value["ethertype"], 0x0806);
+        assert_eq!(serde_json::from_value::<EthernetFrame>(value).unwrap(), original);
+    }
+
+    #[test]
+    fn validate_rejects_runt_frames() {
+        assert_eq!(frame(0x0800, vec![0; 45]).validate(), Err("Runt frame: shorter than the minimum frame length"));
+        let mut padded = frame(0x0800, vec![0; 10]);
+        padded.pad_to_minimum();
+        assert_eq!(padded.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_accepts_payloads_up_to_the_mtu() {
+        assert_eq!(frame(0x0800, vec![0; 46]).validate(), Ok(()));
+        assert_eq!(frame(0x0800, vec![0; EthernetFrame::MAX_PAYLOAD]).validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_rejects_oversized_frames() {
+        assert_eq!(
+            frame(0x0800, vec![0; EthernetFrame::MAX_PAYLOAD + 1]).validate(),
+            Err("Oversized frame: payload exceeds the maximum length")
+        );
+    }
+
+    #[test]
+    fn jumbo_is_ignored_by_equality_and_hashing() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let normal = frame(0x0800, vec![1, 2, 3]);
+        let mut jumbo = normal.clone();
+        jumbo.set_jumbo(true);
+        assert_eq!(jumbo, normal);
+
+        let hash = |frame: &EthernetFrame| {
+            let mut hasher = DefaultHasher::new();
+            frame.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&jumbo), hash(&normal));
+    }
+
+    #[test]
+    fn validate_allows_jumbo_payloads_only_when_enabled() {
+        let mut jumbo = frame(0x0800, vec![0; EthernetFrame::JUMBO_MAX_PAYLOAD]);
+        assert!(jumbo.validate().is_err());
+        jumbo.set_jumbo(true);
+        assert_eq!(jumbo.validate(), Ok(()));
+        jumbo.append_payload(&[0]);
+        assert_eq!(jumbo.validate(), Err("Oversized frame: payload exceeds the maximum length"));
+    }
+
+    #[test]
+    fn qinq_tags_are_serialized_outer_then_inner_and_round_trip() {
+        let frame = frame(0x0800, vec![1, 2]).with_qinq(200, 100).unwrap();
+        assert_eq!(frame.total_length(), 14 + 4 + 4 + 2);
+
+        let bytes = frame.to_bytes();
+        assert_eq!(bytes[12..22], [0x88, 0xA8, 0x00, 0xC8, 0x81, 0x00, 0x00, 0x64, 0x08, 0x00]);
+        assert_eq!(EthernetFrame::from_bytes(&bytes).unwrap(), frame);
+
+        let text = frame.to_string();
+        let outer = text.find("TPID=88A8 PCP=0 DEI=0 VID=200").unwrap();
+        let inner = text.find("TPID=8100 PCP=0 DEI=0 VID=100").unwrap();
+        assert!(outer < inner);
+    }
+
+    #[test]
+    fn from_bytes_requires_an_inner_tag_after_the_service_tag() {
+        let mut bytes = frame(0x0800, vec![1, 2]).with_qinq(200, 100).unwrap().to_bytes();
+        bytes[16..18].copy_from_slice(&[0x08, 0x00]);
+        assert!(EthernetFrame::from_bytes(&bytes).is_err());
+        assert!(EthernetFrame::from_bytes(&bytes[..21]).is_err());
+    }
+
+    #[test]
+    fn set_payload_replaces_the_data_and_length() {
+        let mut edited = frame(0x0800, vec![1, 2, 3]);
+        edited.set_payload(vec![9; 50]);
+        assert_eq!(edited.data, vec![9; 50]);
+        assert_eq!(edited.total_length(), 14 + 50);
+        assert_eq!(edited.to_bytes()[14..], [9; 50]);
+    }
+
+    #[test]
+    fn append_payload_extends_the_data_and_length() {
+        let mut edited = frame(0x0800, vec![1, 2, 3]);
+        edited.append_payload(&[4, 5]);
+        edited.append_payload(&[]);
+        assert_eq!(edited.data, vec![1, 2, 3, 4, 5]);
+        assert_eq!(edited.total_length(), 14 + 5);
+        assert_eq!(edited.to_bytes()[14..], [1, 2, 3, 4, 5]);
     }
 
+    #[test]
+    fn new_broadcast_sends_to_everyone_from_a_local_address() {
+        let broadcast = EthernetFrame::new_broadcast(0x0806, arp_payload());
+        assert!(broadcast.dst_mac.is_broadcast());
+        assert!(broadcast.src_mac.is_locally_administered());
+        assert_eq!(broadcast.ethertype, 0x0806);
+        assert_eq!(broadcast.data, arp_payload());
+
+        // Defaultは全て0のままで、new_broadcastとは違う
+        let default = EthernetFrame::default();
+        assert_eq!(default.dst_mac, MacAddress::from_array([0; 6]));
+        assert_eq!(default.ethertype, 0x0000);
+    }
+
+    #[test]
+    fn padding_needed_reports_without_mutating() {
+        let short = frame(0x0800, vec![0xAB; 10]);
+        assert_eq!(short.padding_needed(), 36);
+        assert_eq!(short.data.len(), 10);
+
+        assert_eq!(frame(0x0800, vec![0xAB; 60]).padding_needed(), 0);
+        assert_eq!(frame(0x0800, vec![0xAB; 46]).padding_needed(), 0);
+        // VLANタグの4バイトもフレーム長に含まれる
+        assert_eq!(frame(0x0800, vec![0xAB; 10]).with_vlan(100, 0).unwrap().padding_needed(), 32);
+    }
 }
@@ -0,0 +1,85 @@
+use crate::layer2::address::MacAddress;
+use crate::layer2::packets::EthernetFrame;
+
+/// EthernetFrameを1つずつフィールドを指定しながら組み立てるためのビルダー
+/// 指定しなかったフィールドはEthernetFrame::newと同じ値になる
+/// (宛先はブロードキャスト、送信元はランダム、イーサタイプは0x0800、ペイロードは空)
+///
+/// ```rust
+/// let frame = EthernetFrame::builder()
+///     .dst(router_mac)
+///     .src(host_mac)
+///     .ethertype(IPv4Packet::ETHERTYPE)
+///     .payload(packet.to_bytes())
+///     .build();
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct EthernetFrameBuilder {
+    dst_mac: Option<MacAddress>,
+    src_mac: Option<MacAddress>,
+    ethertype: Option<u16>,
+    data: Option<Vec<u8>>,
+}
+
+impl EthernetFrameBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 宛先MACアドレスを指定する
+    pub fn dst(mut self, dst_mac: MacAddress) -> Self {
+        self.dst_mac = Some(dst_mac);
+        self
+    }
+
+    /// 送信元MACアドレスを指定する
+    pub fn src(mut self, src_mac: MacAddress) -> Self {
+        self.src_mac = Some(src_mac);
+        self
+    }
+
+    /// イーサタイプを指定する
+    pub fn ethertype(mut self, ethertype: u16) -> Self {
+        self.ethertype = Some(ethertype);
+        self
+    }
+
+    /// ペイロードを指定する
+    pub fn payload(mut self, data: Vec<u8>) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// フレームを生成する
+    pub fn build(self) -> EthernetFrame {
+        EthernetFrame::new(self.dst_mac, self.src_mac, self.ethertype, self.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_frame_matches_an_equivalent_new_call() {
+        let dst = MacAddress::from_array([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        let src = MacAddress::from_array([0x02, 0x00, 0x00, 0x00, 0x00, 0x01]);
+        let built = EthernetFrame::builder()
+            .dst(dst)
+            .src(src)
+            .ethertype(0x0806)
+            .payload(vec![1, 2, 3])
+            .build();
+        assert_eq!(built, EthernetFrame::new(Some(dst), Some(src), Some(0x0806), Some(vec![1, 2, 3])));
+    }
+
+    #[test]
+    fn unset_fields_default_like_new() {
+        let src = MacAddress::from_array([0x02, 0x00, 0x00, 0x00, 0x00, 0x01]);
+        let built = EthernetFrame::builder().src(src).build();
+        assert_eq!(built, EthernetFrame::new(None, Some(src), None, None));
+        assert!(built.dst_mac.is_broadcast());
+        assert_eq!(built.ethertype, 0x0800);
+        assert!(built.data.is_empty());
+    }
+}
@@ -1,3 +1,9 @@
+pub(crate) mod ether_type;
 pub(crate) mod ethernet_frame;
+pub(crate) mod ethernet_frame_builder;
+pub(crate) mod vlan_tag;
 
+pub use ether_type::EtherType;
 pub use ethernet_frame::EthernetFrame;
+pub use ethernet_frame_builder::EthernetFrameBuilder;
+pub use vlan_tag::VlanTag;
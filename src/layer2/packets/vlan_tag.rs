@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// IEEE 802.1QのVLANタグ (4バイト)
+/// イーサネットフレームの送信元MACとイーサタイプの間に挿入される
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VlanTag {
+    pub tpid: u16, // タグプロトコル識別子 (2バイト) 802.1Qでは0x8100、802.1adのサービスタグでは0x88A8
+    pub pcp: u8,   // 優先度 (3ビット)
+    pub dei: bool, // 破棄適格インジケータ (1ビット)
+    pub vid: u16,  // VLAN ID (12ビット)
+}
+
+impl Default for VlanTag {
+    fn default() -> Self {
+        Self {
+            tpid: Self::TPID_8021Q,
+            pcp: 0,
+            dei: false,
+            vid: 0,
+        }
+    }
+}
+
+impl fmt::Display for VlanTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "TPID={:04X} PCP={} DEI={} VID={}",
+            self.tpid, self.pcp, self.dei as u8, self.vid
+        )
+    }
+}
+
+impl VlanTag {
+    /// 802.1QのTPID
+    pub const TPID_8021Q: u16 = 0x8100;
+    /// 802.1ad(QinQ)のサービスタグのTPID
+    pub const TPID_8021AD: u16 = 0x88A8;
+    /// タグのバイト長
+    pub const LENGTH: usize = 4;
+
+    /// VLAN IDと優先度からタグを生成する
+    /// vidは12ビット(0〜4095)、pcpは3ビット(0〜7)に収まらない場合はエラー
+    pub fn new(vid: u16, pcp: u8) -> Result<Self, &'static str> {
+        if vid > 0x0FFF {
+            return Err("VLAN ID must fit in 12 bits (0-4095)");
+        }
+        if pcp > 0x07 {
+            return Err("VLAN priority must fit in 3 bits (0-7)");
+        }
+        Ok(Self {
+            vid,
+            pcp,
+            ..Self::default()
+        })
+    }
+
+    /// TCI(Tag Control Information)を計算する
+    /// 上位から PCP(3ビット) + DEI(1ビット) + VID(12ビット)
+    pub fn tci(&self) -> u16 {
+        ((self.pcp as u16 & 0x07) << 13) | ((self.dei as u16) << 12) | (self.vid & 0x0FFF)
+    }
+
+    /// バイト配列に変換（TPID + TCI、どちらもビッグエンディアン）
+    pub fn to_bytes(self) -> [u8; 4] {
+        let tpid = self.tpid.to_be_bytes();
+        let tci = self.tci().to_be_bytes();
+        [tpid[0], tpid[1], tci[0], tci[1]]
+    }
+
+    /// TPIDとTCIからタグを生成する
+    pub fn from_tpid_tci(tpid: u16, tci: u16) -> Self {
+        Self {
+            tpid,
+            pcp: (tci >> 13) as u8,
+            dei: (tci >> 12) & 0x01 == 1,
+            vid: tci & 0x0FFF,
+        }
+    }
+}
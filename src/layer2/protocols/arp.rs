@@ -0,0 +1,223 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::layer2::address::MacAddress;
+use crate::layer2::packets::EthernetFrame;
+use crate::layer3::address::IPv4Address;
+
+/// ARPの操作の種類（値はARPパケットのoperフィールドと同じ）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ArpOperation {
+    Request = 1, // このIPアドレスを持っている人はMACアドレスを教えてください
+    Reply   = 2, // そのIPアドレスを持っているのは私で、MACアドレスはこれです
+}
+
+impl ArpOperation {
+    /// operフィールドの値から種類を取得する
+    pub fn from_u16(value: u16) -> Option<ArpOperation> {
+        match value {
+            1 => Some(ArpOperation::Request),
+            2 => Some(ArpOperation::Reply),
+            _ => None,
+        }
+    }
+}
+
+/// IPv4アドレスからMACアドレスを調べるためのARPパケット(RFC 826)
+/// Ethernet + IPv4の組み合わせだけを扱う
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ArpPacket {
+    pub htype      : u16,          // ハードウェアタイプ (2バイト) Ethernetは1
+    pub ptype      : u16,          // プロトコルタイプ (2バイト) IPv4は0x0800
+    pub hlen       : u8,           // ハードウェアアドレス長 (1バイト) MACアドレスは6
+    pub plen       : u8,           // プロトコルアドレス長 (1バイト) IPv4アドレスは4
+    pub oper       : ArpOperation, // 操作 (2バイト)
+    pub sender_mac : MacAddress,   // 送信元MACアドレス (6バイト)
+    pub sender_ip  : IPv4Address,  // 送信元IPv4アドレス (4バイト)
+    pub target_mac : MacAddress,   // 目的のMACアドレス (6バイト) リクエストでは00:00:00:00:00:00
+    pub target_ip  : IPv4Address,  // 目的のIPv4アドレス (4バイト)
+}
+
+impl fmt::Display for ArpPacket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "#htype      : {:04X}\n\
+             #ptype      : {:04X}\n\
+             #hlen       : {}\n\
+             #plen       : {}\n\
+             #oper       : {:?}\n\
+             #sender_mac : {}\n\
+             #sender_ip  : {}\n\
+             #target_mac : {}\n\
+             #target_ip  : {}\n",
+            self.htype,
+            self.ptype,
+            self.hlen,
+            self.plen,
+            self.oper,
+            self.sender_mac,
+            self.sender_ip,
+            self.target_mac,
+            self.target_ip,
+        )
+    }
+}
+
+impl ArpPacket {
+    /// バイト列にした時の長さ
+    pub const LENGTH: usize = 28;
+    /// ARPパケットを運ぶイーサネットフレームのイーサタイプ
+    pub const ETHERTYPE: u16 = 0x0806;
+
+    /// target_ipのMACアドレスを問い合わせるARPリクエストを生成
+    pub fn request(sender_mac: MacAddress, sender_ip: IPv4Address, target_ip: IPv4Address) -> Self {
+        Self::new(ArpOperation::Request, sender_mac, sender_ip, MacAddress::get_arp_target_mac_addr(), target_ip)
+    }
+
+    /// ARPリクエストに答えるARPリプライを生成
+    /// sender_mac/sender_ipには問い合わせられた自分のアドレス、target_mac/target_ipには問い合わせてきた相手のアドレスを入れる
+    pub fn reply(sender_mac: MacAddress, sender_ip: IPv4Address, target_mac: MacAddress, target_ip: IPv4Address) -> Self {
+        Self::new(ArpOperation::Reply, sender_mac, sender_ip, target_mac, target_ip)
+    }
+
+    /// Gratuitous ARPを生成
+    /// 送信元と問い合わせ先のIPv4アドレスを同じにしたブロードキャストのARPリクエストで、
+    /// 自分がそのIPv4アドレスを使うことを周りに知らせたり、同じアドレスを使う機器がいないか確かめたりするのに使う
+    pub fn gratuitous(mac: MacAddress, ip: IPv4Address) -> Self {
+        Self::request(mac, ip, ip)
+    }
+
+    fn new(oper: ArpOperation, sender_mac: MacAddress, sender_ip: IPv4Address, target_mac: MacAddress, target_ip: IPv4Address) -> Self {
+        Self {
+            htype : 1,
+            ptype : 0x0800,
+            hlen  : 6,
+            plen  : 4,
+            oper,
+            sender_mac,
+            sender_ip,
+            target_mac,
+            target_ip,
+        }
+    }
+
+    /// このARPパケットを運ぶイーサネットフレームを生成
+    /// リクエストはブロードキャスト、リプライは問い合わせてきた相手に送る
+    pub fn to_ethernet_frame(&self) -> EthernetFrame {
+        let dst_mac = match self.oper {
+            ArpOperation::Request => MacAddress::get_broadcast_mac_addr(),
+            ArpOperation::Reply => self.target_mac,
+        };
+        EthernetFrame::builder()
+            .dst(dst_mac)
+            .src(self.sender_mac)
+            .ethertype(Self::ETHERTYPE)
+            .payload(self.to_bytes())
+            .build()
+    }
+
+    /// バイト配列に変換
+    /// 2バイトのフィールドはネットワークバイトオーダー(ビッグエンディアン)で書き込む
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::LENGTH);
+        bytes.extend_from_slice(&self.htype.to_be_bytes());
+        bytes.extend_from_slice(&self.ptype.to_be_bytes());
+        bytes.push(self.hlen);
+        bytes.push(self.plen);
+        bytes.extend_from_slice(&(self.oper as u16).to_be_bytes());
+        bytes.extend_from_slice(&self.sender_mac.to_array());
+        bytes.extend_from_slice(&self.sender_ip.to_array());
+        bytes.extend_from_slice(&self.target_mac.to_array());
+        bytes.extend_from_slice(&self.target_ip.to_array());
+        bytes
+    }
+
+    /// バイト配列からArpPacketを構築
+    /// 先頭28バイトを読み取る。イーサネットのパディングが付いていても良い
+    pub fn from_bytes(bytes: &[u8]) -> Result<ArpPacket, &'static str> {
+        if bytes.len() < Self::LENGTH {
+            return Err("ARP packet must be at least 28 bytes");
+        }
+        let htype = u16::from_be_bytes([bytes[0], bytes[1]]);
+        let ptype = u16::from_be_bytes([bytes[2], bytes[3]]);
+        let hlen = bytes[4];
+        let plen = bytes[5];
+        if htype != 1 || ptype != 0x0800 || hlen != 6 || plen != 4 {
+            return Err("Only Ethernet/IPv4 ARP packets are supported");
+        }
+        let oper = ArpOperation::from_u16(u16::from_be_bytes([bytes[6], bytes[7]]))
+            .ok_or("Unknown ARP operation")?;
+
+        let mut sender_mac = [0u8; 6];
+        sender_mac.copy_from_slice(&bytes[8..14]);
+        let mut sender_ip = [0u8; 4];
+        sender_ip.copy_from_slice(&bytes[14..18]);
+        let mut target_mac = [0u8; 6];
+        target_mac.copy_from_slice(&bytes[18..24]);
+        let mut target_ip = [0u8; 4];
+        target_ip.copy_from_slice(&bytes[24..28]);
+
+        Ok(ArpPacket {
+            htype,
+            ptype,
+            hlen,
+            plen,
+            oper,
+            sender_mac : MacAddress::from_array(sender_mac),
+            sender_ip  : IPv4Address::from_array(sender_ip),
+            target_mac : MacAddress::from_array(target_mac),
+            target_ip  : IPv4Address::from_array(target_ip),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAC_A: MacAddress = MacAddress([0x02, 0x00, 0x00, 0x00, 0x00, 0x0A]);
+    const MAC_B: MacAddress = MacAddress([0x02, 0x00, 0x00, 0x00, 0x00, 0x0B]);
+
+    fn ip(s: &str) -> IPv4Address {
+        IPv4Address::from_string(s).unwrap()
+    }
+
+    #[test]
+    fn request_round_trips_through_bytes() {
+        let request = ArpPacket::request(MAC_A, ip("192.168.1.10"), ip("192.168.1.1"));
+        assert_eq!(request.target_mac, MacAddress::get_arp_target_mac_addr());
+
+        let bytes = request.to_bytes();
+        assert_eq!(bytes.len(), ArpPacket::LENGTH);
+        assert_eq!(bytes[..8], [0x00, 0x01, 0x08, 0x00, 6, 4, 0x00, 0x01]);
+        assert_eq!(ArpPacket::from_bytes(&bytes).unwrap(), request);
+    }
+
+    #[test]
+    fn reply_round_trips_through_bytes_with_ethernet_padding() {
+        let reply = ArpPacket::reply(MAC_B, ip("192.168.1.1"), MAC_A, ip("192.168.1.10"));
+        let mut bytes = reply.to_bytes();
+        assert_eq!(bytes[6..8], [0x00, 0x02]);
+        bytes.resize(46, 0);
+        assert_eq!(ArpPacket::from_bytes(&bytes).unwrap(), reply);
+
+        let frame = reply.to_ethernet_frame();
+        assert_eq!(frame.ethertype, 0x0806);
+        assert_eq!(frame.dst_mac, MAC_A);
+    }
+
+    #[test]
+    fn from_bytes_rejects_short_or_unsupported_packets() {
+        let bytes = ArpPacket::request(MAC_A, ip("192.168.1.10"), ip("192.168.1.1")).to_bytes();
+        assert!(ArpPacket::from_bytes(&bytes[..27]).is_err());
+
+        let mut unknown_oper = bytes.clone();
+        unknown_oper[7] = 3;
+        assert!(ArpPacket::from_bytes(&unknown_oper).is_err());
+
+        let mut ipv6 = bytes.clone();
+        ipv6[2..4].copy_from_slice(&[0x86, 0xDD]);
+        assert!(ArpPacket::from_bytes(&ipv6).is_err());
+    }
+}
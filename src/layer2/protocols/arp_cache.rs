@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+
+use crate::layer2::{address::MacAddress, protocols::ArpPacket};
+use crate::layer3::address::IPv4Address;
+use crate::util::{browser_clock, Clock};
+
+/// ARPキャッシュの1行
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ArpCacheEntry {
+    pub ip         : String, // IPv4アドレス
+    pub mac        : String, // そのIPv4アドレスを持つ機器のMACアドレス
+    pub updated_ms : f64,    // 登録・更新した時刻(ミリ秒)
+}
+
+/// ARPで調べたIPv4アドレスとMACアドレスの対応を覚えておくキャッシュ
+/// 一度調べた相手には、しばらくの間ARPリクエストを送らずに済む
+#[derive(Clone)]
+pub struct ArpCache {
+    pub entries : HashMap<IPv4Address, (MacAddress, f64)>, // IPv4アドレス → (MACアドレス, 登録・更新した時刻(ミリ秒))
+    clock       : Clock,
+}
+
+impl Default for ArpCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ArpCache {
+    /// ブラウザの時計を使う空のARPキャッシュを生成
+    pub fn new() -> Self {
+        Self::with_clock(browser_clock())
+    }
+
+    /// 時刻の取得方法を指定して空のARPキャッシュを生成
+    /// シミュレーション上の時刻で有効期限を扱いたい時に使う
+    pub fn with_clock(clock: Clock) -> Self {
+        Self {
+            entries : HashMap::new(),
+            clock,
+        }
+    }
+
+    /// IPv4アドレスとMACアドレスの対応を登録する
+    /// 既に登録されている場合は、MACアドレスと時刻を更新する
+    pub fn insert(&mut self, ip: IPv4Address, mac: MacAddress) {
+        let now_ms = (self.clock)();
+        self.entries.insert(ip, (mac, now_ms));
+    }
+
+    /// Gratuitous ARPを受け取った時に呼ぶ
+    /// 送信元のIPv4アドレスとMACアドレスの対応を登録・更新する
+    /// 
+    /// ### 戻り値
+    /// * そのIPv4アドレスが別のMACアドレスで登録されていた(アドレスの重複を見つけた)場合はtrue
+    pub fn handle_gratuitous(&mut self, packet: &ArpPacket) -> bool {
+        let conflict = self.lookup(&packet.sender_ip).is_some_and(|mac| mac != packet.sender_mac);
+        self.insert(packet.sender_ip, packet.sender_mac);
+        conflict
+    }
+
+    /// IPv4アドレスに対応するMACアドレスを調べる
+    pub fn lookup(&self, ip: &IPv4Address) -> Option<MacAddress> {
+        self.entries.get(ip).map(|(mac, _)| *mac)
+    }
+
+    /// 登録・更新からttl_secs秒以上経った対応を消す
+    /// 
+    /// ### 戻り値
+    /// * 消した対応の数
+    pub fn purge_expired(&mut self, ttl_secs: u32) -> usize {
+        let now_ms = (self.clock)();
+        let ttl_ms = ttl_secs as f64 * 1000.0;
+        let entry_count = self.entries.len();
+        self.entries.retain(|_, (_, updated_ms)| now_ms - *updated_ms < ttl_ms);
+        entry_count - self.entries.len()
+    }
+
+    /// 登録されている対応をIPv4アドレスの順に並べて取得
+    pub fn get_entries(&self) -> Vec<ArpCacheEntry> {
+        let mut entries: Vec<(&IPv4Address, &(MacAddress, f64))> = self.entries.iter().collect();
+        entries.sort_by_key(|(ip, _)| **ip);
+        entries
+            .into_iter()
+            .map(|(ip, (mac, updated_ms))| ArpCacheEntry {
+                ip         : Ipv4Addr::from(*ip).to_string(),
+                mac        : mac.to_string_with_separator(':'),
+                updated_ms : *updated_ms,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    const MAC: MacAddress = MacAddress([0x02, 0x00, 0x00, 0x00, 0x00, 0x0A]);
+
+    fn ip(s: &str) -> IPv4Address {
+        IPv4Address::from_string(s).unwrap()
+    }
+
+    /// テストから時刻を進められるARPキャッシュ
+    fn cache_with_clock() -> (ArpCache, Arc<Mutex<f64>>) {
+        let now_ms = Arc::new(Mutex::new(0.0));
+        let clock_now_ms = now_ms.clone();
+        (ArpCache::with_clock(Arc::new(move || *clock_now_ms.lock().unwrap())), now_ms)
+    }
+
+    #[test]
+    fn lookup_hits_registered_addresses_and_misses_others() {
+        let (mut cache, _) = cache_with_clock();
+        cache.insert(ip("192.168.1.10"), MAC);
+        assert_eq!(cache.lookup(&ip("192.168.1.10")), Some(MAC));
+        assert_eq!(cache.lookup(&ip("192.168.1.11")), None);
+    }
+
+    #[test]
+    fn entries_expire_after_the_ttl() {
+        let (mut cache, now_ms) = cache_with_clock();
+        cache.insert(ip("192.168.1.10"), MAC);
+        *now_ms.lock().unwrap() = 30_000.0;
+        cache.insert(ip("192.168.1.20"), MAC);
+
+        *now_ms.lock().unwrap() = 59_999.0;
+        assert_eq!(cache.purge_expired(60), 0);
+        *now_ms.lock().unwrap() = 60_000.0;
+        assert_eq!(cache.purge_expired(60), 1);
+        assert_eq!(cache.lookup(&ip("192.168.1.10")), None);
+        assert_eq!(cache.lookup(&ip("192.168.1.20")), Some(MAC));
+    }
+
+    #[test]
+    fn gratuitous_arp_for_a_cached_ip_with_another_mac_is_a_conflict() {
+        let (mut cache, _) = cache_with_clock();
+        let other_mac = MacAddress([0x02, 0x00, 0x00, 0x00, 0x00, 0x0B]);
+        let announcement = ArpPacket::gratuitous(other_mac, ip("192.168.1.10"));
+        assert_eq!(announcement.sender_ip, announcement.target_ip);
+
+        // 初めて見るIPアドレスや、同じMACアドレスからの通知は衝突ではない
+        assert!(!cache.handle_gratuitous(&ArpPacket::gratuitous(MAC, ip("192.168.1.10"))));
+        assert!(!cache.handle_gratuitous(&ArpPacket::gratuitous(MAC, ip("192.168.1.10"))));
+
+        assert!(cache.handle_gratuitous(&announcement));
+        assert_eq!(cache.lookup(&ip("192.168.1.10")), Some(other_mac));
+    }
+}
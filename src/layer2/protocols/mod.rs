@@ -0,0 +1,5 @@
+pub(crate) mod arp;
+pub(crate) mod arp_cache;
+
+pub use arp::ArpPacket;
+pub use arp_cache::ArpCache;
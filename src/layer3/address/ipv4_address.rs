@@ -1,8 +1,12 @@
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::net::Ipv4Addr;
+use std::num::IntErrorKind;
 
-#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+use crate::error::ParseError;
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct IPv4Address(pub [u8; 4]);
 
 impl fmt::Display for IPv4Address {
@@ -19,24 +23,29 @@ impl IPv4Address {
 
     /// 192.168.0.xのIPv4アドレスをランダムに生成
     pub fn new() -> IPv4Address {
-        let mut rng = rand::thread_rng();
-        let mut addr = [0;4];
-
-        addr = [192, 168, 0, rng.gen_range(1..=254)];
-        IPv4Address(addr)
+        Self::new_in_subnet(IPv4Address([192, 168, 0, 0]), 24)
+    }
+    /// 指定したネットワークの中から、ホストに割り当てられるIPv4アドレスをランダムに生成
+    /// /30以下ではネットワークアドレスとブロードキャストアドレスは選ばない
+    /// /31、/32ではhosts_in_subnetと同じく、すべてのアドレスから選ぶ
+    pub fn new_in_subnet(network: IPv4Address, prefix: u8) -> IPv4Address {
+        let (first, last) = Self::host_range(network, prefix);
+        Self::from_u32(rand::thread_rng().gen_range(first..=last))
     }
     /// "."区切りの文字列からMACアドレスを生成する関数
-    pub fn from_string(s: &str) -> Result<IPv4Address, &'static str> {
+    pub fn from_string(s: &str) -> Result<IPv4Address, ParseError> {
         let parts: Vec<&str> = s.split('.').collect();
         if parts.len() != 4 {
-            return Err("Invalid IPv4 address format");
+            return Err(ParseError::WrongSegmentCount { expected: 4, found: parts.len() });
         }
 
         let mut addr = [0u8; 4];
         for i in 0..4 {
             addr[i] = match parts[i].parse::<u8>() {
                 Ok(num) => num,
-                Err(_) => return Err("Invalid number in IPv4 address"),
+                // 数字としては読めるが255を超えている
+                Err(error) if *error.kind() == IntErrorKind::PosOverflow => return Err(ParseError::OutOfRange),
+                Err(_) => return Err(ParseError::InvalidFormat("Invalid number in IPv4 address")),
             };
         }
 
@@ -46,6 +55,14 @@ impl IPv4Address {
     pub fn from_array(array: [u8; 4]) -> IPv4Address {
         IPv4Address(array)
     }
+    /// バイトスライスからIPv4アドレスを生成する関数
+    /// 長さが4バイトでない場合はParseError::WrongLengthを返す
+    pub fn from_slice(data: &[u8]) -> Result<IPv4Address, ParseError> {
+        let array: [u8; 4] = data
+            .try_into()
+            .map_err(|_| ParseError::WrongLength { expected: 4, found: data.len() })?;
+        Ok(IPv4Address(array))
+    }
     /// IPv4アドレスをバイトスライスとして取得
     pub fn as_slice(&self) -> &[u8] {
         &self.0
@@ -55,4 +72,363 @@ impl IPv4Address {
         self.0
     }
 
-}
\ No newline at end of file
+    /// 32ビットの整数に変換(ビッグエンディアン。192.168.0.1なら0xC0A80001)
+    pub fn to_u32(self) -> u32 {
+        u32::from_be_bytes(self.0)
+    }
+
+    /// 逆引き(PTRレコード)の名前に変換
+    /// オクテットを逆順に並べて".in-addr.arpa"を付ける(1.2.3.4なら4.3.2.1.in-addr.arpa)
+    pub fn to_ptr_name(self) -> String {
+        let [a, b, c, d] = self.0;
+        format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
+    }
+
+    /// 32ビットの整数からIPv4アドレスを生成する(ビッグエンディアン)
+    pub fn from_u32(value: u32) -> IPv4Address {
+        IPv4Address(value.to_be_bytes())
+    }
+
+    /// 次のIPv4アドレス。255.255.255.255の次はないのでNone
+    pub fn next(&self) -> Option<IPv4Address> {
+        self.to_u32().checked_add(1).map(Self::from_u32)
+    }
+
+    /// ネットワーク内でホストに割り当てられるアドレスを順番に返す
+    /// /30以下ではネットワークアドレスとブロードキャストアドレスを除く
+    /// /31(ポイントツーポイント用)は2つ、/32は1つのアドレスをそのまま返す
+    pub fn hosts_in_subnet(network: IPv4Address, prefix: u8) -> impl Iterator<Item = IPv4Address> {
+        let (first, last) = Self::host_range(network, prefix);
+        (first..=last).map(Self::from_u32)
+    }
+
+    /// ホストに割り当てられる最初と最後のアドレス(32ビットの整数)
+    /// /30以下ではネットワークアドレスとブロードキャストアドレスを除く
+    fn host_range(network: IPv4Address, prefix: u8) -> (u32, u32) {
+        let first = network.network_address(prefix).to_u32();
+        let last = network.broadcast_address(prefix).to_u32();
+        if prefix >= 31 { (first, last) } else { (first + 1, last - 1) }
+    }
+
+    /// ネットワーク内でホストに割り当てられるアドレスの数
+    /// 例: /24なら254、/31なら2、/32なら1
+    pub fn host_count(prefix: u8) -> u32 {
+        match prefix.min(32) {
+            32 => 1,
+            31 => 2,
+            // ホスト部のアドレスの数(2^(32-prefix))からネットワークアドレスとブロードキャストアドレスを引く
+            prefix => (u32::MAX >> prefix) - 1,
+        }
+    }
+
+    /// ネットワークアドレスとブロードキャストアドレスを除いた、使えるホストアドレスの数
+    /// 2^(32-prefix) - 2で、/31と/32は0になる
+    /// host_countと違い、/31をポイントツーポイントリンク(RFC 3021)として扱わない
+    pub fn usable_hosts(prefix: u8) -> u32 {
+        match prefix.min(32) {
+            31 | 32 => 0,
+            prefix => (u32::MAX >> prefix) - 1,
+        }
+    }
+
+    /// "192.168.1.0/24"のようなCIDR表記の文字列から、IPv4アドレスとプレフィックス長を取得する
+    /// プレフィックス長は0〜32でなければエラー
+    pub fn from_cidr(s: &str) -> Result<(IPv4Address, u8), ParseError> {
+        let (addr, prefix) = s
+            .split_once('/')
+            .ok_or(ParseError::InvalidFormat("CIDR notation must contain '/'"))?;
+        let ip = Self::from_string(addr)?;
+        let prefix = prefix.parse::<u8>().map_err(|error| match error.kind() {
+            IntErrorKind::PosOverflow => ParseError::OutOfRange,
+            _ => ParseError::InvalidFormat("Invalid prefix length in CIDR notation"),
+        })?;
+        if prefix > 32 {
+            return Err(ParseError::OutOfRange);
+        }
+        Ok((ip, prefix))
+    }
+
+    /// プレフィックス長からサブネットマスクを生成する
+    /// 例: 24 → 255.255.255.0、0 → 0.0.0.0。32より大きい場合は32として扱う
+    pub fn netmask_from_prefix(prefix: u8) -> IPv4Address {
+        let prefix = prefix.min(32) as u32;
+        // 32ビットシフトはできないので、/0の時は0にする
+        Self::from_u32(u32::MAX.checked_shl(32 - prefix).unwrap_or(0))
+    }
+
+    /// プレフィックス長からワイルドカードマスク(サブネットマスクのビットを反転したもの)を生成する
+    /// 例: 24 → 0.0.0.255。ACLやOSPFの設定で使う
+    pub fn wildcard_mask(prefix: u8) -> IPv4Address {
+        Self::from_u32(!Self::netmask_from_prefix(prefix).to_u32())
+    }
+
+    /// このアドレスが属するネットワークのネットワークアドレス(ホスト部を全て0にしたもの)
+    pub fn network_address(&self, prefix: u8) -> IPv4Address {
+        Self::from_u32(self.to_u32() & Self::netmask_from_prefix(prefix).to_u32())
+    }
+
+    /// このアドレスが属するネットワークのブロードキャストアドレス(ホスト部を全て1にしたもの)
+    pub fn broadcast_address(&self, prefix: u8) -> IPv4Address {
+        Self::from_u32(self.to_u32() | !Self::netmask_from_prefix(prefix).to_u32())
+    }
+
+    /// このアドレスがnetwork/prefixのネットワークに含まれるかどうか
+    /// networkのホスト部は見ないので、ネットワーク内のどのアドレスを渡しても良い
+    pub fn is_in_subnet(&self, network: IPv4Address, prefix: u8) -> bool {
+        let netmask = Self::netmask_from_prefix(prefix).to_u32();
+        self.to_u32() & netmask == network.to_u32() & netmask
+    }
+
+    /// aとbが同じネットワーク(プレフィックス長prefix)に属しているかどうか
+    /// 同じネットワークならルーターを通さずに直接通信できる
+    pub fn same_subnet(a: IPv4Address, b: IPv4Address, prefix: u8) -> bool {
+        a.is_in_subnet(b, prefix)
+    }
+
+    /// プライベートアドレス/ループバック/マルチキャスト/予約済みアドレスにならない
+    /// インターネット上にありそうなIPv4アドレスをランダムに生成
+    pub fn new_public() -> IPv4Address {
+        let mut rng = rand::thread_rng();
+        loop {
+            let addr = IPv4Address(rng.gen());
+            if !addr.is_private() && !addr.is_loopback() && !addr.is_multicast() && !addr.is_reserved() {
+                return addr;
+            }
+        }
+    }
+
+    /// プライベートアドレス(10.0.0.0/8, 172.16.0.0/12, 192.168.0.0/16)かどうか
+    pub fn is_private(&self) -> bool {
+        match self.0 {
+            [10, ..] => true,
+            [172, b, ..] => (b & 0xF0) == 16,
+            [192, 168, ..] => true,
+            _ => false,
+        }
+    }
+
+    /// ループバックアドレス(127.0.0.0/8)かどうか
+    pub fn is_loopback(&self) -> bool {
+        self.0[0] == 127
+    }
+
+    /// マルチキャストアドレス(224.0.0.0/4)かどうか
+    pub fn is_multicast(&self) -> bool {
+        (self.0[0] & 0xF0) == 224
+    }
+
+    /// リンクローカルアドレス(169.254.0.0/16)かどうか
+    /// DHCPでアドレスをもらえなかった時に自動で割り当てられるアドレス
+    pub fn is_link_local(&self) -> bool {
+        matches!(self.0, [169, 254, ..])
+    }
+
+    /// リミテッドブロードキャストアドレス(255.255.255.255)かどうか
+    pub fn is_broadcast(&self) -> bool {
+        self.0 == [255; 4]
+    }
+
+    /// 特別な用途のために予約されているアドレスかどうか
+    /// 0.0.0.0/8, 100.64.0.0/10(CGN), 169.254.0.0/16(リンクローカル), 192.0.0.0/24,
+    /// 192.0.2.0/24, 198.51.100.0/24, 203.0.113.0/24(ドキュメント用), 198.18.0.0/15(ベンチマーク用),
+    /// 240.0.0.0/4(将来のための予約。255.255.255.255も含む)
+    pub fn is_reserved(&self) -> bool {
+        match self.0 {
+            [0, ..] => true,
+            [100, b, ..] => (b & 0xC0) == 64,
+            [169, 254, ..] => true,
+            [192, 0, 0, _] | [192, 0, 2, _] => true,
+            [198, 51, 100, _] | [203, 0, 113, _] => true,
+            [198, b, ..] => (b & 0xFE) == 18,
+            [a, ..] => (a & 0xF0) == 240,
+        }
+    }
+
+}
+
+/// 標準ライブラリのIpv4Addrへの変換
+impl From<IPv4Address> for Ipv4Addr {
+    fn from(addr: IPv4Address) -> Self {
+        Ipv4Addr::from(addr.0)
+    }
+}
+
+/// 標準ライブラリのIpv4Addrからの変換
+impl From<Ipv4Addr> for IPv4Address {
+    fn from(addr: Ipv4Addr) -> Self {
+        IPv4Address(addr.octets())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IPv4Address {
+        IPv4Address::from_string(s).unwrap()
+    }
+
+    #[test]
+    fn new_public_never_returns_private_or_reserved_addresses() {
+        for _ in 0..10_000 {
+            let addr = IPv4Address::new_public();
+            assert!(!addr.is_private(), "{:?}", addr);
+            assert!(!addr.is_reserved(), "{:?}", addr);
+            assert!(!addr.is_loopback(), "{:?}", addr);
+            assert!(!addr.is_multicast(), "{:?}", addr);
+        }
+    }
+
+    #[test]
+    fn round_trips_through_std_ipv4addr() {
+        let address = IPv4Address::from_string("192.168.1.10").unwrap();
+        let std_address = Ipv4Addr::from(address);
+        assert_eq!(std_address, Ipv4Addr::new(192, 168, 1, 10));
+        assert_eq!(IPv4Address::from(std_address), address);
+
+        let std_address = Ipv4Addr::new(10, 0, 0, 1);
+        assert_eq!(Ipv4Addr::from(IPv4Address::from(std_address)), std_address);
+    }
+
+    #[test]
+    fn cidr_slash_24_has_the_expected_mask_network_and_broadcast() {
+        let (address, prefix) = IPv4Address::from_cidr("192.168.1.77/24").unwrap();
+        assert_eq!((address, prefix), (ip("192.168.1.77"), 24));
+        assert_eq!(IPv4Address::netmask_from_prefix(prefix), ip("255.255.255.0"));
+        assert_eq!(address.network_address(prefix), ip("192.168.1.0"));
+        assert_eq!(address.broadcast_address(prefix), ip("192.168.1.255"));
+    }
+
+    #[test]
+    fn cidr_slash_30_has_the_expected_mask_network_and_broadcast() {
+        let (address, prefix) = IPv4Address::from_cidr("10.0.0.6/30").unwrap();
+        assert_eq!(IPv4Address::netmask_from_prefix(prefix), ip("255.255.255.252"));
+        assert_eq!(address.network_address(prefix), ip("10.0.0.4"));
+        assert_eq!(address.broadcast_address(prefix), ip("10.0.0.7"));
+    }
+
+    #[test]
+    fn cidr_slash_0_covers_every_address() {
+        let (address, prefix) = IPv4Address::from_cidr("203.0.113.5/0").unwrap();
+        assert_eq!(IPv4Address::netmask_from_prefix(prefix), ip("0.0.0.0"));
+        assert_eq!(address.network_address(prefix), ip("0.0.0.0"));
+        assert_eq!(address.broadcast_address(prefix), ip("255.255.255.255"));
+    }
+
+    #[test]
+    fn from_cidr_rejects_a_missing_or_out_of_range_prefix() {
+        assert!(IPv4Address::from_cidr("192.168.1.0").is_err());
+        assert!(matches!(IPv4Address::from_cidr("192.168.1.0/33"), Err(ParseError::OutOfRange)));
+        assert!(IPv4Address::from_cidr("192.168.1.0/abc").is_err());
+    }
+
+    #[test]
+    fn classifies_representative_addresses() {
+        // (アドレス, private, loopback, multicast, link_local, broadcast)
+        let table = [
+            ("10.1.2.3",        true,  false, false, false, false),
+            ("172.16.0.1",      true,  false, false, false, false),
+            ("172.31.255.254",  true,  false, false, false, false),
+            ("172.32.0.1",      false, false, false, false, false),
+            ("192.168.10.1",    true,  false, false, false, false),
+            ("127.0.0.1",       false, true,  false, false, false),
+            ("224.0.0.1",       false, false, true,  false, false),
+            ("239.255.255.250", false, false, true,  false, false),
+            ("169.254.1.1",     false, false, false, true,  false),
+            ("255.255.255.255", false, false, false, false, true),
+            ("8.8.8.8",         false, false, false, false, false),
+        ];
+        for (address, private, loopback, multicast, link_local, broadcast) in table {
+            let address_ip = ip(address);
+            assert_eq!(address_ip.is_private(), private, "{}", address);
+            assert_eq!(address_ip.is_loopback(), loopback, "{}", address);
+            assert_eq!(address_ip.is_multicast(), multicast, "{}", address);
+            assert_eq!(address_ip.is_link_local(), link_local, "{}", address);
+            assert_eq!(address_ip.is_broadcast(), broadcast, "{}", address);
+        }
+    }
+
+    #[test]
+    fn u32_conversion_round_trips_big_endian() {
+        assert_eq!(ip("192.168.0.1").to_u32(), 0xC0A8_0001);
+        for value in [0, 1, 0xC0A8_0001, 0x7F00_0001, u32::MAX] {
+            assert_eq!(IPv4Address::from_u32(value).to_u32(), value);
+        }
+        assert_eq!(ip("10.0.0.255").next(), Some(ip("10.0.1.0")));
+        assert_eq!(ip("255.255.255.255").next(), None);
+    }
+
+    #[test]
+    fn counts_hosts_for_slash_24_and_slash_31() {
+        let hosts: Vec<IPv4Address> = IPv4Address::hosts_in_subnet(ip("192.168.1.0"), 24).collect();
+        assert_eq!(hosts.len(), 254);
+        assert_eq!(hosts.first(), Some(&ip("192.168.1.1")));
+        assert_eq!(hosts.last(), Some(&ip("192.168.1.254")));
+        assert_eq!(IPv4Address::host_count(24), 254);
+
+        let hosts: Vec<IPv4Address> = IPv4Address::hosts_in_subnet(ip("10.0.0.0"), 31).collect();
+        assert_eq!(hosts, vec![ip("10.0.0.0"), ip("10.0.0.1")]);
+        assert_eq!(IPv4Address::host_count(31), 2);
+    }
+
+    #[test]
+    fn from_slice_requires_exactly_4_bytes() {
+        assert_eq!(IPv4Address::from_slice(&[192, 168, 1, 1]), Ok(ip("192.168.1.1")));
+        assert_eq!(
+            IPv4Address::from_slice(&[192, 168, 1]),
+            Err(ParseError::WrongLength { expected: 4, found: 3 })
+        );
+    }
+
+    #[test]
+    fn new_in_subnet_stays_within_the_usable_hosts() {
+        for _ in 0..200 {
+            let host = IPv4Address::new_in_subnet(ip("10.1.2.0"), 24).to_u32();
+            assert!((ip("10.1.2.1").to_u32()..=ip("10.1.2.254").to_u32()).contains(&host));
+
+            let host = IPv4Address::new_in_subnet(ip("172.16.0.37"), 28).to_u32();
+            assert!((ip("172.16.0.33").to_u32()..=ip("172.16.0.46").to_u32()).contains(&host));
+        }
+        assert!(IPv4Address::new().is_in_subnet(ip("192.168.0.0"), 24));
+    }
+
+    #[test]
+    fn from_string_reports_specific_parse_errors() {
+        assert_eq!(
+            IPv4Address::from_string("10.0.0"),
+            Err(ParseError::WrongSegmentCount { expected: 4, found: 3 })
+        );
+        assert_eq!(IPv4Address::from_string("10.0.0.256"), Err(ParseError::OutOfRange));
+        assert!(matches!(IPv4Address::from_string("10.0.x.1"), Err(ParseError::InvalidFormat(_))));
+        assert!(matches!(IPv4Address::from_cidr("10.0.0.0/33"), Err(ParseError::OutOfRange)));
+    }
+
+    #[test]
+    fn ptr_name_reverses_the_octets() {
+        assert_eq!(IPv4Address::from_string("1.2.3.4").unwrap().to_ptr_name(), "4.3.2.1.in-addr.arpa");
+        assert_eq!(IPv4Address::from_string("192.168.0.10").unwrap().to_ptr_name(), "10.0.168.192.in-addr.arpa");
+    }
+
+    #[test]
+    fn same_slash_24_is_not_always_the_same_slash_25() {
+        let a = ip("192.168.1.10");
+        let b = ip("192.168.1.200");
+        assert!(IPv4Address::same_subnet(a, b, 24));
+        assert!(!IPv4Address::same_subnet(a, b, 25));
+        assert!(a.is_in_subnet(ip("192.168.1.0"), 25));
+        assert!(!b.is_in_subnet(ip("192.168.1.0"), 25));
+        assert!(IPv4Address::same_subnet(a, ip("10.0.0.1"), 0));
+    }
+
+    #[test]
+    fn wildcard_mask_and_usable_hosts_for_a_subnet_calculator() {
+        assert_eq!(IPv4Address::wildcard_mask(24), ip("0.0.0.255"));
+        assert_eq!(IPv4Address::usable_hosts(24), 254);
+        assert_eq!(IPv4Address::wildcard_mask(31), ip("0.0.0.1"));
+        assert_eq!(IPv4Address::usable_hosts(31), 0);
+        assert_eq!(IPv4Address::usable_hosts(32), 0);
+        assert_eq!(IPv4Address::usable_hosts(30), 2);
+        assert_eq!(IPv4Address::wildcard_mask(0), ip("255.255.255.255"));
+        assert_eq!(IPv4Address::usable_hosts(0), u32::MAX - 1);
+    }
+}
@@ -1,8 +1,13 @@
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::net::Ipv6Addr;
 
-#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+use crate::error::ParseError;
+use crate::layer2::address::MacAddress;
+use crate::layer3::address::IPv4Address;
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct IPv6Address(pub [u8; 16]);
 
 impl fmt::Display for IPv6Address {
@@ -37,20 +42,49 @@ impl IPv6Address {
     }
 
     /// ":"区切りの文字列からIPv6アドレスを生成する関数
-    pub fn from_string(s: &str) -> Result<IPv6Address, &'static str> {
-        let parts: Vec<&str> = s.split(':').collect();
+    /// "2001:db8::1"のように、連続する0のグループを"::"で省略した形式も受け付ける（"::"は1回だけ使える）
+    /// "::ffff:192.168.1.1"のように、最後の32ビットをIPv4アドレスの形で書いたものも受け付ける
+    /// 
+    /// 受け付けられない場合は、理由ごとに次のエラーを返す
+    /// - グループが8つでない(省略していない形) : ParseError::WrongSegmentCount
+    /// - 16進数でない文字を含むグループがある : ParseError::InvalidHex
+    /// - 5桁以上のグループがある : ParseError::TooManyDigits
+    /// - 空のグループや"::"の誤った使い方 : ParseError::InvalidFormat
+    pub fn from_string(s: &str) -> Result<IPv6Address, ParseError> {
+        // 最後のグループがIPv4アドレスの形なら、16ビットずつの2つのグループに書き換える
+        if let Some((head, last)) = s.rsplit_once(':') {
+            if last.contains('.') {
+                let ipv4 = IPv4Address::from_string(last)?.to_array();
+                let replaced = format!(
+                    "{}:{:x}:{:x}",
+                    head,
+                    u16::from_be_bytes([ipv4[0], ipv4[1]]),
+                    u16::from_be_bytes([ipv4[2], ipv4[3]])
+                );
+                return Self::from_string(&replaced);
+            }
+        }
+
+        let parts = Self::expand_segments(s)?;
         if parts.len() != 8 {
-            return Err("Invalid IPv6 address format");
+            return Err(ParseError::WrongSegmentCount { expected: 8, found: parts.len() });
         }
 
         let mut addr = [0u8; 16];
         for (i, part) in parts.iter().enumerate() {
+            if part.is_empty() {
+                return Err(ParseError::InvalidFormat("Empty segment in IPv6 address"));
+            }
+            if !part.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(ParseError::InvalidHex);
+            }
+            // 5桁以上の16進数は16ビットに収まらない
             if part.len() > 4 {
-                return Err("Invalid segment in IPv6 address");
+                return Err(ParseError::TooManyDigits { max: 4 });
             }
             let value = match u16::from_str_radix(part, 16) {
                 Ok(num) => num,
-                Err(_) => return Err("Invalid number in IPv6 address"),
+                Err(_) => return Err(ParseError::InvalidHex),
             };
             addr[i * 2] = (value >> 8) as u8; // 高位バイト
             addr[i * 2 + 1] = (value & 0xFF) as u8; // 低位バイト
@@ -59,11 +93,46 @@ impl IPv6Address {
         Ok(IPv6Address(addr))
     }
 
+    /// 文字列をグループごとに分け、"::"で省略されている部分を"0"のグループで埋める
+    fn expand_segments(s: &str) -> Result<Vec<&str>, ParseError> {
+        let Some(pos) = s.find("::") else {
+            return Ok(s.split(':').collect());
+        };
+        let head = &s[..pos];
+        let tail = &s[pos + 2..];
+        if tail.contains("::") {
+            return Err(ParseError::InvalidFormat("'::' can appear only once in IPv6 address"));
+        }
+
+        // "::"の前後が空なら、そちら側にはグループがない（"::1"や"fe80::"、"::"）
+        let head_parts: Vec<&str> = if head.is_empty() { Vec::new() } else { head.split(':').collect() };
+        let tail_parts: Vec<&str> = if tail.is_empty() { Vec::new() } else { tail.split(':').collect() };
+        // "::"は少なくとも1つのグループを省略している
+        if head_parts.len() + tail_parts.len() > 7 {
+            return Err(ParseError::InvalidFormat("Too many segments around '::' in IPv6 address"));
+        }
+
+        let zero_count = 8 - head_parts.len() - tail_parts.len();
+        let mut parts = head_parts;
+        parts.extend(std::iter::repeat_n("0", zero_count));
+        parts.extend(tail_parts);
+        Ok(parts)
+    }
+
     /// バイト配列からIPv6アドレスを生成する関数
     pub fn from_array(array: [u8; 16]) -> IPv6Address {
         IPv6Address(array)
     }
 
+    /// バイトスライスからIPv6アドレスを生成する関数
+    /// 長さが16バイトでない場合はParseError::WrongLengthを返す
+    pub fn from_slice(data: &[u8]) -> Result<IPv6Address, ParseError> {
+        let array: [u8; 16] = data
+            .try_into()
+            .map_err(|_| ParseError::WrongLength { expected: 16, found: data.len() })?;
+        Ok(IPv6Address(array))
+    }
+
     /// IPv6アドレスをバイトスライスとして取得
     pub fn as_slice(&self) -> &[u8] {
         &self.0
@@ -74,7 +143,145 @@ impl IPv6Address {
         self.0
     }
 
-     
+    /// IPv4射影アドレス(::ffff:a.b.c.d)を生成する
+    /// IPv6のソケットでIPv4の通信相手を表す時に使う
+    pub fn from_ipv4_mapped(ipv4: IPv4Address) -> IPv6Address {
+        let mut addr = [0u8; 16];
+        addr[10] = 0xFF;
+        addr[11] = 0xFF;
+        addr[12..].copy_from_slice(&ipv4.to_array());
+        IPv6Address(addr)
+    }
+
+    /// IPv4射影アドレス(先頭10バイトが0、次の2バイトが0xffff)なら、埋め込まれているIPv4アドレスを取得する
+    pub fn to_ipv4_mapped(self) -> Option<IPv4Address> {
+        match self.0 {
+            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xFF, 0xFF, a, b, c, d] => Some(IPv4Address([a, b, c, d])),
+            _ => None,
+        }
+    }
+
+    /// 128ビットの整数に変換(ビッグエンディアン)
+    pub fn to_u128(self) -> u128 {
+        u128::from_be_bytes(self.0)
+    }
+
+    /// 128ビットの整数からIPv6アドレスを生成する(ビッグエンディアン)
+    pub fn from_u128(value: u128) -> IPv6Address {
+        IPv6Address(value.to_be_bytes())
+    }
+
+    /// ループバックアドレス(::1)かどうか
+    pub fn is_loopback(&self) -> bool {
+        self.0 == [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]
+    }
+
+    /// 未指定アドレス(::)かどうか
+    pub fn is_unspecified(&self) -> bool {
+        self.0 == [0; 16]
+    }
+
+    /// リンクローカルアドレス(fe80::/10)かどうか
+    pub fn is_link_local(&self) -> bool {
+        self.0[0] == 0xFE && (self.0[1] & 0xC0) == 0x80
+    }
+
+    /// マルチキャストアドレス(ff00::/8)かどうか
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] == 0xFF
+    }
+
+    /// プレフィックス(上位64ビット)とMACアドレスからEUI-64形式のIPv6アドレスを生成する関数
+    /// SLAACでホストが自分のアドレスを作る時の方法
+    pub fn from_mac_eui64(prefix: &IPv6Address, mac: &MacAddress) -> IPv6Address {
+        let mut addr = [0u8; 16];
+        addr[..8].copy_from_slice(&prefix.0[..8]);
+        addr[8..].copy_from_slice(&mac.to_eui64());
+        IPv6Address(addr)
+    }
+
+    /// このアドレスの要請ノードマルチキャストアドレス(ff02::1:ffXX:XXXX)を取得する関数
+    /// 下位24ビットをそのまま使う
+    pub fn solicited_node_multicast(&self) -> IPv6Address {
+        let mut addr = [0u8; 16];
+        addr[0] = 0xFF;
+        addr[1] = 0x02;
+        addr[11] = 0x01;
+        addr[12] = 0xFF;
+        addr[13..].copy_from_slice(&self.0[13..]);
+        IPv6Address(addr)
+    }
+
+    /// 16ビットずつの8つのグループとして取得
+    pub fn to_segments(self) -> [u16; 8] {
+        let mut segments = [0u16; 8];
+        for (i, segment) in segments.iter_mut().enumerate() {
+            *segment = u16::from_be_bytes([self.0[i * 2], self.0[i * 2 + 1]]);
+        }
+        segments
+    }
+
+    /// RFC 5952で決められた標準の表記に変換
+    /// - 16進数は小文字で、各グループの先頭の0は省略する
+    /// - 2つ以上連続する0のグループのうち、一番長いものを"::"に省略する（同じ長さなら最初のもの）
+    /// - IPv4射影アドレスは、最後の32ビットをIPv4アドレスの形で書く
+    ///
+    /// 例: 2001:0db8:0000:0000:0000:0000:0000:0001 → 2001:db8::1
+    pub fn to_canonical_string(self) -> String {
+        if let Some(ipv4) = self.to_ipv4_mapped() {
+            return format!("::ffff:{}", std::net::Ipv4Addr::from(ipv4));
+        }
+        let segments = self.to_segments();
+
+        // 一番長く0が続いている場所(開始位置, 長さ)を探す
+        let mut longest: Option<(usize, usize)> = None;
+        let mut i = 0;
+        while i < segments.len() {
+            if segments[i] != 0 {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < segments.len() && segments[i] == 0 {
+                i += 1;
+            }
+            let len = i - start;
+            if len >= 2 && longest.is_none_or(|(_, longest_len)| len > longest_len) {
+                longest = Some((start, len));
+            }
+        }
+
+        let join = |segments: &[u16]| {
+            segments.iter().map(|segment| format!("{:x}", segment)).collect::<Vec<String>>().join(":")
+        };
+        match longest {
+            Some((start, len)) => format!("{}::{}", join(&segments[..start]), join(&segments[start + len..])),
+            None => join(&segments),
+        }
+    }
+
+    /// 省略しない表記に変換
+    /// 8つのグループを全て小文字の4桁の16進数で書き、0のグループも"::"に省略しない
+    ///
+    /// 例: 2001:db8::1 → 2001:0db8:0000:0000:0000:0000:0000:0001
+    pub fn to_expanded_string(self) -> String {
+        self.to_segments().map(|segment| format!("{:04x}", segment)).join(":")
+    }
+
+    /// 逆引き(PTRレコード)の名前に変換
+    /// 128ビットを4ビット(ニブル)ずつ小文字の16進数にし、逆順に"."でつないで".ip6.arpa"を付ける
+    ///
+    /// 例: 2001:db8::1 → 1.0.0.0.(中略).8.b.d.0.1.0.0.2.ip6.arpa
+    pub fn to_ptr_name(self) -> String {
+        let nibbles: Vec<String> = self.0
+            .iter()
+            .rev()
+            .flat_map(|byte| [byte & 0x0F, byte >> 4])
+            .map(|nibble| format!("{:x}", nibble))
+            .collect();
+        format!("{}.ip6.arpa", nibbles.join("."))
+    }
+
     /// セパレータを指定してIPv6アドレスを文字列に変換
     pub fn to_string_with_separator(&self, separator: char) -> String {
         format!(
@@ -92,3 +299,190 @@ impl IPv6Address {
     }
 
 }
+
+/// 標準ライブラリのIpv6Addrへの変換
+impl From<IPv6Address> for Ipv6Addr {
+    fn from(addr: IPv6Address) -> Self {
+        Ipv6Addr::from(addr.0)
+    }
+}
+
+/// 標準ライブラリのIpv6Addrからの変換
+impl From<Ipv6Addr> for IPv6Address {
+    fn from(addr: Ipv6Addr) -> Self {
+        IPv6Address(addr.octets())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAC: MacAddress = MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+
+    fn ip(s: &str) -> IPv6Address {
+        IPv6Address::from_string(s).unwrap()
+    }
+
+    #[test]
+    fn from_mac_eui64_combines_the_prefix_and_the_interface_id() {
+        assert_eq!(IPv6Address::from_mac_eui64(&ip("2001:db8::"), &MAC), ip("2001:db8::211:22ff:fe33:4455"));
+    }
+
+    #[test]
+    fn solicited_node_multicast_keeps_the_low_24_bits() {
+        let address = IPv6Address::from_mac_eui64(&ip("2001:db8::"), &MAC);
+        assert_eq!(address.solicited_node_multicast(), ip("ff02::1:ff33:4455"));
+    }
+
+    #[test]
+    fn round_trips_through_std_ipv6addr() {
+        let address = IPv6Address::from_string("2001:db8::1").unwrap();
+        let std_address = Ipv6Addr::from(address);
+        assert_eq!(std_address, Ipv6Addr::new(0x2001, 0x0db8, 0, 0, 0, 0, 0, 1));
+        assert_eq!(IPv6Address::from(std_address), address);
+
+        let std_address: Ipv6Addr = "fe80::211:22ff:fe33:4455".parse().unwrap();
+        assert_eq!(Ipv6Addr::from(IPv6Address::from(std_address)), std_address);
+    }
+
+    #[test]
+    fn parses_zero_compressed_addresses() {
+        assert_eq!(ip("::"), IPv6Address([0; 16]));
+        let mut loopback = [0; 16];
+        loopback[15] = 1;
+        assert_eq!(ip("::1"), IPv6Address(loopback));
+        let mut link_local = [0; 16];
+        link_local[0] = 0xFE;
+        link_local[1] = 0x80;
+        link_local[15] = 1;
+        assert_eq!(ip("fe80::1"), IPv6Address(link_local));
+        assert_eq!(ip("fe80::"), ip("fe80:0:0:0:0:0:0:0"));
+    }
+
+    #[test]
+    fn rejects_more_than_one_double_colon() {
+        assert!(matches!(IPv6Address::from_string("1::2::3"), Err(ParseError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn canonical_string_follows_rfc_5952() {
+        assert_eq!(ip("2001:0db8:0000:0000:0000:0000:0000:0001").to_canonical_string(), "2001:db8::1");
+        assert_eq!(ip("0:0:0:0:0:0:0:0").to_canonical_string(), "::");
+        // 同じ長さの0の並びが複数あれば、最初の並びを省略する
+        assert_eq!(ip("2001:db8:0:0:1:0:0:1").to_canonical_string(), "2001:db8::1:0:0:1");
+        // 1つだけの0は省略しない
+        assert_eq!(ip("2001:db8:0:1:1:1:1:1").to_canonical_string(), "2001:db8:0:1:1:1:1:1");
+    }
+
+    #[test]
+    fn u128_conversion_round_trips() {
+        assert_eq!(ip("::1").to_u128(), 1);
+        assert_eq!(ip("2001:db8::1").to_u128(), 0x2001_0db8_0000_0000_0000_0000_0000_0001);
+        for value in [0, 1, u128::MAX, 0xfe80 << 112] {
+            assert_eq!(IPv6Address::from_u128(value).to_u128(), value);
+        }
+    }
+
+    #[test]
+    fn classifies_special_addresses() {
+        // (アドレス, loopback, unspecified, link_local, multicast)
+        let table = [
+            ("::1",         true,  false, false, false),
+            ("::",          false, true,  false, false),
+            ("fe80::1",     false, false, true,  false),
+            ("febf::1",     false, false, true,  false),
+            ("fec0::1",     false, false, false, false),
+            ("ff02::1",     false, false, false, true),
+            ("2001:db8::1", false, false, false, false),
+        ];
+        for (address, loopback, unspecified, link_local, multicast) in table {
+            let address_ip = ip(address);
+            assert_eq!(address_ip.is_loopback(), loopback, "{}", address);
+            assert_eq!(address_ip.is_unspecified(), unspecified, "{}", address);
+            assert_eq!(address_ip.is_link_local(), link_local, "{}", address);
+            assert_eq!(address_ip.is_multicast(), multicast, "{}", address);
+        }
+    }
+
+    #[test]
+    fn ipv4_mapped_addresses_round_trip() {
+        let ipv4 = IPv4Address::from_string("192.168.1.1").unwrap();
+        let mapped = IPv6Address::from_ipv4_mapped(ipv4);
+        assert_eq!(mapped, ip("::ffff:c0a8:101"));
+        assert_eq!(mapped.to_ipv4_mapped(), Some(ipv4));
+        // 混在表記でも読み取れる
+        assert_eq!(ip("::ffff:192.168.1.1"), mapped);
+    }
+
+    #[test]
+    fn non_mapped_addresses_have_no_ipv4() {
+        assert_eq!(ip("2001:db8::1").to_ipv4_mapped(), None);
+        assert_eq!(ip("::1").to_ipv4_mapped(), None);
+        assert_eq!(ip("::fffe:c0a8:101").to_ipv4_mapped(), None);
+    }
+
+    #[test]
+    fn from_slice_requires_exactly_16_bytes() {
+        let bytes = ip("2001:db8::1").to_array();
+        assert_eq!(IPv6Address::from_slice(&bytes), Ok(ip("2001:db8::1")));
+        assert_eq!(
+            IPv6Address::from_slice(&bytes[..15]),
+            Err(ParseError::WrongLength { expected: 16, found: 15 })
+        );
+    }
+
+    #[test]
+    fn from_string_reports_specific_parse_errors() {
+        assert_eq!(
+            IPv6Address::from_string("2001:db8:0:0:0:0:1"),
+            Err(ParseError::WrongSegmentCount { expected: 8, found: 7 })
+        );
+        assert_eq!(IPv6Address::from_string("2001:db8::zz"), Err(ParseError::InvalidHex));
+        assert_eq!(IPv6Address::from_string("2001:db8::12345"), Err(ParseError::TooManyDigits { max: 4 }));
+        assert!(matches!(IPv6Address::from_string("2001::db8::1"), Err(ParseError::InvalidFormat(_))));
+        assert!(matches!(IPv6Address::from_string("2001:db8:::1"), Err(ParseError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn from_string_reports_the_segment_count_of_uncompressed_addresses() {
+        assert_eq!(
+            IPv6Address::from_string("2001:db8:0:0:0:0:0:0:1"),
+            Err(ParseError::WrongSegmentCount { expected: 8, found: 9 })
+        );
+        assert_eq!(IPv6Address::from_string("1"), Err(ParseError::WrongSegmentCount { expected: 8, found: 1 }));
+    }
+
+    #[test]
+    fn from_string_reports_bad_segments_in_uncompressed_addresses() {
+        assert_eq!(IPv6Address::from_string("2001:db8:0:0:0:0:0:g"), Err(ParseError::InvalidHex));
+        assert_eq!(IPv6Address::from_string("2001:db8:0:0:0:0:0:-1"), Err(ParseError::InvalidHex));
+        assert_eq!(IPv6Address::from_string("02001:db8:0:0:0:0:0:1"), Err(ParseError::TooManyDigits { max: 4 }));
+        assert_eq!(
+            IPv6Address::from_string("2001:db8:0::0:0:0:0:1"),
+            Err(ParseError::InvalidFormat("Too many segments around '::' in IPv6 address"))
+        );
+        assert_eq!(
+            IPv6Address::from_string("2001:db8::1:"),
+            Err(ParseError::InvalidFormat("Empty segment in IPv6 address"))
+        );
+    }
+
+    #[test]
+    fn expanded_string_pairs_with_the_canonical_form() {
+        let address = ip("2001:db8::1");
+        assert_eq!(address.to_expanded_string(), "2001:0db8:0000:0000:0000:0000:0000:0001");
+        assert_eq!(address.to_canonical_string(), "2001:db8::1");
+        assert_eq!(ip(&address.to_expanded_string()), address);
+        assert_eq!(ip("::").to_expanded_string(), "0000:0000:0000:0000:0000:0000:0000:0000");
+        assert_eq!(ip("FE80::ABCD").to_expanded_string(), "fe80:0000:0000:0000:0000:0000:0000:abcd");
+    }
+
+    #[test]
+    fn ptr_name_reverses_every_nibble() {
+        assert_eq!(
+            ip("2001:db8::1").to_ptr_name(),
+            "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"
+        );
+    }
+}
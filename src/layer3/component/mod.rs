@@ -0,0 +1,3 @@
+pub(crate) mod router;
+
+pub use router::Router;
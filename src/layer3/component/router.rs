@@ -0,0 +1,387 @@
+use std::{fmt, sync::{Arc, Mutex, Weak}};
+use rand::Rng;
+
+use crate::layer1::{component::ethernet_cable::debug, packets::{FrameMeta, PhysicalLayerFrame}, receive_callback::PhysicalLayerCallback, EthernetCable};
+use crate::layer2::{address::MacAddress, packets::EthernetFrame, protocols::{arp::ArpOperation, ArpCache, ArpPacket}};
+use crate::layer3::{address::IPv4Address, packets::IPv4Packet};
+
+/// ルーターのインターフェース
+/// インターフェースごとにIPv4アドレスとMACアドレスを持ち、ケーブルが1本つながる
+#[derive(Clone)]
+pub struct RouterInterface {
+    pub ip    : IPv4Address,           // インターフェースのIPv4アドレス
+    pub mask  : IPv4Address,           // サブネットマスク
+    pub mac   : MacAddress,            // インターフェースのMACアドレス
+    pub cable : Option<EthernetCable>, // つながっているケーブル
+}
+
+/// ルーティングテーブルの1行
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Route {
+    pub prefix    : IPv4Address,         // 宛先ネットワーク
+    pub mask      : IPv4Address,         // 宛先ネットワークのサブネットマスク
+    pub next_hop  : Option<IPv4Address>, // 次に渡すルーターのIPv4アドレス。Noneなら宛先に直接届ける
+    pub interface : usize,               // 送り出すインターフェースの番号
+}
+
+/// Routerの本体
+pub struct RouterState {
+    pub id         : String,
+    pub interfaces : Vec<RouterInterface>,
+    pub routes     : Vec<Route>, // 静的に追加したルート。インターフェースのサブネットへのルートは含まない
+    pub arp_cache  : ArpCache,   // 次のホップのMACアドレスを調べるためのARPキャッシュ
+}
+
+impl fmt::Display for RouterState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "###Router= ")?;
+        writeln!(f, "#id                     : {}", self.id)?;
+        writeln!(f, "#interfaces             : {}", self.interfaces.len())?;
+        for (index, interface) in self.interfaces.iter().enumerate() {
+            let cable_id = interface.cable.as_ref().map(|cable| cable.get_id()).unwrap_or_else(|| "-".to_string());
+            writeln!(
+                f,
+                "#  if {:<3}               : {}/{} {} cable={}",
+                index,
+                std::net::Ipv4Addr::from(interface.ip),
+                prefix_length(interface.mask),
+                interface.mac.to_string_with_separator(':'),
+                cable_id,
+            )?;
+        }
+        writeln!(f, "#routes                 : {}", self.routes.len())?;
+        for route in &self.routes {
+            let next_hop = route.next_hop
+                .map(|ip| std::net::Ipv4Addr::from(ip).to_string())
+                .unwrap_or_else(|| "direct".to_string());
+            writeln!(
+                f,
+                "#  {}/{} via {} if {}",
+                std::net::Ipv4Addr::from(route.prefix),
+                prefix_length(route.mask),
+                next_hop,
+                route.interface,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl RouterState {
+    /// 宛先に一番長く一致するルートを探す(最長一致)
+    /// インターフェースのサブネットは、直接つながっているルートとして扱う
+    fn lookup_route(&self, dst_ip: IPv4Address) -> Option<Route> {
+        let connected = self.interfaces.iter().enumerate().map(|(index, interface)| Route {
+            prefix    : interface.ip,
+            mask      : interface.mask,
+            next_hop  : None,
+            interface : index,
+        });
+        connected
+            .chain(self.routes.iter().copied())
+            .filter(|route| in_subnet(dst_ip, route.prefix, route.mask))
+            // 同じ長さなら先にあるもの(直接つながっているルート)を優先する
+            .rev()
+            .max_by_key(|route| prefix_length(route.mask))
+    }
+}
+
+/// サブネットマスクのプレフィックス長(先頭から続く1のビットの数)
+fn prefix_length(mask: IPv4Address) -> u32 {
+    mask.to_u32().leading_ones()
+}
+
+/// ipがprefix/maskのネットワークに含まれるかどうか
+fn in_subnet(ip: IPv4Address, prefix: IPv4Address, mask: IPv4Address) -> bool {
+    let mask = mask.to_u32();
+    ip.to_u32() & mask == prefix.to_u32() & mask
+}
+
+/// IPv4ルーター
+/// インターフェースに届いたIPv4パケットを、ルーティングテーブルの最長一致で選んだインターフェースから送り出す
+/// 送り出す時はTTLを1減らし、新しいイーサネットフレームに入れ直す
+/// 
+/// 次のホップのMACアドレスはARPキャッシュで調べる。わからない場合はARPリクエストを送り、そのパケットは破棄する
+/// 自分のインターフェースのIPv4アドレスへのARPリクエストには応答する
+#[derive(Clone)]
+pub struct Router {
+    state : Arc<Mutex<RouterState>>,
+}
+
+impl fmt::Display for Router {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        write!(f, "{}", *state)
+    }
+}
+
+impl Router {
+    /// 新規にルーターを配置したとき
+    pub fn new(id: Option<String>) -> Self {
+        debug("Router::new([id]) called.");
+        let router_id = id.unwrap_or_else(|| format!("router-{}", rand::thread_rng().gen_range(9..9999)));
+        Router {
+            state: Arc::new(Mutex::new(RouterState {
+                id         : router_id,
+                interfaces : Vec::new(),
+                routes     : Vec::new(),
+                arp_cache  : ArpCache::new(),
+            })),
+        }
+    }
+
+    /// そのルーターのIdを取得
+    pub fn get_id(&self) -> String {
+        let state = self.state.lock().unwrap();
+        state.id.clone()
+    }
+
+    /// インターフェースを追加する
+    /// インターフェースのサブネットは、直接つながっているルートとして使われる
+    /// 
+    /// ### 戻り値
+    /// * 追加したインターフェースの番号
+    pub fn add_interface(&self, ip: IPv4Address, mask: IPv4Address) -> usize {
+        let mut state = self.state.lock().unwrap();
+        state.interfaces.push(RouterInterface {
+            ip,
+            mask,
+            mac   : MacAddress::new(),
+            cable : None,
+        });
+        state.interfaces.len() - 1
+    }
+
+    /// インターフェースのMACアドレスを取得
+    pub fn get_interface_mac(&self, interface: usize) -> Option<MacAddress> {
+        let state = self.state.lock().unwrap();
+        state.interfaces.get(interface).map(|interface| interface.mac)
+    }
+
+    /// ルートを追加する
+    /// next_hopがNoneの場合は、宛先に直接届ける
+    pub fn add_route(&self, prefix: IPv4Address, mask: IPv4Address, next_hop: Option<IPv4Address>, interface: usize) -> Result<(), &'static str> {
+        let mut state = self.state.lock().unwrap();
+        if interface >= state.interfaces.len() {
+            return Err("Interface number is out of range");
+        }
+        state.routes.push(Route { prefix, mask, next_hop, interface });
+        Ok(())
+    }
+
+    /// ケーブルがつながっていない最初のインターフェースの番号を取得
+    /// 全てのインターフェースが使われていればNone
+    pub fn free_interface(&self) -> Option<usize> {
+        let state = self.state.lock().unwrap();
+        state.interfaces.iter().position(|interface| interface.cable.is_none())
+    }
+
+    /// 指定したインターフェースにケーブルをつなぐ
+    /// ルーターのIdでケーブルの端につなぎ、届いたフレームを受け取るcallbackを設定する
+    pub fn connect_cable(&self, interface: usize, cable: EthernetCable) -> Result<(), &'static str> {
+        let mut state = self.state.lock().unwrap();
+        match state.interfaces.get(interface) {
+            None => return Err("Interface number is out of range"),
+            Some(RouterInterface { cable: Some(_), .. }) => return Err("Interface is already in use"),
+            Some(_) => {}
+        }
+        let router_id = state.id.clone();
+
+        // ケーブルがcallbackを持ち、ルーターがケーブルを持つので、循環参照にならないようにWeakで持つ
+        let router_state = Arc::downgrade(&self.state);
+        let callback: PhysicalLayerCallback = Arc::new(move |frame: PhysicalLayerFrame| {
+            Self::receive(&router_state, interface, frame);
+        });
+        if !cable.attach(router_id, callback) {
+            return Err("Both endpoints of the cable are already in use");
+        }
+        state.interfaces[interface].cable = Some(cable);
+        Ok(())
+    }
+
+    /// in_interfaceに届いたフレームを処理する
+    /// 転送するIPv4パケットは新しいフレームに入れ直すが、同じパケットだとわかるようにtrace_idなどのFrameMetaは引き継ぐ
+    fn receive(router_state: &Weak<Mutex<RouterState>>, in_interface: usize, physical_frame: PhysicalLayerFrame) {
+        let Some(router_state) = router_state.upgrade() else {
+            return;
+        };
+        let frame = physical_frame.ethernet_frame;
+        let mut state = router_state.lock().unwrap();
+        let in_mac = state.interfaces[in_interface].mac;
+        if frame.dst_mac != in_mac && frame.dst_mac != MacAddress::get_broadcast_mac_addr() {
+            // 自分宛てでないフレームは受け取らない
+            return;
+        }
+
+        let outgoing = match frame.ethertype {
+            ArpPacket::ETHERTYPE => Self::handle_arp(&mut state, in_interface, &frame),
+            IPv4Packet::ETHERTYPE => Self::route_ipv4(&mut state, in_interface, &frame),
+            _ => None,
+        };
+        let Some((out_interface, out_frame)) = outgoing else {
+            return;
+        };
+        // ARPのフレームはこのルーターが新しく作ったものなので、FrameMetaは引き継がない
+        let meta = if out_frame.ethertype == IPv4Packet::ETHERTYPE { physical_frame.meta } else { FrameMeta::default() };
+        let mut out_frame = PhysicalLayerFrame::new(Some(out_frame));
+        out_frame.meta = meta;
+        let router_id = state.id.clone();
+        let out_cable = state.interfaces[out_interface].cable.clone();
+        // 送り先でまたこのルーターにフレームが届くことがあるので、ロックを外してから送る
+        drop(state);
+        match out_cable {
+            Some(cable) => cable.transmit_signal(router_id, out_frame),
+            None => debug(&format!("Router({})::interface {} has no cable.", router_id, out_interface)),
+        }
+    }
+
+    /// ARPパケットから送信元を学習し、自分宛てのARPリクエストにはリプライを返す
+    fn handle_arp(state: &mut RouterState, in_interface: usize, frame: &EthernetFrame) -> Option<(usize, EthernetFrame)> {
+        let arp = ArpPacket::from_bytes(&frame.data).ok()?;
+        state.arp_cache.insert(arp.sender_ip, arp.sender_mac);
+
+        let interface = &state.interfaces[in_interface];
+        if arp.oper != ArpOperation::Request || arp.target_ip != interface.ip {
+            return None;
+        }
+        let reply = ArpPacket::reply(interface.mac, interface.ip, arp.sender_mac, arp.sender_ip);
+        Some((in_interface, reply.to_ethernet_frame()))
+    }
+
+    /// IPv4パケットを最長一致で選んだインターフェースから送り出す
+    fn route_ipv4(state: &mut RouterState, in_interface: usize, frame: &EthernetFrame) -> Option<(usize, EthernetFrame)> {
+        let mut packet = IPv4Packet::from_bytes(&frame.data).ok()?;
+        // 直接つながっているサブネットからのパケットなら、送信元のMACアドレスを覚えておく
+        let in_if = &state.interfaces[in_interface];
+        if in_subnet(packet.src_ip, in_if.ip, in_if.mask) {
+            state.arp_cache.insert(packet.src_ip, frame.src_mac);
+        }
+        if state.interfaces.iter().any(|interface| interface.ip == packet.dst_ip) {
+            debug(&format!("Router({})::packet to the router itself is not handled yet.", state.id));
+            return None;
+        }
+        // 制限ブロードキャスト(255.255.255.255)は届いたサブネットの中だけのものなので、ルートを探さずに破棄する
+        if packet.dst_ip.is_broadcast() {
+            debug(&format!("Router({})::broadcast packet is not forwarded.", state.id));
+            return None;
+        }
+
+        let Some(route) = state.lookup_route(packet.dst_ip) else {
+            debug(&format!("Router({})::no route to {}.", state.id, std::net::Ipv4Addr::from(packet.dst_ip)));
+            return None;
+        };
+        if let Err(error) = packet.forward() {
+            debug(&format!("Router({})::{}. packet is discarded.", state.id, error));
+            return None;
+        }
+
+        let out_if = &state.interfaces[route.interface];
+        let next_hop = route.next_hop.unwrap_or(packet.dst_ip);
+        match state.arp_cache.lookup(&next_hop) {
+            Some(next_hop_mac) => Some((
+                route.interface,
+                EthernetFrame::builder()
+                    .dst(next_hop_mac)
+                    .src(out_if.mac)
+                    .ethertype(IPv4Packet::ETHERTYPE)
+                    .payload(packet.to_bytes())
+                    .build(),
+            )),
+            None => {
+                // 次のホップのMACアドレスがわからないので、ARPで問い合わせる。このパケットは破棄する
+                debug(&format!("Router({})::MAC address of next hop is unknown. sending ARP request.", state.id));
+                let request = ArpPacket::request(out_if.mac, out_if.ip, next_hop);
+                Some((route.interface, request.to_ethernet_frame()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Received = Arc<Mutex<Vec<PhysicalLayerFrame>>>;
+
+    const MASK_24: IPv4Address = IPv4Address([255, 255, 255, 0]);
+    const HOST_A_IP: IPv4Address = IPv4Address([192, 168, 1, 10]);
+    const HOST_B_IP: IPv4Address = IPv4Address([192, 168, 2, 10]);
+    const HOST_A_MAC: MacAddress = MacAddress([0x02, 0x00, 0x00, 0x00, 0x00, 0x0A]);
+    const HOST_B_MAC: MacAddress = MacAddress([0x02, 0x00, 0x00, 0x00, 0x00, 0x0B]);
+
+    /// ホストをつないだケーブルと、そのホストに届いたフレームの記録
+    fn host_cable(host_id: &str) -> (EthernetCable, Received) {
+        let cable = EthernetCable::new(Some(format!("cable-{}", host_id)));
+        cable.connect_endpoint1(Some(host_id.to_string())).unwrap();
+        let received = Received::default();
+        let record = received.clone();
+        let callback: PhysicalLayerCallback = Arc::new(move |frame| record.lock().unwrap().push(frame));
+        cable.set_callback(host_id.to_string(), callback).unwrap();
+        (cable, received)
+    }
+
+    /// 192.168.1.0/24(インターフェース0)と192.168.2.0/24(インターフェース1)をつなぐルーター
+    /// host-aとhost-bがそれぞれのサブネットにつながっていて、host-bのMACアドレスはARPで学習済み
+    fn two_subnet_router() -> (Router, EthernetCable, Received) {
+        let router = Router::new(Some("router-1".to_string()));
+        router.add_interface(IPv4Address([192, 168, 1, 1]), MASK_24);
+        router.add_interface(IPv4Address([192, 168, 2, 1]), MASK_24);
+        let (cable_a, _) = host_cable("host-a");
+        let (cable_b, received_b) = host_cable("host-b");
+        router.connect_cable(0, cable_a.clone()).unwrap();
+        router.connect_cable(1, cable_b).unwrap();
+        router.state.lock().unwrap().arp_cache.insert(HOST_B_IP, HOST_B_MAC);
+        (router, cable_a, received_b)
+    }
+
+    /// host-aからルーターのインターフェース0に宛てたIPv4パケットのフレーム
+    fn frame_to_router(router: &Router, packet: &IPv4Packet) -> PhysicalLayerFrame {
+        let frame = EthernetFrame::builder()
+            .dst(router.get_interface_mac(0).unwrap())
+            .src(HOST_A_MAC)
+            .ethertype(IPv4Packet::ETHERTYPE)
+            .payload(packet.to_bytes())
+            .build();
+        PhysicalLayerFrame::new(Some(frame))
+    }
+
+    #[test]
+    fn trace_id_is_preserved_on_the_egress_frame() {
+        let (router, cable_a, received_b) = two_subnet_router();
+        let mut frame = frame_to_router(&router, &IPv4Packet::new(HOST_A_IP, HOST_B_IP, IPv4Packet::PROTOCOL_UDP, vec![1, 2, 3]));
+        frame.meta.trace_id = 42;
+
+        cable_a.transmit_signal("host-a".to_string(), frame);
+
+        let received = received_b.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].meta.trace_id, 42);
+        assert_eq!(received[0].ethernet_frame.src_mac, router.get_interface_mac(1).unwrap());
+        assert_eq!(received[0].ethernet_frame.dst_mac, HOST_B_MAC);
+    }
+
+    #[test]
+    fn packets_are_routed_between_the_two_subnets() {
+        let (router, cable_a, received_b) = two_subnet_router();
+        let packet = IPv4Packet::new(HOST_A_IP, HOST_B_IP, IPv4Packet::PROTOCOL_UDP, vec![1, 2, 3]);
+        cable_a.transmit_signal("host-a".to_string(), frame_to_router(&router, &packet));
+
+        let received = received_b.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        let forwarded = IPv4Packet::from_bytes(&received[0].ethernet_frame.data).unwrap();
+        assert_eq!((forwarded.src_ip, forwarded.dst_ip), (HOST_A_IP, HOST_B_IP));
+        assert_eq!(forwarded.ttl, packet.ttl - 1);
+        assert_eq!(forwarded.header_checksum, forwarded.compute_checksum());
+        assert_eq!(forwarded.payload, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn limited_broadcast_is_not_forwarded_even_with_a_default_route() {
+        let (router, cable_a, received_b) = two_subnet_router();
+        router.add_route(IPv4Address([0, 0, 0, 0]), IPv4Address([0, 0, 0, 0]), Some(HOST_B_IP), 1).unwrap();
+        let packet = IPv4Packet::new(HOST_A_IP, IPv4Address([255, 255, 255, 255]), IPv4Packet::PROTOCOL_UDP, vec![1]);
+        cable_a.transmit_signal("host-a".to_string(), frame_to_router(&router, &packet));
+
+        assert!(received_b.lock().unwrap().is_empty());
+    }
+}
@@ -1,4 +1,7 @@
 pub(crate) mod address;
+pub(crate) mod component;
+pub(crate) mod packets;
+pub(crate) mod protocols;
 
 pub use address::IPv4Address;
-pub use address::IPv6Address;
\ No newline at end of file
+pub use address::IPv6Address;
@@ -0,0 +1,278 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::layer3::address::IPv4Address;
+use crate::util::internet_checksum;
+
+/// IPv4パケット(RFC 791)
+/// ヘッダの各フィールドはそのままバイト列に書き込むので、ihlやtotal_lengthを書き換えて壊れたパケットも作れる
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IPv4Packet {
+    pub version         : u8,          // バージョン (4ビット) IPv4は4
+    pub ihl             : u8,          // ヘッダ長 (4ビット) 4バイト単位。オプションがなければ5
+    pub dscp            : u8,          // DiffServコードポイント (6ビット)
+    pub ecn             : u8,          // 明示的輻輳通知 (2ビット)
+    pub total_length    : u16,         // ヘッダとペイロードを合わせた長さ (2バイト)
+    pub identification  : u16,         // フラグメントを識別するId (2バイト)
+    pub flags           : u8,          // フラグ (3ビット) 0b010がDon't Fragment、0b001がMore Fragments
+    pub fragment_offset : u16,         // フラグメントオフセット (13ビット) 8バイト単位
+    pub ttl             : u8,          // 生存時間 (1バイト) ルーターを通るたびに1減る
+    pub protocol        : u8,          // 上位のプロトコル (1バイト) ICMPは1、TCPは6、UDPは17
+    pub header_checksum : u16,         // ヘッダチェックサム (2バイト)
+    pub src_ip          : IPv4Address, // 送信元IPv4アドレス (4バイト)
+    pub dst_ip          : IPv4Address, // 宛先IPv4アドレス (4バイト)
+    pub options         : Vec<u8>,     // オプション (0〜40バイト) 4バイト単位になるように0で埋める
+    pub payload         : Vec<u8>,     // ペイロード
+}
+
+impl fmt::Display for IPv4Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let formatted_payload: Vec<String> = self.payload
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect();
+        write!(
+            f,
+            "#version         : {}\n\
+             #ihl             : {}\n\
+             #dscp            : {}\n\
+             #ecn             : {}\n\
+             #total_length    : {}\n\
+             #identification  : {:04X}\n\
+             #flags           : {:03b}\n\
+             #fragment_offset : {}\n\
+             #ttl             : {}\n\
+             #protocol        : {}\n\
+             #header_checksum : {:04X}\n\
+             #src_ip          : {}\n\
+             #dst_ip          : {}\n\
+             #options         : {} bytes\n\
+             #payload         : [{}]\n",
+            self.version,
+            self.ihl,
+            self.dscp,
+            self.ecn,
+            self.total_length,
+            self.identification,
+            self.flags,
+            self.fragment_offset,
+            self.ttl,
+            self.protocol,
+            self.header_checksum,
+            self.src_ip,
+            self.dst_ip,
+            self.options.len(),
+            formatted_payload.join(" "),
+        )
+    }
+}
+
+impl IPv4Packet {
+    /// オプションなしのヘッダの長さ(バイト)
+    pub const MIN_HEADER_LENGTH: usize = 20;
+    /// IPv4パケットを運ぶイーサネットフレームのイーサタイプ
+    pub const ETHERTYPE: u16 = 0x0800;
+    /// protocolフィールドの値
+    pub const PROTOCOL_TCP: u8 = 6;
+    pub const PROTOCOL_UDP: u8 = 17;
+
+    /// 新しいパケットを生成
+    /// オプションなし、TTLは64、Don't Fragmentを立て、ヘッダ長/全体長/チェックサムは計算して埋める
+    pub fn new(src_ip: IPv4Address, dst_ip: IPv4Address, protocol: u8, payload: Vec<u8>) -> Self {
+        let mut packet = Self {
+            version         : 4,
+            ihl             : 5,
+            dscp            : 0,
+            ecn             : 0,
+            total_length    : (Self::MIN_HEADER_LENGTH + payload.len()) as u16,
+            identification  : 0,
+            flags           : 0b010,
+            fragment_offset : 0,
+            ttl             : 64,
+            protocol,
+            header_checksum : 0,
+            src_ip,
+            dst_ip,
+            options         : Vec::new(),
+            payload,
+        };
+        packet.header_checksum = packet.compute_checksum();
+        packet
+    }
+
+    /// ヘッダの長さ(バイト)
+    /// オプションを4バイト単位になるように0で埋めた長さ
+    pub fn header_length(&self) -> usize {
+        Self::MIN_HEADER_LENGTH + self.options.len().div_ceil(4) * 4
+    }
+
+    /// ヘッダチェックサムを計算する
+    /// チェックサムのフィールドを0にしたヘッダに対するインターネットチェックサム
+    pub fn compute_checksum(&self) -> u16 {
+        internet_checksum(&self.header_bytes(0))
+    }
+
+    /// UDP/TCPのチェックサムの計算に使う疑似ヘッダ(12バイト)
+    /// 送信元IP + 宛先IP + 0x00 + プロトコル番号 + UDP/TCPの長さ
+    pub fn pseudo_header(src_ip: IPv4Address, dst_ip: IPv4Address, protocol: u8, length: u16) -> [u8; 12] {
+        let src = src_ip.to_array();
+        let dst = dst_ip.to_array();
+        let length = length.to_be_bytes();
+        [
+            src[0], src[1], src[2], src[3],
+            dst[0], dst[1], dst[2], dst[3],
+            0, protocol, length[0], length[1],
+        ]
+    }
+
+    /// ルーターがパケットを次のホップに送る時の処理をする
+    /// TTLを1減らし、ヘッダが変わるのでチェックサムを計算し直す
+    /// TTLが0になった場合はエラーを返す。ルーターはパケットを破棄して、ICMPの時間超過を送り返す
+    pub fn forward(&mut self) -> Result<(), &'static str> {
+        self.ttl = self.ttl.saturating_sub(1);
+        self.header_checksum = self.compute_checksum();
+        if self.ttl == 0 {
+            return Err("TTL exceeded in transit");
+        }
+        Ok(())
+    }
+
+    /// バイト配列に変換
+    /// 2バイトのフィールドはネットワークバイトオーダー(ビッグエンディアン)で書き込む
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.header_bytes(self.header_checksum);
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
+
+    /// 指定したチェックサムでヘッダをバイト配列にする
+    fn header_bytes(&self, header_checksum: u16) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.header_length());
+        bytes.push((self.version << 4) | (self.ihl & 0x0F));
+        bytes.push((self.dscp << 2) | (self.ecn & 0x03));
+        bytes.extend_from_slice(&self.total_length.to_be_bytes());
+        bytes.extend_from_slice(&self.identification.to_be_bytes());
+        let flags_and_offset = ((self.flags as u16 & 0x07) << 13) | (self.fragment_offset & 0x1FFF);
+        bytes.extend_from_slice(&flags_and_offset.to_be_bytes());
+        bytes.push(self.ttl);
+        bytes.push(self.protocol);
+        bytes.extend_from_slice(&header_checksum.to_be_bytes());
+        bytes.extend_from_slice(&self.src_ip.to_array());
+        bytes.extend_from_slice(&self.dst_ip.to_array());
+        bytes.extend_from_slice(&self.options);
+        // オプションは4バイト単位になるように0で埋める
+        bytes.resize(self.header_length(), 0);
+        bytes
+    }
+
+    /// バイト配列からIPv4Packetを構築
+    /// total_lengthより後ろのバイト(イーサネットのパディングなど)は無視する
+    pub fn from_bytes(bytes: &[u8]) -> Result<IPv4Packet, &'static str> {
+        if bytes.len() < Self::MIN_HEADER_LENGTH {
+            return Err("IPv4 packet must be at least 20 bytes");
+        }
+        let version = bytes[0] >> 4;
+        if version != 4 {
+            return Err("IPv4 packet version must be 4");
+        }
+        let ihl = bytes[0] & 0x0F;
+        let header_length = ihl as usize * 4;
+        if header_length < Self::MIN_HEADER_LENGTH {
+            return Err("IPv4 header length must be at least 20 bytes");
+        }
+        let total_length = u16::from_be_bytes([bytes[2], bytes[3]]);
+        if (total_length as usize) < header_length || total_length as usize > bytes.len() {
+            return Err("Invalid total length in IPv4 packet");
+        }
+
+        let flags_and_offset = u16::from_be_bytes([bytes[6], bytes[7]]);
+        let mut src_ip = [0u8; 4];
+        src_ip.copy_from_slice(&bytes[12..16]);
+        let mut dst_ip = [0u8; 4];
+        dst_ip.copy_from_slice(&bytes[16..20]);
+
+        Ok(IPv4Packet {
+            version,
+            ihl,
+            dscp            : bytes[1] >> 2,
+            ecn             : bytes[1] & 0x03,
+            total_length,
+            identification  : u16::from_be_bytes([bytes[4], bytes[5]]),
+            flags           : (flags_and_offset >> 13) as u8,
+            fragment_offset : flags_and_offset & 0x1FFF,
+            ttl             : bytes[8],
+            protocol        : bytes[9],
+            header_checksum : u16::from_be_bytes([bytes[10], bytes[11]]),
+            src_ip          : IPv4Address::from_array(src_ip),
+            dst_ip          : IPv4Address::from_array(dst_ip),
+            options         : bytes[Self::MIN_HEADER_LENGTH..header_length].to_vec(),
+            payload         : bytes[header_length..total_length as usize].to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IPv4Address {
+        IPv4Address::from_string(s).unwrap()
+    }
+
+    #[test]
+    fn new_fills_the_header_with_a_known_checksum() {
+        // 45 00 00 73 00 00 40 00 40 11 B8 61 C0 A8 00 01 C0 A8 00 C7
+        let packet = IPv4Packet::new(ip("192.168.0.1"), ip("192.168.0.199"), IPv4Packet::PROTOCOL_UDP, vec![0; 95]);
+        assert_eq!(packet.total_length, 115);
+        assert_eq!(packet.ttl, 64);
+        assert_eq!(packet.header_checksum, 0xB861);
+        assert_eq!(
+            packet.to_bytes()[..20],
+            [0x45, 0x00, 0x00, 0x73, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0xB8, 0x61, 0xC0, 0xA8, 0x00, 0x01, 0xC0, 0xA8, 0x00, 0xC7]
+        );
+    }
+
+    #[test]
+    fn round_trips_through_bytes_ignoring_ethernet_padding() {
+        let mut packet = IPv4Packet::new(ip("10.0.0.1"), ip("10.0.0.2"), IPv4Packet::PROTOCOL_TCP, vec![1, 2, 3]);
+        packet.options = vec![0x01, 0x01, 0x01, 0x01];
+        packet.ihl = 6;
+        packet.total_length = (packet.header_length() + packet.payload.len()) as u16;
+        packet.header_checksum = packet.compute_checksum();
+
+        let mut bytes = packet.to_bytes();
+        bytes.resize(46, 0);
+        assert_eq!(IPv4Packet::from_bytes(&bytes).unwrap(), packet);
+    }
+
+    #[test]
+    fn from_bytes_rejects_malformed_headers() {
+        let bytes = IPv4Packet::new(ip("10.0.0.1"), ip("10.0.0.2"), IPv4Packet::PROTOCOL_UDP, vec![0; 8]).to_bytes();
+        assert!(IPv4Packet::from_bytes(&bytes[..19]).is_err());
+        assert!(IPv4Packet::from_bytes(&bytes[..27]).is_err());
+
+        let mut ipv6 = bytes.clone();
+        ipv6[0] = 0x65;
+        assert!(IPv4Packet::from_bytes(&ipv6).is_err());
+    }
+
+    #[test]
+    fn forwarding_with_ttl_one_is_an_error() {
+        let mut packet = IPv4Packet::new(ip("10.0.0.1"), ip("10.0.0.2"), IPv4Packet::PROTOCOL_UDP, Vec::new());
+        packet.ttl = 1;
+        assert!(packet.forward().is_err());
+        assert_eq!(packet.ttl, 0);
+    }
+
+    #[test]
+    fn forwarding_decrements_ttl_and_keeps_the_checksum_valid() {
+        let mut packet = IPv4Packet::new(ip("10.0.0.1"), ip("10.0.0.2"), IPv4Packet::PROTOCOL_UDP, Vec::new());
+        let checksum_before = packet.header_checksum;
+        assert!(packet.forward().is_ok());
+        assert_eq!(packet.ttl, 63);
+        assert_ne!(packet.header_checksum, checksum_before);
+        assert_eq!(packet.header_checksum, packet.compute_checksum());
+        // 正しいチェックサムの入ったヘッダ全体のインターネットチェックサムは0になる
+        assert_eq!(internet_checksum(&packet.to_bytes()[..20]), 0);
+    }
+}
@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::Ipv6Addr;
+
+use crate::layer3::address::IPv6Address;
+
+/// IPv6パケット(RFC 8200)
+/// 40バイトの固定長のヘッダの後ろにペイロードが続く。IPv4と違ってヘッダチェックサムはない
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IPv6Packet {
+    pub version        : u8,          // バージョン (4ビット) IPv6は6
+    pub traffic_class  : u8,          // トラフィッククラス (1バイト) IPv4のDSCP/ECNにあたる
+    pub flow_label     : u32,         // フローラベル (20ビット)
+    pub payload_length : u16,         // ペイロードの長さ (2バイト) ヘッダは含まない
+    pub next_header    : u8,          // 次のヘッダ (1バイト) TCPは6、UDPは17、ICMPv6は58
+    pub hop_limit      : u8,          // ホップ制限 (1バイト) IPv4のTTLにあたり、ルーターを通るたびに1減る
+    pub src            : IPv6Address, // 送信元IPv6アドレス (16バイト)
+    pub dst            : IPv6Address, // 宛先IPv6アドレス (16バイト)
+    pub payload        : Vec<u8>,     // ペイロード
+}
+
+impl fmt::Display for IPv6Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let formatted_payload: Vec<String> = self.payload
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect();
+        write!(
+            f,
+            "#version        : {}\n\
+             #traffic_class  : {:02X}\n\
+             #flow_label     : {:05X}\n\
+             #payload_length : {}\n\
+             #next_header    : {}\n\
+             #hop_limit      : {}\n\
+             #src            : {}\n\
+             #dst            : {}\n\
+             #payload        : [{}]\n",
+            self.version,
+            self.traffic_class,
+            self.flow_label,
+            self.payload_length,
+            self.next_header,
+            self.hop_limit,
+            Ipv6Addr::from(self.src),
+            Ipv6Addr::from(self.dst),
+            formatted_payload.join(" "),
+        )
+    }
+}
+
+impl IPv6Packet {
+    /// ヘッダの長さ(バイト)
+    pub const HEADER_LENGTH: usize = 40;
+    /// IPv6パケットを運ぶイーサネットフレームのイーサタイプ
+    pub const ETHERTYPE: u16 = 0x86DD;
+    /// ホップ制限の初期値
+    pub const DEFAULT_HOP_LIMIT: u8 = 64;
+
+    /// 新しいパケットを生成
+    /// トラフィッククラスとフローラベルは0、ホップ制限は64にし、ペイロードの長さは計算して埋める
+    pub fn new(src: IPv6Address, dst: IPv6Address, next_header: u8, payload: Vec<u8>) -> Self {
+        Self {
+            version        : 6,
+            traffic_class  : 0,
+            flow_label     : 0,
+            payload_length : payload.len() as u16,
+            next_header,
+            hop_limit      : Self::DEFAULT_HOP_LIMIT,
+            src,
+            dst,
+            payload,
+        }
+    }
+
+    /// ルーターがパケットを次のホップに送る時に、ホップ制限を1減らす
+    /// ヘッダチェックサムがないので、IPv4と違って計算し直す必要はない
+    /// ホップ制限が0になった場合はエラーを返す。ルーターはパケットを破棄して、ICMPv6の時間超過を送り返す
+    pub fn decrement_hop_limit(&mut self) -> Result<(), &'static str> {
+        self.hop_limit = self.hop_limit.saturating_sub(1);
+        if self.hop_limit == 0 {
+            return Err("Hop limit exceeded in transit");
+        }
+        Ok(())
+    }
+
+    /// バイト配列に変換
+    /// 2バイト以上のフィールドはネットワークバイトオーダー(ビッグエンディアン)で書き込む
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::HEADER_LENGTH + self.payload.len());
+        // 先頭の4バイトは バージョン(4ビット) + トラフィッククラス(8ビット) + フローラベル(20ビット)
+        let first_word = ((self.version as u32 & 0x0F) << 28)
+            | ((self.traffic_class as u32) << 20)
+            | (self.flow_label & 0x000F_FFFF);
+        bytes.extend_from_slice(&first_word.to_be_bytes());
+        bytes.extend_from_slice(&self.payload_length.to_be_bytes());
+        bytes.push(self.next_header);
+        bytes.push(self.hop_limit);
+        bytes.extend_from_slice(&self.src.to_array());
+        bytes.extend_from_slice(&self.dst.to_array());
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
+
+    /// バイト配列からIPv6Packetを構築
+    /// payload_lengthより後ろのバイト(イーサネットのパディングなど)は無視する
+    pub fn from_bytes(bytes: &[u8]) -> Result<IPv6Packet, &'static str> {
+        if bytes.len() < Self::HEADER_LENGTH {
+            return Err("IPv6 packet must be at least 40 bytes");
+        }
+        let first_word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let version = (first_word >> 28) as u8;
+        if version != 6 {
+            return Err("IPv6 packet version must be 6");
+        }
+        let payload_length = u16::from_be_bytes([bytes[4], bytes[5]]);
+        let end = Self::HEADER_LENGTH + payload_length as usize;
+        if end > bytes.len() {
+            return Err("Invalid payload length in IPv6 packet");
+        }
+
+        let mut src = [0u8; 16];
+        src.copy_from_slice(&bytes[8..24]);
+        let mut dst = [0u8; 16];
+        dst.copy_from_slice(&bytes[24..40]);
+
+        Ok(IPv6Packet {
+            version,
+            traffic_class  : (first_word >> 20) as u8,
+            flow_label     : first_word & 0x000F_FFFF,
+            payload_length,
+            next_header    : bytes[6],
+            hop_limit      : bytes[7],
+            src            : IPv6Address::from_array(src),
+            dst            : IPv6Address::from_array(dst),
+            payload        : bytes[Self::HEADER_LENGTH..end].to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IPv6Address {
+        IPv6Address::from_string(s).unwrap()
+    }
+
+    #[test]
+    fn round_trips_fixed_header_and_payload() {
+        let mut packet = IPv6Packet::new(ip("2001:db8::1"), ip("2001:db8::2"), 17, vec![0xDE, 0xAD, 0xBE, 0xEF]);
+        packet.traffic_class = 0xB8;
+        packet.flow_label = 0x12345;
+        let bytes = packet.to_bytes();
+        assert_eq!(bytes.len(), IPv6Packet::HEADER_LENGTH + 4);
+        assert_eq!(&bytes[0..4], &[0x6B, 0x81, 0x23, 0x45]);
+        assert_eq!(&bytes[4..8], &[0x00, 0x04, 17, IPv6Packet::DEFAULT_HOP_LIMIT]);
+        assert_eq!(&bytes[8..24], &packet.src.to_array());
+        assert_eq!(&bytes[24..40], &packet.dst.to_array());
+        assert_eq!(&bytes[40..], &[0xDE, 0xAD, 0xBE, 0xEF]);
+        assert_eq!(IPv6Packet::from_bytes(&bytes).unwrap(), packet);
+    }
+
+    #[test]
+    fn from_bytes_ignores_trailing_padding() {
+        let packet = IPv6Packet::new(ip("fe80::1"), ip("ff02::1"), 58, vec![1, 2]);
+        let mut bytes = packet.to_bytes();
+        bytes.extend_from_slice(&[0; 6]);
+        assert_eq!(IPv6Packet::from_bytes(&bytes).unwrap(), packet);
+    }
+
+    #[test]
+    fn from_bytes_rejects_invalid_packets() {
+        let bytes = IPv6Packet::new(ip("::1"), ip("::1"), 17, vec![1, 2, 3]).to_bytes();
+        assert!(IPv6Packet::from_bytes(&bytes[..39]).is_err());
+        assert!(IPv6Packet::from_bytes(&bytes[..42]).is_err());
+        let mut ipv4 = bytes.clone();
+        ipv4[0] = 0x45;
+        assert!(IPv6Packet::from_bytes(&ipv4).is_err());
+    }
+
+    #[test]
+    fn decrement_hop_limit_fails_at_zero() {
+        let mut packet = IPv6Packet::new(ip("::1"), ip("::2"), 17, Vec::new());
+        packet.hop_limit = 2;
+        assert!(packet.decrement_hop_limit().is_ok());
+        assert_eq!(packet.hop_limit, 1);
+        assert!(packet.decrement_hop_limit().is_err());
+        assert_eq!(packet.hop_limit, 0);
+        assert!(packet.decrement_hop_limit().is_err());
+    }
+}
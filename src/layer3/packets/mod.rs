@@ -0,0 +1,5 @@
+pub(crate) mod ipv4_packet;
+pub(crate) mod ipv6_packet;
+
+pub use ipv4_packet::IPv4Packet;
+pub use ipv6_packet::IPv6Packet;
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::util::internet_checksum;
+
+/// ICMPメッセージ(RFC 792)
+/// IPv4パケットのprotocolが1の時のペイロード
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IcmpMessage {
+    pub icmp_type      : u8,      // タイプ (1バイト) エコー応答は0、エコー要求は8
+    pub code           : u8,      // コード (1バイト)
+    pub checksum       : u16,     // チェックサム (2バイト) ICMPメッセージ全体に対するもの
+    pub rest_of_header : [u8; 4], // タイプによって意味が変わる残りのヘッダ (4バイト) エコーでは識別子 + シーケンス番号
+    pub payload        : Vec<u8>, // データ
+}
+
+impl fmt::Display for IcmpMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let formatted_payload: Vec<String> = self.payload
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect();
+        write!(
+            f,
+            "#type           : {}\n\
+             #code           : {}\n\
+             #checksum       : {:04X}\n\
+             #rest_of_header : {:02X} {:02X} {:02X} {:02X}\n\
+             #payload        : [{}]\n",
+            self.icmp_type,
+            self.code,
+            self.checksum,
+            self.rest_of_header[0],
+            self.rest_of_header[1],
+            self.rest_of_header[2],
+            self.rest_of_header[3],
+            formatted_payload.join(" "),
+        )
+    }
+}
+
+impl IcmpMessage {
+    /// ヘッダの長さ(バイト)
+    pub const HEADER_LENGTH: usize = 8;
+    /// typeフィールドの値
+    pub const TYPE_ECHO_REPLY: u8 = 0;
+    pub const TYPE_ECHO_REQUEST: u8 = 8;
+
+    /// ping(エコー要求)を生成
+    pub fn echo_request(identifier: u16, sequence: u16, payload: Vec<u8>) -> Self {
+        Self::new_echo(Self::TYPE_ECHO_REQUEST, identifier, sequence, payload)
+    }
+
+    /// pingへの応答(エコー応答)を生成
+    /// identifier/sequence/payloadはエコー要求と同じものを返す
+    pub fn echo_reply(identifier: u16, sequence: u16, payload: Vec<u8>) -> Self {
+        Self::new_echo(Self::TYPE_ECHO_REPLY, identifier, sequence, payload)
+    }
+
+    fn new_echo(icmp_type: u8, identifier: u16, sequence: u16, payload: Vec<u8>) -> Self {
+        let identifier = identifier.to_be_bytes();
+        let sequence = sequence.to_be_bytes();
+        let mut message = Self {
+            icmp_type,
+            code           : 0,
+            checksum       : 0,
+            rest_of_header : [identifier[0], identifier[1], sequence[0], sequence[1]],
+            payload,
+        };
+        message.checksum = message.compute_checksum();
+        message
+    }
+
+    /// エコー要求/応答の識別子
+    pub fn identifier(&self) -> u16 {
+        u16::from_be_bytes([self.rest_of_header[0], self.rest_of_header[1]])
+    }
+
+    /// エコー要求/応答のシーケンス番号
+    pub fn sequence(&self) -> u16 {
+        u16::from_be_bytes([self.rest_of_header[2], self.rest_of_header[3]])
+    }
+
+    /// チェックサムを計算する
+    /// チェックサムのフィールドを0にしたICMPメッセージ全体に対するインターネットチェックサム
+    pub fn compute_checksum(&self) -> u16 {
+        internet_checksum(&self.bytes_with_checksum(0))
+    }
+
+    /// バイト配列に変換
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bytes_with_checksum(self.checksum)
+    }
+
+    /// 指定したチェックサムでバイト配列にする
+    fn bytes_with_checksum(&self, checksum: u16) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::HEADER_LENGTH + self.payload.len());
+        bytes.push(self.icmp_type);
+        bytes.push(self.code);
+        bytes.extend_from_slice(&checksum.to_be_bytes());
+        bytes.extend_from_slice(&self.rest_of_header);
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
+
+    /// バイト配列からIcmpMessageを構築
+    /// 先頭8バイトをヘッダ、残りをすべてデータとする
+    pub fn from_bytes(bytes: &[u8]) -> Result<IcmpMessage, &'static str> {
+        if bytes.len() < Self::HEADER_LENGTH {
+            return Err("ICMP message must be at least 8 bytes");
+        }
+        Ok(IcmpMessage {
+            icmp_type      : bytes[0],
+            code           : bytes[1],
+            checksum       : u16::from_be_bytes([bytes[2], bytes[3]]),
+            rest_of_header : [bytes[4], bytes[5], bytes[6], bytes[7]],
+            payload        : bytes[Self::HEADER_LENGTH..].to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn echo_request_has_a_valid_checksum_and_parses_back() {
+        let request = IcmpMessage::echo_request(1, 1, b"abcd".to_vec());
+        assert_eq!(request.checksum, 0x3337);
+
+        let bytes = request.to_bytes();
+        assert_eq!(bytes[..8], [0x08, 0x00, 0x33, 0x37, 0x00, 0x01, 0x00, 0x01]);
+        // 正しいチェックサムの入ったメッセージ全体のインターネットチェックサムは0になる
+        assert_eq!(internet_checksum(&bytes), 0);
+
+        let parsed = IcmpMessage::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed, request);
+        assert_eq!((parsed.identifier(), parsed.sequence()), (1, 1));
+        assert_eq!(parsed.compute_checksum(), parsed.checksum);
+    }
+
+    #[test]
+    fn echo_reply_mirrors_the_request() {
+        let reply = IcmpMessage::echo_reply(0x1234, 7, vec![0xAA; 4]);
+        assert_eq!(reply.icmp_type, IcmpMessage::TYPE_ECHO_REPLY);
+        assert_eq!((reply.identifier(), reply.sequence()), (0x1234, 7));
+        assert!(IcmpMessage::from_bytes(&reply.to_bytes()[..7]).is_err());
+    }
+}
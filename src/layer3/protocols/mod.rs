@@ -0,0 +1,3 @@
+pub(crate) mod icmp;
+
+pub use icmp::IcmpMessage;
@@ -0,0 +1,496 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::layer2::address::MacAddress;
+use crate::layer3::address::IPv4Address;
+
+/// DHCPのメッセージの種類（値はDHCPのメッセージタイプオプションと同じ）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DhcpMessageType {
+    Discover = 1, // クライアント → サーバー : アドレスをください
+    Offer    = 2, // サーバー → クライアント : このアドレスはどうですか
+    Request  = 3, // クライアント → サーバー : そのアドレスを使わせてください
+    Ack      = 5, // サーバー → クライアント : 使って良いです
+}
+
+impl DhcpMessageType {
+    /// メッセージタイプの値から種類を取得する
+    pub fn from_u8(value: u8) -> Option<DhcpMessageType> {
+        match value {
+            1 => Some(DhcpMessageType::Discover),
+            2 => Some(DhcpMessageType::Offer),
+            3 => Some(DhcpMessageType::Request),
+            5 => Some(DhcpMessageType::Ack),
+            _ => None,
+        }
+    }
+}
+
+/// アドレスプールからIPv4アドレスを貸し出す簡単なDHCPサーバー
+/// DISCOVER/OFFER/REQUEST/ACKの4つのメッセージのやり取りで、クライアントにアドレスを割り当てる
+#[derive(Clone, Debug)]
+pub struct DhcpServer {
+    pub server_ip   : IPv4Address,
+    pub pool_start  : IPv4Address,                       // 貸し出す範囲の先頭
+    pub pool_end    : IPv4Address,                       // 貸し出す範囲の末尾（これも含む）
+    pub lease_time  : u32,                               // 貸し出す期間(秒)
+    pub allocations : HashMap<MacAddress, IPv4Address>,  // OFFER済み・貸し出し済みのアドレス
+}
+
+impl DhcpServer {
+    pub fn new(server_ip: IPv4Address, pool_start: IPv4Address, pool_end: IPv4Address, lease_time: u32) -> Self {
+        Self {
+            server_ip,
+            pool_start,
+            pool_end,
+            lease_time,
+            allocations: HashMap::new(),
+        }
+    }
+
+    /// クライアントからのメッセージを処理して返信するメッセージを返す
+    /// DISCOVERにはOFFERを、REQUESTにはACKを返す。OFFERとACKにはリース時間のオプションを付ける
+    /// プールが枯渇している時や、OFFERしていないアドレスのREQUESTには何も返さない
+    pub fn handle(&mut self, message: &DhcpMessage) -> Option<DhcpMessage> {
+        match message.message_type()? {
+            DhcpMessageType::Discover => {
+                let offered_ip = self.allocate(message.chaddr)?;
+                let mut offer = DhcpMessage::offer(message.chaddr, message.xid, offered_ip, self.server_ip);
+                offer.options.push(DhcpOption::lease_time(self.lease_time));
+                Some(offer)
+            }
+            DhcpMessageType::Request => {
+                let allocated = *self.allocations.get(&message.chaddr)?;
+                if message.requested_ip() != Some(allocated) {
+                    return None;
+                }
+                Some(DhcpMessage::ack(message.chaddr, message.xid, allocated, self.server_ip, self.lease_time))
+            }
+            DhcpMessageType::Offer | DhcpMessageType::Ack => None,
+        }
+    }
+
+    /// まだ貸し出せるアドレスの数
+    /// 0.0.0.0〜255.255.255.255のようにプール全体を指定するとu32に収まらないので、u64で数える
+    pub fn available_count(&self) -> u64 {
+        let start = self.pool_start.to_u32() as u64;
+        let end = self.pool_end.to_u32() as u64;
+        if end < start {
+            return 0;
+        }
+        (end - start + 1).saturating_sub(self.allocations.len() as u64)
+    }
+
+    /// MACアドレスにアドレスを割り当てる。既に割り当て済みなら同じアドレスを返す
+    fn allocate(&mut self, client_mac: MacAddress) -> Option<IPv4Address> {
+        if let Some(ip) = self.allocations.get(&client_mac) {
+            return Some(*ip);
+        }
+        let allocated: HashSet<IPv4Address> = self.allocations.values().copied().collect();
+        let start = self.pool_start.to_u32();
+        let end = self.pool_end.to_u32();
+        let free_ip = (start..=end)
+            .map(IPv4Address::from_u32)
+            .find(|ip| !allocated.contains(ip))?;
+        self.allocations.insert(client_mac, free_ip);
+        Some(free_ip)
+    }
+}
+
+/// DHCPのオプション(コード, 長さ, 値)
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DhcpOption {
+    pub code : u8,      // オプションの種類 (1バイト)
+    pub data : Vec<u8>, // オプションの値 (長さは1バイトで表す)
+}
+
+impl DhcpOption {
+    /// DHCPのメッセージタイプを表すオプションのコード
+    pub const MESSAGE_TYPE: u8 = 53;
+    /// クライアントが使いたいIPv4アドレスを表すオプションのコード
+    pub const REQUESTED_IP: u8 = 50;
+    /// リース時間(秒)を表すオプションのコード
+    pub const LEASE_TIME: u8 = 51;
+    /// DHCPサーバーのIPv4アドレスを表すオプションのコード
+    pub const SERVER_IDENTIFIER: u8 = 54;
+    /// 詰め物のオプションのコード。長さを持たない
+    pub const PAD: u8 = 0;
+    /// オプションの終わりを表すコード。長さを持たない
+    pub const END: u8 = 255;
+
+    /// メッセージタイプのオプションを生成
+    pub fn message_type(message_type: DhcpMessageType) -> Self {
+        Self { code: Self::MESSAGE_TYPE, data: vec![message_type as u8] }
+    }
+
+    /// DHCPサーバーのIPv4アドレスのオプションを生成
+    pub fn server_identifier(server_ip: IPv4Address) -> Self {
+        Self { code: Self::SERVER_IDENTIFIER, data: server_ip.to_array().to_vec() }
+    }
+
+    /// クライアントが使いたいIPv4アドレスのオプションを生成
+    pub fn requested_ip(ip: IPv4Address) -> Self {
+        Self { code: Self::REQUESTED_IP, data: ip.to_array().to_vec() }
+    }
+
+    /// リース時間(秒)のオプションを生成
+    pub fn lease_time(lease_time: u32) -> Self {
+        Self { code: Self::LEASE_TIME, data: lease_time.to_be_bytes().to_vec() }
+    }
+}
+
+/// 実際のDHCP(RFC 2131)のメッセージ
+/// BOOTPのヘッダの後ろにマジッククッキーとオプションが続く形で、UDPの67/68番ポートで運ばれる
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DhcpMessage {
+    pub op      : u8,              // 1: クライアントからの要求(BOOTREQUEST)、2: サーバーからの応答(BOOTREPLY) (1バイト)
+    pub htype   : u8,              // ハードウェアの種類。イーサネットは1 (1バイト)
+    pub hlen    : u8,              // ハードウェアアドレスの長さ。MACアドレスは6 (1バイト)
+    pub hops    : u8,              // 中継したリレーエージェントの数 (1バイト)
+    pub xid     : u32,             // やり取りを識別するId (4バイト)
+    pub secs    : u16,             // クライアントがアドレスを探し始めてからの秒数 (2バイト)
+    pub flags   : u16,             // 先頭のビットが1なら応答をブロードキャストで送ってもらう (2バイト)
+    pub ciaddr  : IPv4Address,     // クライアントが今使っているIPv4アドレス (4バイト)
+    pub yiaddr  : IPv4Address,     // サーバーがクライアントに割り当てるIPv4アドレス (4バイト)
+    pub siaddr  : IPv4Address,     // 次に使うサーバーのIPv4アドレス (4バイト)
+    pub giaddr  : IPv4Address,     // リレーエージェントのIPv4アドレス (4バイト)
+    pub chaddr  : MacAddress,      // クライアントのMACアドレス (16バイトのうち先頭6バイト)
+    pub options : Vec<DhcpOption>, // マジッククッキーの後ろに続くオプション
+}
+
+impl fmt::Display for DhcpMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message_type = self.message_type()
+            .map(|message_type| format!("{:?}", message_type))
+            .unwrap_or_else(|| "-".to_string());
+        writeln!(f, "#op           : {}", self.op)?;
+        writeln!(f, "#htype        : {}", self.htype)?;
+        writeln!(f, "#hlen         : {}", self.hlen)?;
+        writeln!(f, "#hops         : {}", self.hops)?;
+        writeln!(f, "#xid          : {:08X}", self.xid)?;
+        writeln!(f, "#secs         : {}", self.secs)?;
+        writeln!(f, "#flags        : {:04X}", self.flags)?;
+        writeln!(f, "#ciaddr       : {}", std::net::Ipv4Addr::from(self.ciaddr))?;
+        writeln!(f, "#yiaddr       : {}", std::net::Ipv4Addr::from(self.yiaddr))?;
+        writeln!(f, "#siaddr       : {}", std::net::Ipv4Addr::from(self.siaddr))?;
+        writeln!(f, "#giaddr       : {}", std::net::Ipv4Addr::from(self.giaddr))?;
+        writeln!(f, "#chaddr       : {}", self.chaddr.to_string_with_separator(':'))?;
+        writeln!(f, "#message_type : {}", message_type)?;
+        for option in &self.options {
+            let data: Vec<String> = option.data.iter().map(|byte| format!("{:02X}", byte)).collect();
+            writeln!(f, "#  option {:<3}  : [{}]", option.code, data.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl DhcpMessage {
+    /// クライアントからの要求
+    pub const BOOTREQUEST: u8 = 1;
+    /// サーバーからの応答
+    pub const BOOTREPLY: u8 = 2;
+    /// 応答をブロードキャストで送ってもらうフラグ
+    pub const FLAG_BROADCAST: u16 = 0x8000;
+    /// オプションの前に置く、DHCPのメッセージであることを表す値
+    pub const MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+    /// オプションより前(マジッククッキーまで)の長さ
+    /// BOOTPのヘッダ236バイト(chaddrは16バイト、sname 64バイト、file 128バイトを含む) + マジッククッキー4バイト
+    pub const HEADER_LENGTH: usize = 240;
+
+    /// クライアントがアドレスを探すDISCOVERを生成
+    /// クライアントはまだアドレスを持っていないので、OFFERはブロードキャストで送ってもらう
+    pub fn discover(client_mac: MacAddress, xid: u32) -> Self {
+        Self {
+            flags   : Self::FLAG_BROADCAST,
+            options : vec![DhcpOption::message_type(DhcpMessageType::Discover)],
+            ..Self::new(Self::BOOTREQUEST, client_mac, xid)
+        }
+    }
+
+    /// サーバーがDISCOVERに対してアドレスを提案するOFFERを生成
+    pub fn offer(client_mac: MacAddress, xid: u32, offered_ip: IPv4Address, server_ip: IPv4Address) -> Self {
+        Self {
+            yiaddr  : offered_ip,
+            siaddr  : server_ip,
+            options : vec![
+                DhcpOption::message_type(DhcpMessageType::Offer),
+                DhcpOption::server_identifier(server_ip),
+            ],
+            ..Self::new(Self::BOOTREPLY, client_mac, xid)
+        }
+    }
+
+    /// クライアントがOFFERされたアドレスを要求するREQUESTを生成
+    /// どのサーバーのOFFERを受け入れたのかを、サーバーのIPv4アドレスのオプションで伝える
+    pub fn request(client_mac: MacAddress, xid: u32, requested_ip: IPv4Address, server_ip: IPv4Address) -> Self {
+        Self {
+            flags   : Self::FLAG_BROADCAST,
+            options : vec![
+                DhcpOption::message_type(DhcpMessageType::Request),
+                DhcpOption::requested_ip(requested_ip),
+                DhcpOption::server_identifier(server_ip),
+            ],
+            ..Self::new(Self::BOOTREQUEST, client_mac, xid)
+        }
+    }
+
+    /// サーバーがREQUESTを承認してアドレスを貸し出すACKを生成
+    pub fn ack(client_mac: MacAddress, xid: u32, assigned_ip: IPv4Address, server_ip: IPv4Address, lease_time: u32) -> Self {
+        Self {
+            yiaddr  : assigned_ip,
+            siaddr  : server_ip,
+            options : vec![
+                DhcpOption::message_type(DhcpMessageType::Ack),
+                DhcpOption::server_identifier(server_ip),
+                DhcpOption::lease_time(lease_time),
+            ],
+            ..Self::new(Self::BOOTREPLY, client_mac, xid)
+        }
+    }
+
+    fn new(op: u8, chaddr: MacAddress, xid: u32) -> Self {
+        Self {
+            op,
+            htype   : 1,
+            hlen    : 6,
+            hops    : 0,
+            xid,
+            secs    : 0,
+            flags   : 0,
+            ciaddr  : IPv4Address([0; 4]),
+            yiaddr  : IPv4Address([0; 4]),
+            siaddr  : IPv4Address([0; 4]),
+            giaddr  : IPv4Address([0; 4]),
+            chaddr,
+            options : Vec::new(),
+        }
+    }
+
+    /// メッセージタイプのオプションから、メッセージの種類を取得
+    pub fn message_type(&self) -> Option<DhcpMessageType> {
+        self.options
+            .iter()
+            .find(|option| option.code == DhcpOption::MESSAGE_TYPE)
+            .and_then(|option| option.data.first())
+            .and_then(|&value| DhcpMessageType::from_u8(value))
+    }
+
+    /// codeのオプションの値を取得
+    fn option(&self, code: u8) -> Option<&[u8]> {
+        self.options
+            .iter()
+            .find(|option| option.code == code)
+            .map(|option| option.data.as_slice())
+    }
+
+    /// REQUESTでクライアントが使いたいIPv4アドレスを取得
+    pub fn requested_ip(&self) -> Option<IPv4Address> {
+        let data: [u8; 4] = self.option(DhcpOption::REQUESTED_IP)?.try_into().ok()?;
+        Some(IPv4Address::from_array(data))
+    }
+
+    /// OFFER/ACKで伝えるリース時間(秒)を取得
+    pub fn lease_time(&self) -> Option<u32> {
+        let data: [u8; 4] = self.option(DhcpOption::LEASE_TIME)?.try_into().ok()?;
+        Some(u32::from_be_bytes(data))
+    }
+
+    /// バイト配列に変換
+    /// オプションの最後には終わりを表すEND(255)を置く
+    /// 長さは1バイトなので、255バイトを超える値は同じコードの複数のオプションに分けて書く(RFC 3396)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::HEADER_LENGTH + 64);
+        bytes.extend_from_slice(&[self.op, self.htype, self.hlen, self.hops]);
+        bytes.extend_from_slice(&self.xid.to_be_bytes());
+        bytes.extend_from_slice(&self.secs.to_be_bytes());
+        bytes.extend_from_slice(&self.flags.to_be_bytes());
+        bytes.extend_from_slice(&self.ciaddr.to_array());
+        bytes.extend_from_slice(&self.yiaddr.to_array());
+        bytes.extend_from_slice(&self.siaddr.to_array());
+        bytes.extend_from_slice(&self.giaddr.to_array());
+        bytes.extend_from_slice(&self.chaddr.to_array());
+        // chaddrの残り10バイトと、使わないsname(64バイト)/file(128バイト)は0で埋める
+        bytes.resize(Self::HEADER_LENGTH - Self::MAGIC_COOKIE.len(), 0);
+        bytes.extend_from_slice(&Self::MAGIC_COOKIE);
+        for option in &self.options {
+            if option.data.is_empty() {
+                bytes.extend_from_slice(&[option.code, 0]);
+            }
+            for chunk in option.data.chunks(u8::MAX as usize) {
+                bytes.push(option.code);
+                bytes.push(chunk.len() as u8);
+                bytes.extend_from_slice(chunk);
+            }
+        }
+        bytes.push(DhcpOption::END);
+        bytes
+    }
+
+    /// バイト配列からDhcpMessageを構築
+    /// PADは読み飛ばし、ENDかバイト列の終わりまでをオプションとして読む
+    /// 同じコードのオプションが続いている時は、値をつなげて1つのオプションにする(RFC 3396)
+    pub fn from_bytes(bytes: &[u8]) -> Result<DhcpMessage, &'static str> {
+        if bytes.len() < Self::HEADER_LENGTH {
+            return Err("DHCP message is too short");
+        }
+        if bytes[236..240] != Self::MAGIC_COOKIE {
+            return Err("Invalid DHCP magic cookie");
+        }
+        let address = |offset: usize| IPv4Address::from_array([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+        let mut chaddr = [0u8; 6];
+        chaddr.copy_from_slice(&bytes[28..34]);
+
+        let mut options = Vec::new();
+        let mut offset = Self::HEADER_LENGTH;
+        while offset < bytes.len() {
+            let code = bytes[offset];
+            match code {
+                DhcpOption::END => break,
+                DhcpOption::PAD => offset += 1,
+                _ => {
+                    let length = *bytes.get(offset + 1).ok_or("DHCP option length is missing")? as usize;
+                    let data = bytes.get(offset + 2..offset + 2 + length).ok_or("DHCP option is truncated")?;
+                    match options.last_mut() {
+                        Some(DhcpOption { code: last_code, data: last_data }) if *last_code == code => {
+                            last_data.extend_from_slice(data)
+                        }
+                        _ => options.push(DhcpOption { code, data: data.to_vec() }),
+                    }
+                    offset += 2 + length;
+                }
+            }
+        }
+
+        Ok(DhcpMessage {
+            op      : bytes[0],
+            htype   : bytes[1],
+            hlen    : bytes[2],
+            hops    : bytes[3],
+            xid     : u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            secs    : u16::from_be_bytes([bytes[8], bytes[9]]),
+            flags   : u16::from_be_bytes([bytes[10], bytes[11]]),
+            ciaddr  : address(12),
+            yiaddr  : address(16),
+            siaddr  : address(20),
+            giaddr  : address(24),
+            chaddr  : MacAddress::from_array(chaddr),
+            options,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENT_MAC: MacAddress = MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+    const SERVER_IP: IPv4Address = IPv4Address([192, 168, 1, 1]);
+
+    /// バイト列にしてから読み戻す。クライアントとサーバーの間をUDPで運ばれたことにする
+    fn over_the_wire(message: DhcpMessage) -> DhcpMessage {
+        DhcpMessage::from_bytes(&message.to_bytes()).unwrap()
+    }
+
+    fn server(pool_start: [u8; 4], pool_end: [u8; 4]) -> DhcpServer {
+        DhcpServer::new(SERVER_IP, IPv4Address(pool_start), IPv4Address(pool_end), 3600)
+    }
+
+    #[test]
+    fn four_message_handshake_round_trips_through_bytes() {
+        let mut server = server([192, 168, 1, 100], [192, 168, 1, 101]);
+
+        let discover = over_the_wire(DhcpMessage::discover(CLIENT_MAC, 0x1234_5678));
+        assert_eq!(discover.message_type(), Some(DhcpMessageType::Discover));
+
+        let offer = over_the_wire(server.handle(&discover).unwrap());
+        assert_eq!(offer.message_type(), Some(DhcpMessageType::Offer));
+        assert_eq!(offer.xid, 0x1234_5678);
+        assert_eq!(offer.chaddr, CLIENT_MAC);
+        assert_eq!(offer.yiaddr, IPv4Address([192, 168, 1, 100]));
+        assert_eq!(offer.lease_time(), Some(3600));
+
+        let request = over_the_wire(DhcpMessage::request(CLIENT_MAC, offer.xid, offer.yiaddr, offer.siaddr));
+        assert_eq!(request.message_type(), Some(DhcpMessageType::Request));
+        assert_eq!(request.requested_ip(), Some(IPv4Address([192, 168, 1, 100])));
+
+        let ack = over_the_wire(server.handle(&request).unwrap());
+        assert_eq!(ack.message_type(), Some(DhcpMessageType::Ack));
+        assert_eq!(ack.chaddr, CLIENT_MAC);
+        assert_eq!(ack.yiaddr, IPv4Address([192, 168, 1, 100]));
+        assert_eq!(ack.lease_time(), Some(3600));
+    }
+
+    #[test]
+    fn exhausted_pool_offers_nothing() {
+        let mut server = server([192, 168, 1, 100], [192, 168, 1, 101]);
+        for (i, xid) in [1u32, 2].into_iter().enumerate() {
+            let mac = MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, i as u8]);
+            assert!(server.handle(&DhcpMessage::discover(mac, xid)).is_some());
+        }
+        assert_eq!(server.available_count(), 0);
+        assert!(server.handle(&DhcpMessage::discover(CLIENT_MAC, 3)).is_none());
+    }
+
+    #[test]
+    fn available_count_covers_the_full_address_range() {
+        let server = server([0, 0, 0, 0], [255, 255, 255, 255]);
+        assert_eq!(server.available_count(), 1 << 32);
+    }
+
+    #[test]
+    fn discover_round_trips_with_message_type_1_after_the_magic_cookie() {
+        let discover = DhcpMessage::discover(CLIENT_MAC, 0xCAFE_F00D);
+        let bytes = discover.to_bytes();
+        assert_eq!(bytes[0], DhcpMessage::BOOTREQUEST);
+        assert_eq!(bytes[28..34], CLIENT_MAC.to_array());
+        assert_eq!(bytes[236..240], DhcpMessage::MAGIC_COOKIE);
+        assert_eq!(bytes[240..243], [DhcpOption::MESSAGE_TYPE, 1, 1]);
+        assert_eq!(bytes.last(), Some(&DhcpOption::END));
+
+        let decoded = DhcpMessage::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, discover);
+        assert_eq!(decoded.message_type(), Some(DhcpMessageType::Discover));
+    }
+
+    #[test]
+    fn from_bytes_rejects_short_messages_and_a_wrong_magic_cookie() {
+        let bytes = DhcpMessage::discover(CLIENT_MAC, 1).to_bytes();
+        assert!(DhcpMessage::from_bytes(&bytes[..239]).is_err());
+        let mut wrong_cookie = bytes.clone();
+        wrong_cookie[236] = 0;
+        assert!(DhcpMessage::from_bytes(&wrong_cookie).is_err());
+    }
+
+    #[test]
+    fn options_longer_than_255_bytes_are_split_and_joined_again() {
+        let mut discover = DhcpMessage::discover(CLIENT_MAC, 1);
+        let long: Vec<u8> = (0..600).map(|i| i as u8).collect();
+        discover.options.push(DhcpOption { code: 43, data: long.clone() });
+        let bytes = discover.to_bytes();
+
+        let start = DhcpMessage::HEADER_LENGTH + 3;
+        assert_eq!(bytes[start..start + 2], [43, 255]);
+        assert_eq!(bytes[start + 257..start + 259], [43, 255]);
+        assert_eq!(bytes[start + 514..start + 516], [43, 90]);
+
+        let decoded = DhcpMessage::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, discover);
+        assert_eq!(decoded.options[1].data, long);
+    }
+
+    #[test]
+    fn allocate_fills_a_released_address_first() {
+        let mut server = server([10, 0, 0, 1], [10, 0, 3, 232]);
+        for i in 0..1000u32 {
+            let mac = MacAddress([0x02, 0, 0, 0, (i >> 8) as u8, i as u8]);
+            assert!(server.handle(&DhcpMessage::discover(mac, i)).is_some());
+        }
+        assert_eq!(server.available_count(), 0);
+
+        let released = MacAddress([0x02, 0, 0, 0, 0, 10]);
+        assert_eq!(server.allocations.remove(&released), Some(IPv4Address([10, 0, 0, 11])));
+        let offer = server.handle(&DhcpMessage::discover(CLIENT_MAC, 1)).unwrap();
+        assert_eq!(offer.yiaddr, IPv4Address([10, 0, 0, 11]));
+    }
+}
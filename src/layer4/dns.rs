@@ -0,0 +1,297 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::layer3::address::IPv4Address;
+
+/// DNSの問い合わせ(質問部の1件)
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DnsQuestion {
+    pub name   : String, // 問い合わせる名前 (ラベルの列)
+    pub qtype  : u16,    // 問い合わせるレコードの種類 (2バイト)
+    pub qclass : u16,    // クラス。インターネットは1 (2バイト)
+}
+
+/// DNSのリソースレコード(回答部の1件)
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DnsRecord {
+    pub name   : String,  // レコードの名前 (ラベルの列)
+    pub rtype  : u16,     // レコードの種類 (2バイト)
+    pub rclass : u16,     // クラス。インターネットは1 (2バイト)
+    pub ttl    : u32,     // キャッシュしてよい秒数 (4バイト)
+    pub data   : Vec<u8>, // レコードの値。Aレコードなら4バイトのIPv4アドレス (長さは2バイトで表す)
+}
+
+/// DNS(RFC 1035)のメッセージ
+/// 12バイトのヘッダの後ろに質問部と回答部が続く。UDPの53番ポートで運ばれる
+/// 質問と回答の数はヘッダに書くが、ここではquestions/answersの長さから求める
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DnsMessage {
+    pub id        : u16,              // 問い合わせと応答を対応させるId (2バイト)
+    pub flags     : u16,              // QR(応答かどうか)、RD(再帰問い合わせの要求)などのフラグ (2バイト)
+    pub questions : Vec<DnsQuestion>, // 質問部
+    pub answers   : Vec<DnsRecord>,   // 回答部
+}
+
+impl fmt::Display for DnsMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "#id        : {:04X}", self.id)?;
+        writeln!(f, "#flags     : {:04X}", self.flags)?;
+        writeln!(f, "#questions : {}", self.questions.len())?;
+        for question in &self.questions {
+            writeln!(f, "#  {} type={} class={}", question.name, question.qtype, question.qclass)?;
+        }
+        writeln!(f, "#answers   : {}", self.answers.len())?;
+        for answer in &self.answers {
+            let data = match (answer.rtype, answer.data.as_slice()) {
+                (DnsMessage::TYPE_A, &[a, b, c, d]) => std::net::Ipv4Addr::new(a, b, c, d).to_string(),
+                _ => answer.data.iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<String>>().join(" "),
+            };
+            writeln!(f, "#  {} type={} class={} ttl={} {}", answer.name, answer.rtype, answer.rclass, answer.ttl, data)?;
+        }
+        Ok(())
+    }
+}
+
+impl DnsMessage {
+    /// ヘッダの長さ(バイト)
+    pub const HEADER_LENGTH: usize = 12;
+    /// Aレコード(IPv4アドレス)
+    pub const TYPE_A: u16 = 1;
+    /// インターネットのクラス
+    pub const CLASS_IN: u16 = 1;
+    /// 応答であることを表すフラグ(QR)
+    pub const FLAG_RESPONSE: u16 = 0x8000;
+    /// 再帰問い合わせを要求するフラグ(RD)
+    pub const FLAG_RECURSION_DESIRED: u16 = 0x0100;
+    /// 再帰問い合わせができることを表すフラグ(RA)
+    pub const FLAG_RECURSION_AVAILABLE: u16 = 0x0080;
+    /// Aレコードの応答に付けるTTL(秒)
+    pub const DEFAULT_TTL: u32 = 300;
+
+    /// nameのqtypeのレコードを問い合わせるメッセージを生成
+    /// Idはランダムに決める
+    pub fn query(name: &str, qtype: u16) -> Result<Self, &'static str> {
+        encode_name(name)?;
+        Ok(Self {
+            id        : rand::thread_rng().gen(),
+            flags     : Self::FLAG_RECURSION_DESIRED,
+            questions : vec![DnsQuestion { name: name.to_string(), qtype, qclass: Self::CLASS_IN }],
+            answers   : Vec::new(),
+        })
+    }
+
+    /// nameのAレコードの問い合わせに、ipを答える応答を生成
+    /// Idは0にしておくので、問い合わせに答える時は問い合わせのIdに書き換える
+    pub fn a_response(name: &str, ip: IPv4Address) -> Result<Self, &'static str> {
+        encode_name(name)?;
+        Ok(Self {
+            id        : 0,
+            flags     : Self::FLAG_RESPONSE | Self::FLAG_RECURSION_DESIRED | Self::FLAG_RECURSION_AVAILABLE,
+            questions : vec![DnsQuestion { name: name.to_string(), qtype: Self::TYPE_A, qclass: Self::CLASS_IN }],
+            answers   : vec![DnsRecord {
+                name   : name.to_string(),
+                rtype  : Self::TYPE_A,
+                rclass : Self::CLASS_IN,
+                ttl    : Self::DEFAULT_TTL,
+                data   : ip.to_array().to_vec(),
+            }],
+        })
+    }
+
+    /// 応答かどうか
+    pub fn is_response(&self) -> bool {
+        self.flags & Self::FLAG_RESPONSE != 0
+    }
+
+    /// バイト配列に変換
+    /// 名前は長さ付きのラベルの列にし、圧縮(ポインタ)は使わない。ラベルにできない名前はルート(長さ0のラベル)として書き込む
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::HEADER_LENGTH + 64);
+        bytes.extend_from_slice(&self.id.to_be_bytes());
+        bytes.extend_from_slice(&self.flags.to_be_bytes());
+        bytes.extend_from_slice(&(self.questions.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&(self.answers.len() as u16).to_be_bytes());
+        // 権威部と追加部は使わない
+        bytes.extend_from_slice(&[0; 4]);
+        for question in &self.questions {
+            bytes.extend_from_slice(&encode_name(&question.name).unwrap_or_else(|_| vec![0]));
+            bytes.extend_from_slice(&question.qtype.to_be_bytes());
+            bytes.extend_from_slice(&question.qclass.to_be_bytes());
+        }
+        for answer in &self.answers {
+            bytes.extend_from_slice(&encode_name(&answer.name).unwrap_or_else(|_| vec![0]));
+            bytes.extend_from_slice(&answer.rtype.to_be_bytes());
+            bytes.extend_from_slice(&answer.rclass.to_be_bytes());
+            bytes.extend_from_slice(&answer.ttl.to_be_bytes());
+            bytes.extend_from_slice(&(answer.data.len() as u16).to_be_bytes());
+            bytes.extend_from_slice(&answer.data);
+        }
+        bytes
+    }
+
+    /// バイト配列からDnsMessageを構築
+    /// 質問部と回答部だけを読み、権威部と追加部は読み飛ばす。圧縮された名前(ポインタ)も読める
+    pub fn from_bytes(bytes: &[u8]) -> Result<DnsMessage, &'static str> {
+        if bytes.len() < Self::HEADER_LENGTH {
+            return Err("DNS message is too short");
+        }
+        let read_u16 = |offset: usize| -> Result<u16, &'static str> {
+            let field = bytes.get(offset..offset + 2).ok_or("DNS message is truncated")?;
+            Ok(u16::from_be_bytes([field[0], field[1]]))
+        };
+        let question_count = read_u16(4)?;
+        let answer_count = read_u16(6)?;
+
+        let mut offset = Self::HEADER_LENGTH;
+        let mut questions = Vec::new();
+        for _ in 0..question_count {
+            let (name, next) = decode_name(bytes, offset)?;
+            questions.push(DnsQuestion { name, qtype: read_u16(next)?, qclass: read_u16(next + 2)? });
+            offset = next + 4;
+        }
+        let mut answers = Vec::new();
+        for _ in 0..answer_count {
+            let (name, next) = decode_name(bytes, offset)?;
+            let ttl = bytes.get(next + 4..next + 8).ok_or("DNS message is truncated")?;
+            let data_length = read_u16(next + 8)? as usize;
+            let data = bytes.get(next + 10..next + 10 + data_length).ok_or("DNS record data is truncated")?;
+            answers.push(DnsRecord {
+                name,
+                rtype  : read_u16(next)?,
+                rclass : read_u16(next + 2)?,
+                ttl    : u32::from_be_bytes([ttl[0], ttl[1], ttl[2], ttl[3]]),
+                data   : data.to_vec(),
+            });
+            offset = next + 10 + data_length;
+        }
+
+        Ok(DnsMessage {
+            id    : read_u16(0)?,
+            flags : read_u16(2)?,
+            questions,
+            answers,
+        })
+    }
+}
+
+/// 名前を長さ付きのラベルの列に変換する
+/// 例えば`www.example.com`は`3 www 7 example 3 com 0`になる。末尾の`.`はあってもなくても良い
+pub fn encode_name(name: &str) -> Result<Vec<u8>, &'static str> {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    let mut bytes = Vec::with_capacity(name.len() + 2);
+    if !name.is_empty() {
+        for label in name.split('.') {
+            if label.is_empty() {
+                return Err("DNS name has an empty label");
+            }
+            if label.len() > 63 {
+                return Err("DNS label must be 63 bytes or less");
+            }
+            bytes.push(label.len() as u8);
+            bytes.extend_from_slice(label.as_bytes());
+        }
+    }
+    bytes.push(0);
+    if bytes.len() > 255 {
+        return Err("DNS name must be 255 bytes or less");
+    }
+    Ok(bytes)
+}
+
+/// offsetから始まるラベルの列を名前に戻し、名前の次の位置と一緒に返す
+/// 先頭の2ビットが11のバイトは圧縮(メッセージの先頭からの位置を指すポインタ)として扱う
+fn decode_name(bytes: &[u8], offset: usize) -> Result<(String, usize), &'static str> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut position = offset;
+    let mut next = None;
+    // ポインタが循環していても止まるように、たどる回数を制限する
+    for _ in 0..128 {
+        let length = *bytes.get(position).ok_or("DNS name is truncated")?;
+        match length {
+            0 => {
+                let name = if labels.is_empty() { ".".to_string() } else { labels.join(".") };
+                return Ok((name, next.unwrap_or(position + 1)));
+            }
+            length if length & 0xC0 == 0xC0 => {
+                let low = *bytes.get(position + 1).ok_or("DNS name is truncated")?;
+                next.get_or_insert(position + 2);
+                position = (((length & 0x3F) as usize) << 8) | low as usize;
+            }
+            length if length <= 63 => {
+                let label = bytes.get(position + 1..position + 1 + length as usize).ok_or("DNS name is truncated")?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                position += 1 + length as usize;
+            }
+            _ => return Err("Invalid DNS label length"),
+        }
+    }
+    Err("DNS name has too many labels")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_name_writes_length_prefixed_labels() {
+        let mut expected = vec![3];
+        expected.extend_from_slice(b"www");
+        expected.push(7);
+        expected.extend_from_slice(b"example");
+        expected.push(3);
+        expected.extend_from_slice(b"com");
+        expected.push(0);
+        assert_eq!(encode_name("www.example.com").unwrap(), expected);
+        assert_eq!(encode_name("www.example.com.").unwrap(), expected);
+        assert_eq!(decode_name(&expected, 0).unwrap(), ("www.example.com".to_string(), expected.len()));
+    }
+
+    #[test]
+    fn encode_name_rejects_invalid_labels() {
+        assert!(encode_name("www..com").is_err());
+        assert!(encode_name(&"a".repeat(64)).is_err());
+        assert_eq!(encode_name("").unwrap(), vec![0]);
+    }
+
+    #[test]
+    fn query_round_trips() {
+        let query = DnsMessage::query("www.example.com", DnsMessage::TYPE_A).unwrap();
+        let bytes = query.to_bytes();
+        assert_eq!(bytes.len(), DnsMessage::HEADER_LENGTH + 17 + 4);
+        assert_eq!(&bytes[4..6], &[0, 1]);
+        assert_eq!(&bytes[6..8], &[0, 0]);
+        assert_eq!(DnsMessage::from_bytes(&bytes).unwrap(), query);
+        assert!(!query.is_response());
+    }
+
+    #[test]
+    fn a_response_round_trips() {
+        let ip = IPv4Address::from_string("93.184.216.34").unwrap();
+        let response = DnsMessage::a_response("www.example.com", ip).unwrap();
+        let parsed = DnsMessage::from_bytes(&response.to_bytes()).unwrap();
+        assert_eq!(parsed, response);
+        assert!(parsed.is_response());
+        assert_eq!(parsed.answers[0].data, vec![93, 184, 216, 34]);
+        assert_eq!(parsed.answers[0].ttl, DnsMessage::DEFAULT_TTL);
+    }
+
+    #[test]
+    fn from_bytes_follows_name_pointers() {
+        let ip = IPv4Address::from_string("10.0.0.1").unwrap();
+        let mut bytes = DnsMessage::a_response("example.com", ip).unwrap().to_bytes();
+        // 回答部の名前を、質問部の名前(オフセット12)へのポインタに置き換える
+        let answer_name = DnsMessage::HEADER_LENGTH + 13 + 4;
+        bytes.splice(answer_name..answer_name + 13, [0xC0, 0x0C]);
+        let parsed = DnsMessage::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed.answers[0].name, "example.com");
+        assert_eq!(parsed.answers[0].data, vec![10, 0, 0, 1]);
+    }
+
+    #[test]
+    fn from_bytes_rejects_truncated_messages() {
+        let bytes = DnsMessage::query("www.example.com", DnsMessage::TYPE_A).unwrap().to_bytes();
+        assert!(DnsMessage::from_bytes(&bytes[..11]).is_err());
+        assert!(DnsMessage::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
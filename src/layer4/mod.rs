@@ -0,0 +1,9 @@
+pub(crate) mod dhcp;
+pub(crate) mod dns;
+pub(crate) mod tcp;
+pub(crate) mod udp;
+
+pub use dhcp::{DhcpMessage, DhcpServer};
+pub use dns::DnsMessage;
+pub use tcp::{TcpFlags, TcpSegment};
+pub use udp::UdpDatagram;
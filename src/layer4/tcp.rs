@@ -0,0 +1,267 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::layer3::address::IPv4Address;
+use crate::layer3::packets::IPv4Packet;
+use crate::util::internet_checksum;
+
+/// TCPヘッダのフラグ (1バイト)
+/// 上位ビットから CWR ECE URG ACK PSH RST SYN FIN の順に並ぶ
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TcpFlags(pub u8);
+
+impl fmt::Display for TcpFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<&str> = [
+            (Self::URG, "URG"),
+            (Self::ACK, "ACK"),
+            (Self::PSH, "PSH"),
+            (Self::RST, "RST"),
+            (Self::SYN, "SYN"),
+            (Self::FIN, "FIN"),
+        ]
+        .into_iter()
+        .filter(|(bit, _)| self.0 & bit != 0)
+        .map(|(_, name)| name)
+        .collect();
+        write!(f, "{:02X} [{}]", self.0, names.join(" "))
+    }
+}
+
+impl TcpFlags {
+    pub const FIN: u8 = 0x01;
+    pub const SYN: u8 = 0x02;
+    pub const RST: u8 = 0x04;
+    pub const PSH: u8 = 0x08;
+    pub const ACK: u8 = 0x10;
+    pub const URG: u8 = 0x20;
+
+    pub fn syn(&self) -> bool { self.get(Self::SYN) }
+    pub fn ack(&self) -> bool { self.get(Self::ACK) }
+    pub fn fin(&self) -> bool { self.get(Self::FIN) }
+    pub fn rst(&self) -> bool { self.get(Self::RST) }
+    pub fn psh(&self) -> bool { self.get(Self::PSH) }
+    pub fn urg(&self) -> bool { self.get(Self::URG) }
+
+    pub fn set_syn(&mut self, value: bool) { self.set(Self::SYN, value) }
+    pub fn set_ack(&mut self, value: bool) { self.set(Self::ACK, value) }
+    pub fn set_fin(&mut self, value: bool) { self.set(Self::FIN, value) }
+    pub fn set_rst(&mut self, value: bool) { self.set(Self::RST, value) }
+    pub fn set_psh(&mut self, value: bool) { self.set(Self::PSH, value) }
+    pub fn set_urg(&mut self, value: bool) { self.set(Self::URG, value) }
+
+    fn get(&self, bit: u8) -> bool {
+        self.0 & bit != 0
+    }
+
+    fn set(&mut self, bit: u8, value: bool) {
+        if value {
+            self.0 |= bit;
+        } else {
+            self.0 &= !bit;
+        }
+    }
+}
+
+/// TCPセグメント(RFC 793)
+/// IPv4パケットのprotocolが6(IPv4Packet::PROTOCOL_TCP)の時のペイロード
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TcpSegment {
+    pub src_port    : u16,      // 送信元ポート番号 (2バイト)
+    pub dst_port    : u16,      // 宛先ポート番号 (2バイト)
+    pub seq         : u32,      // シーケンス番号 (4バイト)
+    pub ack         : u32,      // 確認応答番号 (4バイト) ACKフラグが立っている時だけ意味がある
+    pub data_offset : u8,       // ヘッダ長 (4ビット) 4バイト単位。オプションなしなら5
+    pub flags       : TcpFlags, // フラグ (1バイト)
+    pub window      : u16,      // ウィンドウサイズ (2バイト)
+    pub checksum    : u16,      // チェックサム (2バイト) 疑似ヘッダを含めて計算する
+    pub urgent_ptr  : u16,      // 緊急ポインタ (2バイト) URGフラグが立っている時だけ意味がある
+    pub options     : Vec<u8>,  // オプション (4バイトの倍数)
+    pub payload     : Vec<u8>,  // データ
+}
+
+impl fmt::Display for TcpSegment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let formatted_payload: Vec<String> = self.payload
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect();
+        write!(
+            f,
+            "#src_port    : {}\n\
+             #dst_port    : {}\n\
+             #seq         : {}\n\
+             #ack         : {}\n\
+             #data_offset : {}\n\
+             #flags       : {}\n\
+             #window      : {}\n\
+             #checksum    : {:04X}\n\
+             #urgent_ptr  : {}\n\
+             #options     : {} bytes\n\
+             #payload     : [{}]\n",
+            self.src_port,
+            self.dst_port,
+            self.seq,
+            self.ack,
+            self.data_offset,
+            self.flags,
+            self.window,
+            self.checksum,
+            self.urgent_ptr,
+            self.options.len(),
+            formatted_payload.join(" "),
+        )
+    }
+}
+
+impl TcpSegment {
+    /// オプションなしのヘッダの長さ(バイト)
+    pub const MIN_HEADER_LENGTH: usize = 20;
+    /// 新しいセグメントのウィンドウサイズ
+    pub const DEFAULT_WINDOW: u16 = 65535;
+
+    /// 新しいセグメントを生成
+    /// オプションなし、ウィンドウサイズは65535、チェックサムは0にしておく
+    pub fn new(src_port: u16, dst_port: u16, seq: u32, ack: u32, flags: TcpFlags, payload: Vec<u8>) -> Self {
+        Self {
+            src_port,
+            dst_port,
+            seq,
+            ack,
+            data_offset : (Self::MIN_HEADER_LENGTH / 4) as u8,
+            flags,
+            window      : Self::DEFAULT_WINDOW,
+            checksum    : 0,
+            urgent_ptr  : 0,
+            options     : Vec::new(),
+            payload,
+        }
+    }
+
+    /// ヘッダの長さ(バイト)
+    pub fn header_length(&self) -> usize {
+        self.data_offset as usize * 4
+    }
+
+    /// チェックサムを計算する
+    /// 疑似ヘッダ + チェックサムのフィールドを0にしたセグメント全体に対するインターネットチェックサム
+    pub fn compute_checksum(&self, src_ip: IPv4Address, dst_ip: IPv4Address) -> u16 {
+        let segment = self.bytes_with_checksum(0);
+        let mut bytes = IPv4Packet::pseudo_header(src_ip, dst_ip, IPv4Packet::PROTOCOL_TCP, segment.len() as u16).to_vec();
+        bytes.extend_from_slice(&segment);
+        internet_checksum(&bytes)
+    }
+
+    /// バイト配列に変換
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bytes_with_checksum(self.checksum)
+    }
+
+    /// 指定したチェックサムでバイト配列にする
+    fn bytes_with_checksum(&self, checksum: u16) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.header_length() + self.payload.len());
+        bytes.extend_from_slice(&self.src_port.to_be_bytes());
+        bytes.extend_from_slice(&self.dst_port.to_be_bytes());
+        bytes.extend_from_slice(&self.seq.to_be_bytes());
+        bytes.extend_from_slice(&self.ack.to_be_bytes());
+        bytes.push(self.data_offset << 4);
+        bytes.push(self.flags.0);
+        bytes.extend_from_slice(&self.window.to_be_bytes());
+        bytes.extend_from_slice(&checksum.to_be_bytes());
+        bytes.extend_from_slice(&self.urgent_ptr.to_be_bytes());
+        bytes.extend_from_slice(&self.options);
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
+
+    /// バイト配列からTcpSegmentを構築
+    /// data_offsetからヘッダ長を求め、20バイトを超える部分をオプション、残りをすべてデータとする
+    pub fn from_bytes(bytes: &[u8]) -> Result<TcpSegment, &'static str> {
+        if bytes.len() < Self::MIN_HEADER_LENGTH {
+            return Err("TCP segment must be at least 20 bytes");
+        }
+        let data_offset = bytes[12] >> 4;
+        let header_length = data_offset as usize * 4;
+        if header_length < Self::MIN_HEADER_LENGTH {
+            return Err("TCP data offset must be at least 5");
+        }
+        if bytes.len() < header_length {
+            return Err("TCP segment is shorter than its header length");
+        }
+        Ok(TcpSegment {
+            src_port    : u16::from_be_bytes([bytes[0], bytes[1]]),
+            dst_port    : u16::from_be_bytes([bytes[2], bytes[3]]),
+            seq         : u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            ack         : u32::from_be_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]),
+            data_offset,
+            flags       : TcpFlags(bytes[13]),
+            window      : u16::from_be_bytes([bytes[14], bytes[15]]),
+            checksum    : u16::from_be_bytes([bytes[16], bytes[17]]),
+            urgent_ptr  : u16::from_be_bytes([bytes[18], bytes[19]]),
+            options     : bytes[Self::MIN_HEADER_LENGTH..header_length].to_vec(),
+            payload     : bytes[header_length..].to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 192.168.0.10:49152 → 192.168.0.1:80 のSYN (MSS=1460のオプション付き)
+    const SYN: [u8; 24] = [
+        0xC0, 0x00, 0x00, 0x50, // 送信元ポート 49152, 宛先ポート 80
+        0x00, 0x00, 0x03, 0xE8, // seq 1000
+        0x00, 0x00, 0x00, 0x00, // ack 0
+        0x60, 0x02, 0xFF, 0xFF, // data_offset 6, SYN, window 65535
+        0x52, 0x92, 0x00, 0x00, // checksum, urgent_ptr
+        0x02, 0x04, 0x05, 0xB4, // MSS 1460
+    ];
+
+    fn ip(s: &str) -> IPv4Address {
+        IPv4Address::from_string(s).unwrap()
+    }
+
+    #[test]
+    fn parses_a_syn_segment() {
+        let segment = TcpSegment::from_bytes(&SYN).unwrap();
+        assert_eq!((segment.src_port, segment.dst_port), (49152, 80));
+        assert_eq!((segment.seq, segment.ack), (1000, 0));
+        assert_eq!(segment.header_length(), 24);
+        assert!(segment.flags.syn());
+        assert!(!segment.flags.ack() && !segment.flags.fin() && !segment.flags.rst());
+        assert_eq!(segment.options, vec![0x02, 0x04, 0x05, 0xB4]);
+        assert!(segment.payload.is_empty());
+        assert_eq!(segment.to_bytes(), SYN.to_vec());
+    }
+
+    #[test]
+    fn checksum_covers_the_ipv4_pseudo_header() {
+        let segment = TcpSegment::from_bytes(&SYN).unwrap();
+        assert_eq!(segment.compute_checksum(ip("192.168.0.10"), ip("192.168.0.1")), 0x5292);
+        // 疑似ヘッダのアドレスが違えばチェックサムも変わる
+        assert_ne!(segment.compute_checksum(ip("192.168.0.11"), ip("192.168.0.1")), 0x5292);
+    }
+
+    #[test]
+    fn flag_setters_toggle_single_bits() {
+        let mut flags = TcpFlags::default();
+        flags.set_syn(true);
+        flags.set_ack(true);
+        assert_eq!(flags.0, TcpFlags::SYN | TcpFlags::ACK);
+        flags.set_syn(false);
+        assert_eq!(flags.0, TcpFlags::ACK);
+        assert_eq!(flags.to_string(), "10 [ACK]");
+    }
+
+    #[test]
+    fn from_bytes_rejects_bad_header_lengths() {
+        assert!(TcpSegment::from_bytes(&SYN[..19]).is_err());
+        let mut short_offset = SYN;
+        short_offset[12] = 0x40;
+        assert!(TcpSegment::from_bytes(&short_offset).is_err());
+        let mut long_offset = SYN;
+        long_offset[12] = 0x70;
+        assert!(TcpSegment::from_bytes(&long_offset).is_err());
+    }
+}
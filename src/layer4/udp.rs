@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::layer3::address::IPv4Address;
+use crate::layer3::packets::IPv4Packet;
+use crate::util::internet_checksum;
+
+/// UDPデータグラム(RFC 768)
+/// IPv4パケットのprotocolが17(IPv4Packet::PROTOCOL_UDP)の時のペイロード
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct UdpDatagram {
+    pub src_port : u16,     // 送信元ポート番号 (2バイト)
+    pub dst_port : u16,     // 宛先ポート番号 (2バイト)
+    pub length   : u16,     // ヘッダとデータを合わせた長さ (2バイト)
+    pub checksum : u16,     // チェックサム (2バイト) IPv4では0なら計算していないという意味
+    pub payload  : Vec<u8>, // データ
+}
+
+impl fmt::Display for UdpDatagram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let formatted_payload: Vec<String> = self.payload
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect();
+        write!(
+            f,
+            "#src_port : {}\n\
+             #dst_port : {}\n\
+             #length   : {}\n\
+             #checksum : {:04X}\n\
+             #payload  : [{}]\n",
+            self.src_port,
+            self.dst_port,
+            self.length,
+            self.checksum,
+            formatted_payload.join(" "),
+        )
+    }
+}
+
+impl UdpDatagram {
+    /// ヘッダの長さ(バイト)
+    pub const HEADER_LENGTH: usize = 8;
+
+    /// 新しいデータグラムを生成
+    /// lengthはヘッダ + データの長さを計算して埋める。チェックサムは0(計算していない)にしておく
+    pub fn new(src_port: u16, dst_port: u16, payload: Vec<u8>) -> Self {
+        Self {
+            src_port,
+            dst_port,
+            length   : (Self::HEADER_LENGTH + payload.len()) as u16,
+            checksum : 0,
+            payload,
+        }
+    }
+
+    /// チェックサムを計算する
+    /// 疑似ヘッダ + チェックサムのフィールドを0にしたデータグラム全体に対するインターネットチェックサム
+    /// 計算結果が0の場合は、0(計算していない)と区別するために0xFFFFにする
+    pub fn compute_checksum(&self, src_ip: IPv4Address, dst_ip: IPv4Address) -> u16 {
+        let mut bytes = IPv4Packet::pseudo_header(src_ip, dst_ip, IPv4Packet::PROTOCOL_UDP, self.length).to_vec();
+        bytes.extend_from_slice(&self.bytes_with_checksum(0));
+        match internet_checksum(&bytes) {
+            0 => 0xFFFF,
+            checksum => checksum,
+        }
+    }
+
+    /// バイト配列に変換
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bytes_with_checksum(self.checksum)
+    }
+
+    /// 指定したチェックサムでバイト配列にする
+    fn bytes_with_checksum(&self, checksum: u16) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::HEADER_LENGTH + self.payload.len());
+        bytes.extend_from_slice(&self.src_port.to_be_bytes());
+        bytes.extend_from_slice(&self.dst_port.to_be_bytes());
+        bytes.extend_from_slice(&self.length.to_be_bytes());
+        bytes.extend_from_slice(&checksum.to_be_bytes());
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
+
+    /// バイト配列からUdpDatagramを構築
+    /// lengthフィールドの長さまでをデータグラムとして読み取り、後ろに余ったバイト(イーサネットのパディングなど)は無視する
+    pub fn from_bytes(bytes: &[u8]) -> Result<UdpDatagram, &'static str> {
+        if bytes.len() < Self::HEADER_LENGTH {
+            return Err("UDP datagram must be at least 8 bytes");
+        }
+        let length = u16::from_be_bytes([bytes[4], bytes[5]]);
+        if (length as usize) < Self::HEADER_LENGTH || (length as usize) > bytes.len() {
+            return Err("UDP length field does not match the datagram");
+        }
+        Ok(UdpDatagram {
+            src_port : u16::from_be_bytes([bytes[0], bytes[1]]),
+            dst_port : u16::from_be_bytes([bytes[2], bytes[3]]),
+            length,
+            checksum : u16::from_be_bytes([bytes[6], bytes[7]]),
+            payload  : bytes[Self::HEADER_LENGTH..length as usize].to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IPv4Address {
+        IPv4Address::from_string(s).unwrap()
+    }
+
+    #[test]
+    fn new_fills_the_length_with_header_and_payload() {
+        assert_eq!(UdpDatagram::new(12345, 53, Vec::new()).length, 8);
+        assert_eq!(UdpDatagram::new(12345, 53, b"hello".to_vec()).length, 13);
+    }
+
+    #[test]
+    fn checksum_matches_a_known_vector_and_verifies_to_zero() {
+        let mut datagram = UdpDatagram::new(12345, 53, b"hello".to_vec());
+        datagram.checksum = datagram.compute_checksum(ip("10.0.0.1"), ip("10.0.0.2"));
+        assert_eq!(datagram.checksum, 0x7791);
+
+        // 計算したチェックサムを入れたまま疑似ヘッダごと足し合わせると0になる
+        let mut bytes = IPv4Packet::pseudo_header(ip("10.0.0.1"), ip("10.0.0.2"), IPv4Packet::PROTOCOL_UDP, datagram.length).to_vec();
+        bytes.extend_from_slice(&datagram.to_bytes());
+        assert_eq!(internet_checksum(&bytes), 0);
+    }
+
+    #[test]
+    fn from_bytes_round_trips_and_ignores_trailing_padding() {
+        let mut datagram = UdpDatagram::new(12345, 53, b"hello".to_vec());
+        datagram.checksum = 0x7791;
+        let mut bytes = datagram.to_bytes();
+        assert_eq!(UdpDatagram::from_bytes(&bytes), Ok(datagram.clone()));
+        bytes.extend_from_slice(&[0; 10]);
+        assert_eq!(UdpDatagram::from_bytes(&bytes), Ok(datagram));
+    }
+
+    #[test]
+    fn from_bytes_rejects_short_or_inconsistent_input() {
+        assert!(UdpDatagram::from_bytes(&[0; 7]).is_err());
+        assert!(UdpDatagram::from_bytes(&[0x30, 0x39, 0x00, 0x35, 0x00, 0x10, 0x00, 0x00]).is_err());
+        assert!(UdpDatagram::from_bytes(&[0x30, 0x39, 0x00, 0x35, 0x00, 0x04, 0x00, 0x00]).is_err());
+    }
+}
@@ -2,20 +2,47 @@
 pub(crate) mod layer1;  // 物理層の実装
 pub(crate) mod layer2;  // データリンク層の実装
 pub(crate) mod layer3;  // ネットワーク層の実装
+pub(crate) mod layer4;  // トランスポート層より上のプロトコルの実装
+pub(crate) mod util;    // 各層で共通して使う処理
+pub(crate) mod error;   // 共通のエラー型
+pub(crate) mod capture; // パケットキャプチャの実装
+pub(crate) mod topology; // コンポーネントとケーブルのつながりの管理
 
 use layer1::component::EthernetCable;
-use layer1::PhysicalLayerCallback;
+use layer1::component::Hub;
+use layer2::component::Switch;
+use layer2::component::switch::SwitchEvent;
+use layer2::component::NetworkInterface;
+use layer2::component::Bridge;
+use layer2::component::bridge::BridgePort;
+use layer3::component::Router;
+use topology::{ComponentHandle, Topology};
+use layer1::{PhysicalLayerCallback, TapCallback};
 // 必要なクレートをインポート
 use wasm_bindgen::prelude::*;      // WebAssembly関連の機能
 use wasm_bindgen::JsValue;         // JavaScript値との相互運用
 use js_sys::Uint8Array;            // JavaScript配列型との相互運用
+use std::sync::Arc;
 
 // 必要な型をインポート
 use crate::layer1::packets::PhysicalLayerFrame; // 物理層フレーム
+use crate::layer1::component::ethernet_cable::{CableEvent, Endpoint, TapFilter}; // ケーブルのイベント、ケーブルの端、タップで受け取るフレームの条件
 use crate::layer2::packets::EthernetFrame;      // イーサネットフレーム
+use crate::layer2::protocols::ArpPacket;        // ARPパケット
+use crate::layer2::protocols::ArpCache;         // ARPキャッシュ
 use crate::layer2::address::MacAddress;         // MACアドレス
 use crate::layer3::address::IPv4Address;        // IPv4アドレス
 use crate::layer3::address::IPv6Address;        // IPv6アドレス
+use crate::layer3::packets::IPv4Packet;         // IPv4パケット
+use crate::layer3::packets::IPv6Packet;         // IPv6パケット
+use crate::layer3::protocols::IcmpMessage;      // ICMPメッセージ
+use crate::layer4::UdpDatagram;                 // UDPデータグラム
+use crate::layer4::{TcpFlags, TcpSegment};      // TCPセグメント
+use crate::layer4::DhcpMessage;                 // DHCPメッセージ
+use crate::layer4::DhcpServer;                  // DHCPサーバー
+use crate::layer4::DnsMessage;                  // DNSメッセージ
+use crate::capture::PcapWriter;                 // pcap形式での書き出し
+use crate::util::hex_dump;                      // 16進ダンプ
 
 
 //////////////////////////////////////////////
@@ -45,10 +72,32 @@ impl WasmMacAddress {
         }
     }
 
+    /// 先頭3オクテットを指定したOUIにしたMACアドレスをランダムに作成
+    /// 
+    /// ### 引数
+    /// * `oui` - 3バイトのOUI
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmMacAddress, JsValue>` - 成功時はWasmMacAddress、長さが3バイトでない場合はエラーメッセージ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let mac = WasmMacAddress.new_with_oui(new Uint8Array([0x00, 0x50, 0x56])); // VMwareのMACアドレス
+    /// ```
+    #[wasm_bindgen]
+    pub fn new_with_oui(oui: &[u8]) -> Result<WasmMacAddress, JsValue> {
+        let oui: [u8; 3] = oui.try_into().map_err(|_| {
+            JsValue::from_str(&format!("OUIは3バイトである必要があります（{}バイトが渡されました）", oui.len()))
+        })?;
+        Ok(WasmMacAddress {
+            inner_mac: MacAddress::new_with_oui(oui)
+        })
+    }
+
     /// 文字列からMACアドレスを生成
     /// 
     /// ### 引数
-    /// * `mac_str` - "00:11:22:33:44:55" 形式のMACアドレス文字列
+    /// * `mac_str` - "00:11:22:33:44:55" 形式、または区切りのない "001122334455" 形式のMACアドレス文字列
     /// 
     /// ### 戻り値
     /// * `Result<WasmMacAddress, JsValue>` - 成功時はWasmMacAddress、失敗時はエラーメッセージ
@@ -66,10 +115,52 @@ impl WasmMacAddress {
                 inner_mac: mac_address
             }),
             // 変換失敗時はエラーメッセージをJavaScript用の値に変換
-            Err(error_message) => Err(JsValue::from_str(error_message))
+            Err(error) => Err(error.into())
+        }
+    }
+
+    /// いろいろな表記の文字列からMACアドレスを作成
+    /// 
+    /// ### 引数
+    /// * `mac_str` - "00:11:22:33:44:55"、"00-11-22-33-44-55"、"0011.2233.4455"、"001122334455" のいずれかの形式の文字列
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmMacAddress, JsValue>` - 成功時はWasmMacAddress、失敗時はエラーメッセージ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let mac = WasmMacAddress.from_string_flexible("0011.2233.4455");
+    /// ```
+    #[wasm_bindgen]
+    pub fn from_string_flexible(mac_str: &str) -> Result<WasmMacAddress, JsValue> {
+        match MacAddress::from_string_flexible(mac_str) {
+            Ok(mac_address) => Ok(WasmMacAddress {
+                inner_mac: mac_address
+            }),
+            Err(error) => Err(error.into())
         }
     }
 
+    /// バイト配列からMACアドレスを作成
+    /// 
+    /// ### 引数
+    /// * `data` - 6バイトのMACアドレスデータ
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmMacAddress, JsValue>` - 成功時はWasmMacAddress、長さが6バイトでない場合はエラーメッセージ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let mac = WasmMacAddress.from_bytes(new Uint8Array([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]));
+    /// ```
+    #[wasm_bindgen]
+    pub fn from_bytes(data: &[u8]) -> Result<WasmMacAddress, JsValue> {
+        let address = MacAddress::from_slice(data)?;
+        Ok(WasmMacAddress {
+            inner_mac: address
+        })
+    }
+
     /// MACアドレスを文字列形式で取得
     /// 
     /// ### 戻り値
@@ -80,16 +171,122 @@ impl WasmMacAddress {
         self.inner_mac.to_string()
     }
 
+    /// セパレータを指定してMACアドレスを文字列形式で取得
+    /// 
+    /// ### 引数
+    /// * `separator` - オクテットの間に入れる文字。"\0"を指定するとセパレータなし
+    /// 
+    /// ### 戻り値
+    /// * `String` - 大文字の2桁の16進数をセパレータでつないだ文字列
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// mac.to_string_with_separator("-");  // "00-11-22-33-44-55"
+    /// mac.to_string_with_separator("\0"); // "001122334455"
+    /// ```
+    #[wasm_bindgen]
+    pub fn to_string_with_separator(&self, separator: char) -> String {
+        self.inner_mac.to_string_with_separator(separator)
+    }
+
     /// MACアドレスをバイト配列として取得
+    /// from_bytesに渡すと同じMACアドレスに戻る
     /// 
     /// ### 戻り値
     /// * `Uint8Array` - 6バイトのMACアドレスデータ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let copy = WasmMacAddress.from_bytes(mac.to_bytes());
+    /// ```
     #[wasm_bindgen]
     pub fn to_bytes(&self) -> Uint8Array {
-        // 内部のMacAddressインスタンスからバイト配列を取得
-        let mac_bytes = self.inner_mac.to_array();
-        // バイト配列をJavaScript用のUint8Arrayに変換
-        Uint8Array::from(&mac_bytes[..])
+        // 内部のMacAddressインスタンスのバイト列を借用して、そのままJavaScript用のUint8Arrayにコピーする
+        Uint8Array::from(self.inner_mac.as_slice())
+    }
+
+    /// MACアドレスからEUI-64形式のインターフェースIDを取得
+    /// 
+    /// ### 戻り値
+    /// * `Uint8Array` - 8バイトのインターフェースID（FF:FEを挟みU/Lビットを反転したもの）
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let eui64 = WasmMacAddress.from_string("00:11:22:33:44:55").to_eui64();
+    /// // => [0x02, 0x11, 0x22, 0xFF, 0xFE, 0x33, 0x44, 0x55]
+    /// ```
+    #[wasm_bindgen]
+    pub fn to_eui64(&self) -> Uint8Array {
+        let eui64 = self.inner_mac.to_eui64();
+        Uint8Array::from(&eui64[..])
+    }
+
+    /// IPv6マルチキャストアドレスに対応するMACアドレス(33:33:XX:XX:XX:XX)を生成
+    /// 
+    /// ### 引数
+    /// * `ip` - IPv6マルチキャストアドレス
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let mac = WasmMacAddress.from_ipv6_multicast(WasmIPv6Address.from_string("ff02:0:0:0:0:1:ff33:4455"));
+    /// // => 33:33:FF:33:44:55
+    /// ```
+    #[wasm_bindgen]
+    pub fn from_ipv6_multicast(ip: &WasmIPv6Address) -> WasmMacAddress {
+        WasmMacAddress {
+            inner_mac: MacAddress::from_ipv6_multicast(&ip.inner_ip)
+        }
+    }
+
+    /// OUI(先頭3オクテット)からベンダー名を取得
+    /// 
+    /// ### 戻り値
+    /// * `Option<String>` - ベンダー名。組み込みの表にないOUIの場合はundefined
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// WasmMacAddress.from_string("00:50:56:12:34:56").vendor_name(); // "VMware"
+    /// ```
+    #[wasm_bindgen]
+    pub fn vendor_name(&self) -> Option<String> {
+        self.inner_mac.vendor_name().map(|name| name.to_string())
+    }
+
+    /// ブロードキャストアドレス(FF:FF:FF:FF:FF:FF)かどうか
+    #[wasm_bindgen]
+    pub fn is_broadcast(&self) -> bool {
+        self.inner_mac.is_broadcast()
+    }
+
+    /// マルチキャストアドレス(先頭オクテットのI/Gビットが1)かどうか
+    #[wasm_bindgen]
+    pub fn is_multicast(&self) -> bool {
+        self.inner_mac.is_multicast()
+    }
+
+    /// ユニキャストアドレスかどうか
+    #[wasm_bindgen]
+    pub fn is_unicast(&self) -> bool {
+        self.inner_mac.is_unicast()
+    }
+
+    /// ローカル管理アドレス(先頭オクテットのU/Lビットが1)かどうか
+    /// WasmMacAddress.new()で作ったアドレスは常にtrue
+    #[wasm_bindgen]
+    pub fn is_locally_administered(&self) -> bool {
+        self.inner_mac.is_locally_administered()
+    }
+
+    /// 別のMACアドレスと値が等しいかを比較
+    /// 
+    /// ### 引数
+    /// * `other` - 比較するMACアドレス
+    /// 
+    /// ### 戻り値
+    /// * `bool` - 値が等しければtrue
+    #[wasm_bindgen]
+    pub fn equals(&self, other: &WasmMacAddress) -> bool {
+        self.inner_mac == other.inner_mac
     }
 }
 
@@ -120,6 +317,36 @@ impl WasmIPv4Address {
         }
     }
 
+    /// 指定したネットワークの中から、ホストに割り当てられるIPv4アドレスをランダムに作成
+    /// 
+    /// ### 引数
+    /// * `network` - ネットワーク内のアドレス（ネットワークアドレスでなくてもよい）
+    /// * `prefix` - プレフィックス長
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let ip = WasmIPv4Address.new_in_subnet(WasmIPv4Address.from_string("10.0.0.0"), 28); // 10.0.0.1〜10.0.0.14
+    /// ```
+    #[wasm_bindgen]
+    pub fn new_in_subnet(network: &WasmIPv4Address, prefix: u8) -> WasmIPv4Address {
+        WasmIPv4Address {
+            inner_ip: IPv4Address::new_in_subnet(network.inner_ip, prefix)
+        }
+    }
+
+    /// プライベート/ループバック/マルチキャスト/予約済みではないIPv4アドレスをランダムに作成
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let ipv4 = WasmIPv4Address.new_public();
+    /// ```
+    #[wasm_bindgen]
+    pub fn new_public() -> WasmIPv4Address {
+        WasmIPv4Address {
+            inner_ip: IPv4Address::new_public()
+        }
+    }
+
     /// 文字列からIPv4アドレスを生成
     /// 
     /// ###引数
@@ -141,10 +368,178 @@ impl WasmIPv4Address {
                 inner_ip: ip_address
             }),
             // 変換失敗時はエラーメッセージをJavaScript用の値に変換
-            Err(error_message) => Err(JsValue::from_str(error_message))
+            Err(error) => Err(error.into())
+        }
+    }
+
+    /// バイト配列からIPv4アドレスを作成
+    /// 
+    /// ### 引数
+    /// * `data` - 4バイトのIPv4アドレスデータ
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmIPv4Address, JsValue>` - 成功時はWasmIPv4Address、長さが4バイトでない場合はエラーメッセージ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let ip = WasmIPv4Address.from_bytes(new Uint8Array([192, 168, 1, 1]));
+    /// ```
+    #[wasm_bindgen]
+    pub fn from_bytes(data: &[u8]) -> Result<WasmIPv4Address, JsValue> {
+        let address = IPv4Address::from_slice(data)?;
+        Ok(WasmIPv4Address {
+            inner_ip: address
+        })
+    }
+
+    /// CIDR表記の文字列からIPv4アドレスとプレフィックス長を取得
+    /// 
+    /// ### 引数
+    /// * `cidr` - "192.168.1.0/24" 形式の文字列
+    /// 
+    /// ### 戻り値
+    /// * `Result<js_sys::Array, JsValue>` - 成功時は [WasmIPv4Address, プレフィックス長] の配列、失敗時はエラーメッセージ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// const [ip, prefix] = WasmIPv4Address.from_cidr("192.168.1.10/24");
+    /// ip.network_address(prefix).to_string();   // 192.168.1.0
+    /// ip.broadcast_address(prefix).to_string(); // 192.168.1.255
+    /// ```
+    #[wasm_bindgen]
+    pub fn from_cidr(cidr: &str) -> Result<js_sys::Array, JsValue> {
+        let (ip_address, prefix) = IPv4Address::from_cidr(cidr)?;
+        let wasm_ip: JsValue = WasmIPv4Address { inner_ip: ip_address }.into();
+        Ok(js_sys::Array::of2(&wasm_ip, &JsValue::from(prefix)))
+    }
+
+    /// プレフィックス長からサブネットマスクを作成
+    /// 
+    /// ### 引数
+    /// * `prefix` - プレフィックス長(0〜32)
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// WasmIPv4Address.netmask_from_prefix(24).to_string(); // 255.255.255.0
+    /// ```
+    #[wasm_bindgen]
+    pub fn netmask_from_prefix(prefix: u8) -> WasmIPv4Address {
+        WasmIPv4Address {
+            inner_ip: IPv4Address::netmask_from_prefix(prefix)
+        }
+    }
+
+    /// プレフィックス長からワイルドカードマスク(サブネットマスクのビットを反転したもの)を作成
+    /// 
+    /// ### 引数
+    /// * `prefix` - プレフィックス長(0〜32)
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// WasmIPv4Address.wildcard_mask(24).to_string(); // 0.0.0.255
+    /// ```
+    #[wasm_bindgen]
+    pub fn wildcard_mask(prefix: u8) -> WasmIPv4Address {
+        WasmIPv4Address {
+            inner_ip: IPv4Address::wildcard_mask(prefix)
+        }
+    }
+
+    /// ネットワーク内でホストに割り当てられるアドレスの数を取得
+    /// 
+    /// ### 引数
+    /// * `prefix` - プレフィックス長(0〜32)
+    /// 
+    /// ### 戻り値
+    /// * `u32` - /24なら254、/31なら2、/32なら1
+    #[wasm_bindgen]
+    pub fn host_count(prefix: u8) -> u32 {
+        IPv4Address::host_count(prefix)
+    }
+
+    /// ネットワークアドレスとブロードキャストアドレスを除いた、使えるホストアドレスの数を取得
+    /// 
+    /// ### 引数
+    /// * `prefix` - プレフィックス長(0〜32)
+    /// 
+    /// ### 戻り値
+    /// * `u32` - 2^(32-prefix) - 2。/24なら254、/31と/32は0
+    #[wasm_bindgen]
+    pub fn usable_hosts(prefix: u8) -> u32 {
+        IPv4Address::usable_hosts(prefix)
+    }
+
+    /// このアドレスが属するネットワークのネットワークアドレスを取得
+    /// 
+    /// ### 引数
+    /// * `prefix` - プレフィックス長(0〜32)
+    #[wasm_bindgen]
+    pub fn network_address(&self, prefix: u8) -> WasmIPv4Address {
+        WasmIPv4Address {
+            inner_ip: self.inner_ip.network_address(prefix)
         }
     }
 
+    /// このアドレスが属するネットワークのブロードキャストアドレスを取得
+    /// 
+    /// ### 引数
+    /// * `prefix` - プレフィックス長(0〜32)
+    #[wasm_bindgen]
+    pub fn broadcast_address(&self, prefix: u8) -> WasmIPv4Address {
+        WasmIPv4Address {
+            inner_ip: self.inner_ip.broadcast_address(prefix)
+        }
+    }
+
+    /// 相手のアドレスと同じネットワークに属しているかどうか
+    /// 同じネットワークならルーターを通さずに直接通信できる
+    /// 
+    /// ### 引数
+    /// * `other` - 比べるIPv4アドレス
+    /// * `prefix` - プレフィックス長(0〜32)
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let a = WasmIPv4Address.from_string("192.168.1.10");
+    /// let b = WasmIPv4Address.from_string("192.168.1.200");
+    /// a.same_subnet(b, 24); // true
+    /// a.same_subnet(b, 25); // false
+    /// ```
+    #[wasm_bindgen]
+    pub fn same_subnet(&self, other: &WasmIPv4Address, prefix: u8) -> bool {
+        IPv4Address::same_subnet(self.inner_ip, other.inner_ip, prefix)
+    }
+
+    /// プライベートアドレス(10.0.0.0/8, 172.16.0.0/12, 192.168.0.0/16)かどうか
+    #[wasm_bindgen]
+    pub fn is_private(&self) -> bool {
+        self.inner_ip.is_private()
+    }
+
+    /// ループバックアドレス(127.0.0.0/8)かどうか
+    #[wasm_bindgen]
+    pub fn is_loopback(&self) -> bool {
+        self.inner_ip.is_loopback()
+    }
+
+    /// マルチキャストアドレス(224.0.0.0/4)かどうか
+    #[wasm_bindgen]
+    pub fn is_multicast(&self) -> bool {
+        self.inner_ip.is_multicast()
+    }
+
+    /// リンクローカルアドレス(169.254.0.0/16)かどうか
+    #[wasm_bindgen]
+    pub fn is_link_local(&self) -> bool {
+        self.inner_ip.is_link_local()
+    }
+
+    /// リミテッドブロードキャストアドレス(255.255.255.255)かどうか
+    #[wasm_bindgen]
+    pub fn is_broadcast(&self) -> bool {
+        self.inner_ip.is_broadcast()
+    }
+
     /// IPv4アドレスを文字列形式で取得
     /// 
     /// ### 戻り値
@@ -155,6 +550,21 @@ impl WasmIPv4Address {
         self.inner_ip.to_string()
     }
 
+    /// 逆引き(PTRレコード)の名前を取得
+    /// 
+    /// ### 戻り値
+    /// * `String` - "4.3.2.1.in-addr.arpa" のように、オクテットを逆順に並べた名前
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let ip = WasmIPv4Address.from_string("1.2.3.4");
+    /// let query = WasmDnsMessage.query(ip.to_ptr_name(), 12);
+    /// ```
+    #[wasm_bindgen]
+    pub fn to_ptr_name(&self) -> String {
+        self.inner_ip.to_ptr_name()
+    }
+
     /// IPv4アドレスをバイト配列として取得
     /// 
     /// ### 戻り値
@@ -166,6 +576,18 @@ impl WasmIPv4Address {
         // バイト配列をJavaScript用のUint8Arrayに変換
         Uint8Array::from(&ip_bytes[..])
     }
+
+    /// 別のIPv4アドレスと値が等しいかを比較
+    /// 
+    /// ### 引数
+    /// * `other` - 比較するIPv4アドレス
+    /// 
+    /// ### 戻り値
+    /// * `bool` - 値が等しければtrue
+    #[wasm_bindgen]
+    pub fn equals(&self, other: &WasmIPv4Address) -> bool {
+        self.inner_ip == other.inner_ip
+    }
 }
 
 /// WebAssemblyからIPv6アドレスを扱うためのラッパー構造体
@@ -212,10 +634,30 @@ impl WasmIPv6Address {
                 inner_ip: ip_address
             }),
             // 変換失敗時はエラーメッセージをJavaScript用の値に変換
-            Err(error_message) => Err(JsValue::from_str(error_message))
+            Err(error) => Err(error.into())
         }
     }
 
+    /// バイト配列からIPv6アドレスを作成
+    /// 
+    /// ### 引数
+    /// * `data` - 16バイトのIPv6アドレスデータ
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmIPv6Address, JsValue>` - 成功時はWasmIPv6Address、長さが16バイトでない場合はエラーメッセージ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let ip = WasmIPv6Address.from_bytes(frame.payload().slice(8, 24));
+    /// ```
+    #[wasm_bindgen]
+    pub fn from_bytes(data: &[u8]) -> Result<WasmIPv6Address, JsValue> {
+        let address = IPv6Address::from_slice(data)?;
+        Ok(WasmIPv6Address {
+            inner_ip: address
+        })
+    }
+
     /// IPv6アドレスを文字列形式で取得
     /// 
     /// ### 戻り値
@@ -226,324 +668,3577 @@ impl WasmIPv6Address {
         self.inner_ip.to_string()
     }
 
-    /// IPv6アドレスをバイト配列として取得
+    /// IPv6アドレスをRFC 5952の標準の表記で取得
     /// 
     /// ### 戻り値
-    /// * `Uint8Array` - 16バイトのIPv6アドレスデータ
+    /// * `String` - "2001:db8::1" のように小文字で、0を省略した形式の文字列
     #[wasm_bindgen]
-    pub fn to_bytes(&self) -> Uint8Array {
-        // 内部のIPv6Addressインスタンスからバイト配列を取得
+    pub fn to_canonical_string(&self) -> String {
+        self.inner_ip.to_canonical_string()
+    }
+
+    /// IPv6アドレスを省略しない表記で取得
+    /// 
+    /// ### 戻り値
+    /// * `String` - "2001:0db8:0000:0000:0000:0000:0000:0001" のように、8つのグループを全て小文字の4桁で書いた文字列
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let ip = WasmIPv6Address.from_string("2001:db8::1");
+    /// console.log(ip.to_canonical_string(), ip.to_expanded_string());
+    /// ```
+    #[wasm_bindgen]
+    pub fn to_expanded_string(&self) -> String {
+        self.inner_ip.to_expanded_string()
+    }
+
+    /// 逆引き(PTRレコード)の名前を取得
+    /// 
+    /// ### 戻り値
+    /// * `String` - "1.0.0.0.(中略).8.b.d.0.1.0.0.2.ip6.arpa" のように、4ビットずつ逆順に並べた名前
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let ip = WasmIPv6Address.from_string("2001:db8::1");
+    /// let query = WasmDnsMessage.query(ip.to_ptr_name(), 12);
+    /// ```
+    #[wasm_bindgen]
+    pub fn to_ptr_name(&self) -> String {
+        self.inner_ip.to_ptr_name()
+    }
+
+    /// IPv6アドレスをバイト配列として取得
+    /// 
+    /// ### 戻り値
+    /// * `Uint8Array` - 16バイトのIPv6アドレスデータ
+    #[wasm_bindgen]
+    pub fn to_bytes(&self) -> Uint8Array {
+        // 内部のIPv6Addressインスタンスからバイト配列を取得
         let ip_bytes = self.inner_ip.to_array();
         // バイト配列をJavaScript用のUint8Arrayに変換
         Uint8Array::from(&ip_bytes[..])
     }
+
+    /// プレフィックスとMACアドレスからEUI-64形式のIPv6アドレスを生成（SLAAC）
+    /// 
+    /// ### 引数
+    /// * `prefix` - 上位64ビットに使うプレフィックス
+    /// * `mac` - インターフェースIDの元になるMACアドレス
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let prefix = WasmIPv6Address.from_string("fe80:0:0:0:0:0:0:0");
+    /// let mac = WasmMacAddress.from_string("00:11:22:33:44:55");
+    /// let ipv6 = WasmIPv6Address.from_mac_eui64(prefix, mac);
+    /// // => fe80::211:22ff:fe33:4455
+    /// ```
+    #[wasm_bindgen]
+    pub fn from_mac_eui64(prefix: &WasmIPv6Address, mac: &WasmMacAddress) -> WasmIPv6Address {
+        WasmIPv6Address {
+            inner_ip: IPv6Address::from_mac_eui64(&prefix.inner_ip, &mac.inner_mac)
+        }
+    }
+
+    /// このアドレスの要請ノードマルチキャストアドレス(ff02::1:ffXX:XXXX)を取得
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let multicast = ipv6.solicited_node_multicast();
+    /// ```
+    #[wasm_bindgen]
+    pub fn solicited_node_multicast(&self) -> WasmIPv6Address {
+        WasmIPv6Address {
+            inner_ip: self.inner_ip.solicited_node_multicast()
+        }
+    }
+
+    /// IPv4アドレスからIPv4射影アドレス(::ffff:a.b.c.d)を作成
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let mapped = WasmIPv6Address.from_ipv4_mapped(WasmIPv4Address.from_string("192.168.1.1"));
+    /// mapped.to_canonical_string(); // "::ffff:192.168.1.1"
+    /// ```
+    #[wasm_bindgen]
+    pub fn from_ipv4_mapped(ipv4: &WasmIPv4Address) -> WasmIPv6Address {
+        WasmIPv6Address {
+            inner_ip: IPv6Address::from_ipv4_mapped(ipv4.inner_ip)
+        }
+    }
+
+    /// IPv4射影アドレスに埋め込まれているIPv4アドレスを取得
+    /// 
+    /// ### 戻り値
+    /// * `Option<WasmIPv4Address>` - IPv4射影アドレスでなければundefined
+    #[wasm_bindgen]
+    pub fn to_ipv4_mapped(&self) -> Option<WasmIPv4Address> {
+        self.inner_ip.to_ipv4_mapped().map(|ipv4| WasmIPv4Address { inner_ip: ipv4 })
+    }
+
+    /// ループバックアドレス(::1)かどうか
+    #[wasm_bindgen]
+    pub fn is_loopback(&self) -> bool {
+        self.inner_ip.is_loopback()
+    }
+
+    /// 未指定アドレス(::)かどうか
+    #[wasm_bindgen]
+    pub fn is_unspecified(&self) -> bool {
+        self.inner_ip.is_unspecified()
+    }
+
+    /// リンクローカルアドレス(fe80::/10)かどうか
+    #[wasm_bindgen]
+    pub fn is_link_local(&self) -> bool {
+        self.inner_ip.is_link_local()
+    }
+
+    /// マルチキャストアドレス(ff00::/8)かどうか
+    #[wasm_bindgen]
+    pub fn is_multicast(&self) -> bool {
+        self.inner_ip.is_multicast()
+    }
+
+    /// 別のIPv6アドレスと値が等しいかを比較
+    /// 
+    /// ### 引数
+    /// * `other` - 比較するIPv6アドレス
+    /// 
+    /// ### 戻り値
+    /// * `bool` - 値が等しければtrue
+    #[wasm_bindgen]
+    pub fn equals(&self, other: &WasmIPv6Address) -> bool {
+        self.inner_ip == other.inner_ip
+    }
+}
+
+//////////////////////////////////////////////
+// イーサネットフレームと物理層フレームのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからイーサネットフレームを扱うためのラッパー構造体
+/// inner_frame: 内部に保持する実際のEthernetFrameインスタンス
+#[wasm_bindgen]
+pub struct WasmEthernetFrame {
+    inner_frame: EthernetFrame
 }
 
+#[wasm_bindgen]
+impl WasmEthernetFrame {
+    /// 新しいイーサネットフレームを作成
+    /// 
+    /// ### 引数
+    /// * `dst_mac` - 宛先MACアドレス
+    /// * `src_mac` - 送信元MACアドレス
+    /// * `ethertype` - イーサタイプ (例: 0x0800 for IPv4)
+    /// * `data` - ペイロードデータのバイト配列
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let frame = new WasmEthernetFrame(dstMac, srcMac, 0x0800, new Uint8Array([...]));
+    /// ```
+    #[wasm_bindgen(constructor)]
+    pub fn new(dst_mac: &WasmMacAddress, src_mac: &WasmMacAddress, ethertype: u16, data: &[u8]) -> Self {
+        // 新しいEthernetFrameインスタンスを作成
+        WasmEthernetFrame {
+            inner_frame: EthernetFrame::new(
+                Some(dst_mac.inner_mac.clone()),  // 宛先MACアドレスをクローン
+                Some(src_mac.inner_mac.clone()),  // 送信元MACアドレスをクローン
+                Some(ethertype),                  // イーサタイプ
+                Some(data.to_vec())               // データをベクターにコピー
+            )
+        }
+    }
+
+    /// ブロードキャストのイーサネットフレームを作成
+    /// 宛先はブロードキャストアドレス(FF:FF:FF:FF:FF:FF)、送信元はランダムなローカル管理アドレスになる
+    /// 
+    /// ### 引数
+    /// * `ethertype` - イーサタイプ (例: 0x0806 for ARP)
+    /// * `data` - ペイロードデータのバイト配列
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let frame = WasmEthernetFrame.new_broadcast(0x0806, arpPacket.to_bytes());
+    /// ```
+    #[wasm_bindgen]
+    pub fn new_broadcast(ethertype: u16, data: &[u8]) -> WasmEthernetFrame {
+        WasmEthernetFrame {
+            inner_frame: EthernetFrame::new_broadcast(ethertype, data.to_vec())
+        }
+    }
+
+    /// バイト配列からイーサネットフレームを生成
+    /// 
+    /// ### 引数
+    /// * `data` - 宛先MAC + 送信元MAC + イーサタイプ + データ の順に並んだバイト配列（FCSは含まない）
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmEthernetFrame, JsValue>` - 成功時はWasmEthernetFrame、14バイト未満の場合はエラーメッセージ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let frame = WasmEthernetFrame.from_bytes(otherFrame.to_bytes());
+    /// ```
+    #[wasm_bindgen]
+    pub fn from_bytes(data: &[u8]) -> Result<WasmEthernetFrame, JsValue> {
+        match EthernetFrame::from_bytes(data) {
+            Ok(frame) => Ok(WasmEthernetFrame {
+                inner_frame: frame
+            }),
+            // 変換失敗時はエラーメッセージをJavaScript用の値に変換
+            Err(error_message) => Err(JsValue::from_str(error_message))
+        }
+    }
+
+    /// イーサーネットフレームを文字列形式で取得
+    /// 
+    /// ### 戻り値
+    /// * `String` - "#dst_mac,#src_mac,#type,#data" 形式の文字列
+    #[wasm_bindgen]
+    pub fn to_string(&self) -> String {
+        // 内部のEthernetFrameインスタンスの文字列表現を取得
+        self.inner_frame.to_string().replace("\n","\r\n")
+    }
+
+    /// ペイロードを先頭のmax_bytesバイトだけにしたイーサーネットフレームの文字列を取得
+    /// 
+    /// ### 引数
+    /// * `max_bytes` - 表示するペイロードの最大バイト数
+    /// 
+    /// ### 戻り値
+    /// * `String` - 省略したペイロードは "... (N more bytes)" と表示される
+    #[wasm_bindgen]
+    pub fn to_string_truncated(&self, max_bytes: usize) -> String {
+        self.inner_frame.to_string_truncated(max_bytes).replace("\n","\r\n")
+    }
+
+    /// フレームの合計長を取得（バイト単位）
+    /// 
+    /// ### 戻り値
+    /// * `usize` - イーサーネットフレームの総バイト長
+    #[wasm_bindgen]
+    pub fn total_length(&self) -> usize {
+        // 内部のEthernetFrameインスタンスの長さを取得
+        self.inner_frame.total_length()
+    }
+
+    /// ARPフレームとして最低限正しい形をしているかを確認
+    /// 
+    /// ### 戻り値
+    /// * `bool` - ethertypeが0x0806で、ペイロードのハードウェアタイプ/プロトコルタイプ/アドレス長がEthernet+IPv4のARPとして正しければtrue
+    #[wasm_bindgen]
+    pub fn is_valid_arp(&self) -> bool {
+        self.inner_frame.is_valid_arp()
+    }
+
+    /// フレーム長が最小フレーム長(60バイト、FCSを除く)になるまで、ペイロードの末尾に0を追加
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let frame = new WasmEthernetFrame(dstMac, srcMac, 0x0800, new Uint8Array(10));
+    /// frame.pad_to_minimum(); // ペイロードが46バイトになる
+    /// ```
+    #[wasm_bindgen]
+    pub fn pad_to_minimum(&mut self) {
+        self.inner_frame.pad_to_minimum()
+    }
+
+    /// pad_to_minimumで追加される0のバイト数を取得
+    /// フレームは変更しないので、送る前に「Nバイト埋められます」と表示する時などに使う
+    /// 
+    /// ### 戻り値
+    /// * `usize` - 追加されるバイト数。すでに最小フレーム長以上あれば0
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let frame = new WasmEthernetFrame(dstMac, srcMac, 0x0800, new Uint8Array(10));
+    /// frame.padding_needed(); // 36
+    /// ```
+    #[wasm_bindgen]
+    pub fn padding_needed(&self) -> usize {
+        self.inner_frame.padding_needed()
+    }
+
+    /// ペイロードを置き換える
+    /// フレームを作り直さずに中身だけ書き換えられる。total_lengthやto_bytesにもすぐ反映される
+    /// 
+    /// ### 引数
+    /// * `data` - 新しいペイロードのバイト配列
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// frame.set_payload(new Uint8Array([0x01, 0x02, 0x03]));
+    /// frame.total_length(); // 17
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_payload(&mut self, data: &[u8]) {
+        self.inner_frame.set_payload(data.to_vec())
+    }
+
+    /// ペイロードの末尾にバイト列を追加
+    /// 
+    /// ### 引数
+    /// * `more` - 追加するバイト配列
+    #[wasm_bindgen]
+    pub fn append_payload(&mut self, more: &[u8]) {
+        self.inner_frame.append_payload(more)
+    }
+
+    /// ジャンボフレームを許すかどうかを設定
+    /// 
+    /// ### 引数
+    /// * `allow` - trueならvalidateで許すペイロード長の上限を9000バイトにする。falseなら1500バイト
+    #[wasm_bindgen]
+    pub fn set_jumbo(&mut self, allow: bool) {
+        self.inner_frame.set_jumbo(allow)
+    }
+
+    /// フレームの長さが正しいかを確認
+    /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - 正しければ成功。最小フレーム長に満たない(runt)、またはペイロードが上限を超えている(oversized)場合はエラーメッセージ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let frame = new WasmEthernetFrame(dstMac, srcMac, 0x0800, new Uint8Array(10));
+    /// try { frame.validate(); } catch (e) { console.log(e); } // runt
+    /// frame.pad_to_minimum();
+    /// frame.validate(); // OK
+    /// ```
+    #[wasm_bindgen]
+    pub fn validate(&self) -> Result<(), JsValue> {
+        self.inner_frame.validate().map_err(JsValue::from_str)
+    }
+
+    /// イーサタイプの名前を取得
+    /// 
+    /// ### 戻り値
+    /// * `String` - "IPv4"、"ARP"、"IPv6"、"802.1Q"のいずれか。知らない値なら"Unknown"
+    #[wasm_bindgen]
+    pub fn ethertype_name(&self) -> String {
+        self.inner_frame.ethertype_kind().name().to_string()
+    }
+
+    /// 802.1QのVLANタグを設定
+    /// 
+    /// ### 引数
+    /// * `vid` - VLAN ID (0〜4095)
+    /// * `pcp` - 優先度 (0〜7)
+    /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - vidまたはpcpが範囲外の場合はエラーメッセージ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// frame.set_vlan(100, 0);
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_vlan(&mut self, vid: u16, pcp: u8) -> Result<(), JsValue> {
+        self.inner_frame = self.inner_frame.clone().with_vlan(vid, pcp).map_err(JsValue::from_str)?;
+        Ok(())
+    }
+
+    /// 802.1ad(QinQ)の2重のVLANタグを設定
+    /// 
+    /// ### 引数
+    /// * `s_vid` - 外側のサービスタグ(TPID 0x88A8)のVLAN ID (0〜4095)
+    /// * `c_vid` - 内側のカスタマータグ(TPID 0x8100)のVLAN ID (0〜4095)
+    /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - s_vidまたはc_vidが範囲外の場合はエラーメッセージ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// frame.set_qinq(200, 100);
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_qinq(&mut self, s_vid: u16, c_vid: u16) -> Result<(), JsValue> {
+        let frame = self.inner_frame.clone().with_qinq(s_vid, c_vid).map_err(JsValue::from_str)?;
+        self.inner_frame = frame;
+        Ok(())
+    }
+
+    /// イーサネットフレーム全体をバイト配列として取得
+    /// 
+    /// ### 戻り値
+    /// * `Uint8Array` - フレーム全体のバイトデータ
+    /// （宛先MAC + 送信元MAC + イーサタイプ + データ）
+    /// イーサタイプはビッグエンディアン（0x0806なら 08 06 の順）
+    #[wasm_bindgen]
+    pub fn to_bytes(&self) -> Uint8Array {
+        // バイト配列をJavaScript用のUint8Arrayに変換
+        Uint8Array::from(&self.inner_frame.to_bytes()[..])
+    }
+
+    /// イーサネットフレーム全体(to_bytesの結果)を16進ダンプの形式で取得
+    /// 
+    /// ### 戻り値
+    /// * `String` - 1行16バイトずつ、オフセット・16進数・ASCIIを並べた文字列
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// console.log(frame.to_hexdump());
+    /// // 00000000  FF FF FF FF FF FF 02 11  22 33 44 55 08 06 00 01  |........"3DU....|
+    /// ```
+    #[wasm_bindgen]
+    pub fn to_hexdump(&self) -> String {
+        hex_dump(&self.inner_frame.to_bytes()).replace("\n","\r\n")
+    }
+
+    /// イーサネットフレームをJavaScriptのオブジェクトとして取得
+    /// フィールド名はRustの構造体と同じ
+    /// 
+    /// ### 戻り値
+    /// * `Result<JsValue, JsValue>` - 成功時は { dst_mac: [255, 255, 255, 255, 255, 255], src_mac: [2, 17, 34, 51, 68, 85], outer_vlan: null, vlan: null, ethertype: 2054, data: [...] } のようなオブジェクト
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// localStorage.setItem("frame", JSON.stringify(frame.to_json()));
+    /// ```
+    #[wasm_bindgen]
+    pub fn to_json(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.inner_frame).map_err(|error| error.into())
+    }
+
+    /// to_jsonで取得したオブジェクトからイーサネットフレームを作成
+    /// 
+    /// ### 引数
+    /// * `value` - to_jsonで取得したものと同じ形のオブジェクト
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmEthernetFrame, JsValue>` - 成功時はWasmEthernetFrame、フィールドが足りないなど形が違う場合はエラー
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let frame = WasmEthernetFrame.from_json(JSON.parse(localStorage.getItem("frame")));
+    /// ```
+    #[wasm_bindgen]
+    pub fn from_json(value: JsValue) -> Result<WasmEthernetFrame, JsValue> {
+        let frame: EthernetFrame = serde_wasm_bindgen::from_value(value)?;
+        Ok(WasmEthernetFrame {
+            inner_frame: frame
+        })
+    }
+
+    /// 宛先MACアドレスを取得
+    /// 
+    /// ### 戻り値
+    /// * `WasmMacAddress` - 宛先MACアドレスのコピー
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// const dst = frame.dst_mac();
+    /// console.log(dst.to_string());
+    /// ```
+    #[wasm_bindgen]
+    pub fn dst_mac(&self) -> WasmMacAddress {
+        WasmMacAddress {
+            inner_mac: self.inner_frame.dst_mac
+        }
+    }
+
+    /// 送信元MACアドレスを取得
+    /// 
+    /// ### 戻り値
+    /// * `WasmMacAddress` - 送信元MACアドレスのコピー
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// const src = frame.src_mac();
+    /// console.log(src.to_string());
+    /// ```
+    #[wasm_bindgen]
+    pub fn src_mac(&self) -> WasmMacAddress {
+        WasmMacAddress {
+            inner_mac: self.inner_frame.src_mac
+        }
+    }
+
+    /// イーサタイプを取得
+    /// 
+    /// ### 戻り値
+    /// * `u16` - イーサタイプ (VLANタグが付いていても、タグの後ろの値)
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// if (frame.ethertype() === 0x0806) {
+    ///     // ARPとして処理する
+    /// }
+    /// ```
+    #[wasm_bindgen]
+    pub fn ethertype(&self) -> u16 {
+        self.inner_frame.ethertype
+    }
+
+    /// ペイロードを取得
+    /// 
+    /// ### 戻り値
+    /// * `Uint8Array` - ペイロードのコピー
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// const arp = WasmArpPacket.from_bytes(frame.payload());
+    /// ```
+    #[wasm_bindgen]
+    pub fn payload(&self) -> Uint8Array {
+        Uint8Array::from(&self.inner_frame.data[..])
+    }
+
+    /// FCS(Frame Check Sequence)を計算
+    /// 
+    /// ### 戻り値
+    /// * `u32` - 宛先MAC + 送信元MAC + イーサタイプ + データに対するIEEE 802.3のCRC-32
+    #[wasm_bindgen]
+    pub fn compute_fcs(&self) -> u32 {
+        self.inner_frame.compute_fcs()
+    }
+
+    /// FCSを末尾に付けたフレーム全体をバイト配列として取得
+    /// 
+    /// ### 戻り値
+    /// * `Uint8Array` - to_bytesの結果の後ろにFCS(ビッグエンディアン4バイト)を付けたもの
+    #[wasm_bindgen]
+    pub fn to_bytes_with_fcs(&self) -> Uint8Array {
+        Uint8Array::from(&self.inner_frame.to_bytes_with_fcs()[..])
+    }
+
+    /// 末尾4バイトのFCSが正しいかを確認
+    /// 
+    /// ### 引数
+    /// * `bytes` - FCS付きのフレームのバイト配列
+    /// 
+    /// ### 戻り値
+    /// * `bool` - FCSが一致すればtrue
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// const bytes = frame.to_bytes_with_fcs();
+    /// WasmEthernetFrame.verify_fcs(bytes); // true
+    /// ```
+    #[wasm_bindgen]
+    pub fn verify_fcs(bytes: &[u8]) -> bool {
+        EthernetFrame::verify_fcs(bytes)
+    }
+
+    /// 別のイーサネットフレームと値が等しいかを比較
+    /// ペイロードやVLANタグも含めて、すべてのフィールドを比較する
+    /// 
+    /// ### 引数
+    /// * `other` - 比較するイーサネットフレーム
+    /// 
+    /// ### 戻り値
+    /// * `bool` - 値が等しければtrue
+    #[wasm_bindgen]
+    pub fn equals(&self, other: &WasmEthernetFrame) -> bool {
+        self.inner_frame == other.inner_frame
+    }
+
+    /// イーサネットフレームを複製する
+    /// 中身ごとコピーするので、複製したフレームを書き換えても元のフレームは変わらない
+    /// 
+    /// ### 戻り値
+    /// * `WasmEthernetFrame` - 同じ内容の別のフレーム
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let snapshot = frame.clone_frame();
+    /// frame.pad_to_minimum();
+    /// console.log(snapshot.equals(frame)); // false
+    /// ```
+    #[wasm_bindgen]
+    pub fn clone_frame(&self) -> WasmEthernetFrame {
+        WasmEthernetFrame {
+            inner_frame: self.inner_frame.clone()
+        }
+    }
+}
+
+/// WebAssemblyから物理層フレームを扱うためのラッパー構造体
+/// inner_frame: 内部に保持する実際のPhysicalLayerFrameインスタンス
+#[wasm_bindgen]
+pub struct WasmPhysicalLayerFrame {
+    inner_frame: PhysicalLayerFrame
+}
+
+#[wasm_bindgen]
+impl WasmPhysicalLayerFrame {
+    /// 新しい物理層フレームを作成
+    /// 
+    /// ### 引数
+    /// * `ethernet_frame` - カプセル化するイーサネットフレーム
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let phyFrame = new WasmPhysicalLayerFrame(ethernetFrame);
+    /// ```
+    #[wasm_bindgen(constructor)]
+    pub fn new(ethernet_frame: &WasmEthernetFrame) -> Self {
+        // 新しいPhysicalLayerFrameインスタンスを作成
+        WasmPhysicalLayerFrame {
+            inner_frame: PhysicalLayerFrame::new(
+                Some(ethernet_frame.inner_frame.clone())  // イーサネットフレームをクローン
+            )
+        }
+    }
+
+    /// バイト配列から物理層フレームを生成
+    /// 
+    /// ### 引数
+    /// * `data` - プリアンブル + SFD + イーサネットフレーム (+ FCS) の順に並んだバイト配列
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmPhysicalLayerFrame, JsValue>` - 成功時はWasmPhysicalLayerFrame、失敗時はエラーメッセージ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let frame = WasmPhysicalLayerFrame.from_bytes(otherFrame.to_bytes());
+    /// ```
+    #[wasm_bindgen]
+    pub fn from_bytes(data: &[u8]) -> Result<WasmPhysicalLayerFrame, JsValue> {
+        match PhysicalLayerFrame::from_bytes(data) {
+            Ok(frame) => Ok(WasmPhysicalLayerFrame {
+                inner_frame: frame
+            }),
+            // 変換失敗時はエラーメッセージをJavaScript用の値に変換
+            Err(error_message) => Err(JsValue::from_str(error_message))
+        }
+    }
+
+    /// 物理層フレームを文字列形式で取得
+    /// 
+    /// ### 戻り値
+    /// * `String` - "#preamble,#sfd,#ethernet_frame" 形式の文字列
+    #[wasm_bindgen]
+    pub fn to_string(&self) -> String {
+        // 内部のEthernetFrameインスタンスの文字列表現を取得
+        self.inner_frame.to_string().replace("\n","\r\n")
+    }
+
+    /// フレームの合計長を取得（バイト単位）
+    /// プリアンブル、SFD、イーサネットフレーム、FCSを含む
+    /// 
+    /// ### 戻り値
+    /// * `usize` - フレームの総バイト長
+    #[wasm_bindgen]
+    pub fn total_length(&self) -> usize {
+        // 内部のPhysicalLayerFrameインスタンスの長さを取得
+        self.inner_frame.total_length()
+    }
+
+    /// FCSが正しいかを確認
+    /// ケーブルのビット誤りで壊れたフレームはfalseになる
+    /// 
+    /// ### 戻り値
+    /// * `bool` - FCSが一致すればtrue。ケーブルでビット誤りが起きていないフレームは常にtrue
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_ber(0.001);
+    /// cable.set_receive_callback("pc-2", (frame) => {
+    ///     if (!frame.fcs_valid()) console.log("corrupted frame");
+    /// });
+    /// ```
+    #[wasm_bindgen]
+    pub fn fcs_valid(&self) -> bool {
+        self.inner_frame.fcs_valid()
+    }
+
+    /// trace_idを取得
+    /// ルーターでフレームが作り直されても同じ値が引き継がれるので、複数のホップにまたがって1つのパケットを追いかけられる
+    /// 
+    /// ### 戻り値
+    /// * `u64` - trace_id。設定されていなければ0
+    #[wasm_bindgen]
+    pub fn get_trace_id(&self) -> u64 {
+        self.inner_frame.meta.trace_id
+    }
+
+    /// trace_idを設定する
+    /// バイト列には含まれないので、to_bytes()/from_bytes()では引き継がれない
+    /// 
+    /// ### 引数
+    /// * `trace_id` - 追いかけるパケットに付けるId
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// phyFrame.set_trace_id(42n);
+    /// cable.transmit("pc-1", phyFrame);
+    /// // ルーターの先で受け取ったフレームのget_trace_id()も42nになる
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_trace_id(&mut self, trace_id: u64) {
+        self.inner_frame.meta.trace_id = trace_id;
+    }
+
+    /// 物理層フレーム全体をバイト配列として取得
+    /// 
+    /// ### 戻り値
+    /// * `Uint8Array` - フレーム全体のバイトデータ
+    /// （プリアンブル + SFD + イーサネットフレーム + FCS）
+    #[wasm_bindgen]
+    pub fn to_bytes(&self) -> Uint8Array {
+        // 内部のPhysicalLayerFrameインスタンスからバイト配列を取得
+        let bytes = self.inner_frame.to_bytes();
+        // バイト配列をJavaScript用のUint8Arrayに変換
+        Uint8Array::from(&bytes[..])
+    }
+
+    /// 物理層フレーム全体(to_bytesの結果)を16進ダンプの形式で取得
+    /// 
+    /// ### 戻り値
+    /// * `String` - 1行16バイトずつ、オフセット・16進数・ASCIIを並べた文字列
+    #[wasm_bindgen]
+    pub fn to_hexdump(&self) -> String {
+        hex_dump(&self.inner_frame.to_bytes()).replace("\n","\r\n")
+    }
+
+    /// 物理層フレームをJavaScriptのオブジェクトとして取得
+    /// フィールド名はRustの構造体と同じ
+    /// 
+    /// ### 戻り値
+    /// * `Result<JsValue, JsValue>` - 成功時は { preamble: [170, ...], sfd: 171, ethernet_frame: { dst_mac: [...], ... } } のようなオブジェクト
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// localStorage.setItem("frame", JSON.stringify(frame.to_json()));
+    /// ```
+    #[wasm_bindgen]
+    pub fn to_json(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.inner_frame).map_err(|error| error.into())
+    }
+
+    /// to_jsonで取得したオブジェクトから物理層フレームを作成
+    /// 
+    /// ### 引数
+    /// * `value` - to_jsonで取得したものと同じ形のオブジェクト
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmPhysicalLayerFrame, JsValue>` - 成功時はWasmPhysicalLayerFrame、フィールドが足りないなど形が違う場合はエラー
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let frame = WasmPhysicalLayerFrame.from_json(JSON.parse(localStorage.getItem("frame")));
+    /// ```
+    #[wasm_bindgen]
+    pub fn from_json(value: JsValue) -> Result<WasmPhysicalLayerFrame, JsValue> {
+        let frame: PhysicalLayerFrame = serde_wasm_bindgen::from_value(value)?;
+        Ok(WasmPhysicalLayerFrame {
+            inner_frame: frame
+        })
+    }
+
+    /// 物理層フレームを複製する
+    /// 中身ごとコピーするので、複製したフレームを書き換えても元のフレームは変わらない
+    /// 
+    /// ### 戻り値
+    /// * `WasmPhysicalLayerFrame` - 同じ内容の別のフレーム
+    #[wasm_bindgen]
+    pub fn clone_frame(&self) -> WasmPhysicalLayerFrame {
+        WasmPhysicalLayerFrame {
+            inner_frame: self.inner_frame.clone()
+        }
+    }
+}
+
+/// wasm-bindgenの初期化関数
+/// このマクロは、WebAssemblyモジュールが読み込まれた時に自動的に実行される関数を指定します
+#[wasm_bindgen(start)]
+pub fn init() {
+    // Rustのパニック時のエラーメッセージをブラウザのコンソールに表示するように設定
+    // これにより、デバッグが容易になります
+    console_error_panic_hook::set_once();
+}
+
+/// インターネットチェックサム(RFC 1071)を計算する
+/// 
+/// ### 引数
+/// * `data` - チェックサムを計算するバイト配列
+/// 
+/// ### 戻り値
+/// * `u16` - 16ビットの1の補数和の1の補数
+/// 
+/// ### 使用例（JavaScript）:
+/// ```javascript
+/// let checksum = checksum_internet(new Uint8Array([0x00, 0x01, 0xF2, 0x03, 0xF4, 0xF5, 0xF6, 0xF7]));
+/// // => 0x220D
+/// ```
+#[wasm_bindgen]
+pub fn checksum_internet(data: &[u8]) -> u16 {
+    util::internet_checksum(data)
+}
+
+/// JS側にデバッグ表示のために用意された関数を呼び出す
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+extern "C" {
+    pub fn showTerminal(s: &str);
+}
+
+/// WebAssembly以外(ネイティブで動かすテストなど)ではJS側の関数がないので、標準エラー出力に表示する
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(non_snake_case)]
+pub fn showTerminal(s: &str) {
+    eprintln!("{}", s);
+}
+
+/// ブラウザのsetTimeoutを呼び出す
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &JsValue, timeout_ms: u32) -> JsValue;
+}
+
+/// 指定したミリ秒が経ってから処理を実行する
+/// WebAssemblyはブラウザのメインスレッドで動くので、ブロックせずにsetTimeoutで後から呼び出してもらう
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn run_after_ms(delay_ms: u32, task: impl FnOnce() + Send + 'static) {
+    let handler = Closure::once_into_js(task);
+    set_timeout(&handler, delay_ms);
+}
+
+/// 指定したミリ秒が経ってから処理を実行する
+/// WebAssembly以外ではsetTimeoutがないので、別のスレッドで待ってから呼び出す
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn run_after_ms(delay_ms: u32, task: impl FnOnce() + Send + 'static) {
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(delay_ms as u64));
+        task();
+    });
+}
+
+//////////////////////////////////////////////
+// JavaScriptの関数をRustのcallbackとして使うための仕組み
+//////////////////////////////////////////////
+
+/// JavaScriptの関数を保持するラッパー構造体
+/// PhysicalLayerCallbackなどのcallbackの型はSend + Syncを要求するが、js_sys::FunctionはSend/Syncではない
+/// WebAssemblyはシングルスレッドで動き、別のスレッドに渡されることはないのでSend/Syncとして扱う
+struct JsCallback(js_sys::Function);
+
+unsafe impl Send for JsCallback {}
+unsafe impl Sync for JsCallback {}
+
+impl JsCallback {
+    /// 引数1つでJavaScriptの関数を呼び出す。例外が起きた時はターミナルに表示する
+    fn call1(&self, arg: &JsValue) {
+        if let Err(error) = self.0.call1(&JsValue::NULL, arg) {
+            showTerminal(&format!("callbackの呼び出しでエラーが発生しました: {:?}", error));
+        }
+    }
+
+    /// 引数2つでJavaScriptの関数を呼び出す。例外が起きた時はターミナルに表示する
+    fn call2(&self, arg1: &JsValue, arg2: &JsValue) {
+        if let Err(error) = self.0.call2(&JsValue::NULL, arg1, arg2) {
+            showTerminal(&format!("callbackの呼び出しでエラーが発生しました: {:?}", error));
+        }
+    }
+}
+
+/// JavaScriptの関数を、届いたフレームをUint8Array(物理層フレームのバイト列)にして渡すPhysicalLayerCallbackに変換する
+fn frame_callback_from_js(callback: js_sys::Function) -> PhysicalLayerCallback {
+    let callback = JsCallback(callback);
+    Arc::new(move |frame: PhysicalLayerFrame| {
+        let bytes = Uint8Array::from(&frame.to_bytes()[..]);
+        callback.call1(&bytes.into());
+    })
+}
+
+/// JavaScriptの関数を、タップに流れたフレームをUint8Array(物理層フレームのバイト列)と向きの文字列にして渡すTapCallbackに変換する
+/// 向きは"ep1_to_ep2"か"ep2_to_ep1"(ループバックの時は送った端から始まる方)
+fn tap_callback_from_js(callback: js_sys::Function) -> TapCallback {
+    let callback = JsCallback(callback);
+    Arc::new(move |frame: PhysicalLayerFrame, from: Endpoint| {
+        let bytes = Uint8Array::from(&frame.to_bytes()[..]);
+        let direction = match from {
+            Endpoint::One => "ep1_to_ep2",
+            Endpoint::Two => "ep2_to_ep1",
+        };
+        callback.call2(&bytes.into(), &JsValue::from_str(direction));
+    })
+}
+
+
+//////////////////////////////////////////////
+// イーサネットケーブルのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからイーサネットケーブルを扱うためのラッパー構造体
+/// inner_cable: 内部に保持する実際のEthernetCableインスタンス
+#[wasm_bindgen]
+pub struct WasmEthernetCable {
+    inner_cable: Option<EthernetCable>,
+}
+
+#[wasm_bindgen]
+impl WasmEthernetCable {
+    /// 新しいイーサネットケーブルを作成
+    /// 
+    /// ### 引数
+    /// * `id` - ケーブルのId（タグのようなものケーブルを識別できるように。なくても良い）
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let cable = new WasmEthernetCable(id);
+    /// ```
+    /// 
+    #[wasm_bindgen(constructor)]
+    pub fn new(id:Option<String>) -> Self {
+        // 新しいEthernetCableインスタンスを作成
+        WasmEthernetCable {
+            inner_cable: Some(EthernetCable::new(id))
+        }
+    }
+    /// イーサーネットケーブルを削除する
+    /// Noneを指定することで明示的に削除することになる
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.remove();
+    /// ```
+    #[wasm_bindgen]
+    pub fn remove(&mut self) {
+        // Optionを使って、内部のケーブルリソースを明示的に破棄
+        self.inner_cable = None;
+    }
+    /// イーサーネットケーブルが有効かどうかをチェック
+    /// 
+    /// ### 戻り値
+    /// * `bool` - ケーブルが有効かどうか。
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// if(cable.is_valid()){...有効な時の処理...}else{...無効な時の処理...}
+    /// ```
+    /// 
+    #[wasm_bindgen]
+    pub fn is_valid(&self) -> bool {
+        self.inner_cable.is_some()
+    }
+    /// 同じケーブルを指す別のハンドルを作成
+    /// フレームと違って中身はコピーせず、どちらのハンドルからの操作も同じケーブルの状態（接続、統計情報など）に反映される
+    /// remove()はそのハンドルを無効にするだけで、もう一方のハンドルは引き続き使える
+    /// 
+    /// ### 戻り値
+    /// * `WasmEthernetCable` - 同じケーブルを指すハンドル。このハンドルが無効なら、返すハンドルも無効
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let handle = cable.clone_cable();
+    /// handle.connect_endpoint1("pc-1");
+    /// console.log(cable.get_endpoint1_component_id()); // "pc-1"
+    /// ```
+    /// 
+    #[wasm_bindgen]
+    pub fn clone_cable(&self) -> WasmEthernetCable {
+        WasmEthernetCable {
+            inner_cable: self.inner_cable.clone()
+        }
+    }
+    /// そのイーサネットケーブルのIdを取得
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let id = cable.getId();
+    /// ```
+    /// 
+    #[wasm_bindgen]
+    pub fn get_id(&self) -> String {
+         self.inner_cable.as_ref().map(|cable| cable.get_id()).unwrap_or_default()
+    }
+
+    /// イーサーネットケーブルの内容表示
+    /// 
+    /// ### 戻り値
+    /// * `String` - ケーブルの情報を表す文字列
+    #[wasm_bindgen]
+    pub fn to_string(&self) -> String {
+        // 内部のEthernetFrameインスタンスの文字列表現を取得
+        self.inner_cable.as_ref().map(|cable| cable.to_string().replace("\n","\r\n")).unwrap_or_default()
+    }
+   
+    /// イーサネットケーブルをつなげる
+    /// 
+    /// ### 引数
+    /// * `ep1_connect_id` - 端1に繋げるコンポーネントのId
+    /// * `ep2_connect_id` - 端2に繋げるコンポーネントのId
+    ///
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - 無効なケーブルの場合や、両端に同じIdを指定した場合はエラーで、つながりは変わらない
+    ///   （自分に折り返したい時はset_loopbackを使う）
+    ///
+    #[wasm_bindgen]
+    pub fn connect(&self, ep1_connect_id: Option<String>, ep2_connect_id: Option<String>) -> Result<(), JsValue> {
+        let cable = self.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        cable.connect(ep1_connect_id, ep2_connect_id).map_err(JsValue::from_str)
+    }
+    /// endpoint1の方にイーサネットケーブルをつなげる
+    /// 
+    /// ### 引数
+    /// * `ep1_coonect_id` - 端1に繋げるコンポーネントId
+    /// ケーブルの片方を別のポートに差し替えたり、別のコンポーネントに繋げ直すような時
+    /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - 無効なケーブルの場合や、endpoint2と同じIdを指定した場合はエラー
+    /// 
+    #[wasm_bindgen]
+    pub fn connect_endpoint1(&self, ep1_connect_id: Option<String>) -> Result<(), JsValue> {
+        let cable = self.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        cable.connect_endpoint1(ep1_connect_id).map_err(JsValue::from_str)
+    }
+    /// endpoint1に繋がっているコンポーネントのIdを取得
+    /// 
+    /// ### 戻り値
+    /// * `Option<String>` - "endpoint1に繋がっているコンポーネントがあったらそのコンポーネントのIdが返される
+    /// 
+    #[wasm_bindgen]
+    pub fn get_endpoint1_component_id(&self) -> Option<String> {
+        //self.inner_cable.as_ref().?.get_endpoint1_component_id()
+        self.inner_cable.as_ref().and_then(|cable| {
+            cable.get_endpoint1_component_id()
+        }).or_else(|| {
+            showTerminal("このケーブルは無効です。");
+            None
+        })
+    }
+
+    /// endpoint2の方にイーサネットケーブルをつなげる
+    /// 
+    /// ### 引数
+    /// * `ep2_coonect_id` - 端1に繋げるコンポーネントId
+    /// ケーブルの片方を別のポートに差し替えたり、別のコンポーネントに繋げ直すような時
+    /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - 無効なケーブルの場合や、endpoint1と同じIdを指定した場合はエラー
+    /// 
+    #[wasm_bindgen]
+    pub fn connect_endpoint2(&self, ep2_connect_id: Option<String>) -> Result<(), JsValue> {
+        let cable = self.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        cable.connect_endpoint2(ep2_connect_id).map_err(JsValue::from_str)
+    }
+    /// endpoint2に繋がっているコンポーネントのIdを取得
+    /// 
+    /// ### 戻り値
+    /// * `Option<String>` - "endpoint2に繋がっているコンポーネントがあったらそのコンポーネントのIdが返される
+    /// 
+    #[wasm_bindgen]
+    pub fn get_endpoint2_component_id(&self) -> Option<String> {
+        self.inner_cable.as_ref()?.get_endpoint2_component_id()
+    }
+
+    /// 両端につながっているコンポーネントのIdと、両端がつながっているかどうかをまとめて取得
+    /// 
+    /// ### 戻り値
+    /// * `JsValue` - `{ endpoint1, endpoint2, connected }` の形のオブジェクト。つながっていない端はundefined、ケーブルが無効な場合はnull
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// const info = cable.get_connection_info();
+    /// if (info !== null && info.connected) {
+    ///     console.log(`${info.endpoint1} <-> ${info.endpoint2}`);
+    /// }
+    /// ```
+    #[wasm_bindgen]
+    pub fn get_connection_info(&self) -> JsValue {
+        self.inner_cable.as_ref()
+            .and_then(|cable| serde_wasm_bindgen::to_value(&cable.get_connection_info()).ok())
+            .unwrap_or(JsValue::NULL)
+    }
+
+    /// 指定したコンポーネントがどちらの端につながっているかを取得
+    ///
+    /// ### 引数
+    /// * `component_id` - 調べるコンポーネントのId
+    ///
+    /// ### 戻り値
+    /// * `Option<u8>` - endpoint1なら1、endpoint2なら2。どちらにもつながっていないか、両端が同じIdの場合と、無効なケーブルの場合はundefined
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.connect("pc-1", "pc-2");
+    /// cable.which_endpoint("pc-2"); // 2
+    /// ```
+    #[wasm_bindgen]
+    pub fn which_endpoint(&self, component_id: &str) -> Option<u8> {
+        let endpoint = self.inner_cable.as_ref()?.which_endpoint(component_id)?;
+        Some(match endpoint {
+            Endpoint::One => 1,
+            Endpoint::Two => 2,
+        })
+    }
+
+    /// endpoint1からコンポーネントを外す（ケーブルを抜く）
+    /// コンポーネントのIdとcallbackが消え、connectedがfalseになるので、もう一度つなぐまでフレームは送れない
+    #[wasm_bindgen]
+    pub fn disconnect_endpoint1(&self) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.disconnect_endpoint1();
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// endpoint2からコンポーネントを外す（ケーブルを抜く）
+    /// コンポーネントのIdとcallbackが消え、connectedがfalseになるので、もう一度つなぐまでフレームは送れない
+    #[wasm_bindgen]
+    pub fn disconnect_endpoint2(&self) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.disconnect_endpoint2();
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// 届いたフレームを受け取るJavaScriptの関数を、指定したコンポーネントがつながっている端に登録する
+    /// フレームはWasmPhysicalLayerFrameとしてcallbackに渡される
+    /// 
+    /// ※ component_idがconnect()などで先にケーブルの端につながっている必要がある
+    ///   また、callbackが呼ばれるのは両端がつながって(connected)、相手側からフレームが送られてきた時だけ
+    ///
+    /// ### 引数
+    /// * `component_id` - フレームを受け取るコンポーネントのId
+    /// * `callback` - WasmPhysicalLayerFrameを受け取るJavaScriptの関数
+    ///
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - 無効なケーブルの場合や、component_idがどちらの端にもつながっていない場合はエラー
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.connect("pc-1", "pc-2");
+    /// cable.set_receive_callback("pc-2", (frame) => { console.log(frame.to_string()); });
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_receive_callback(&self, component_id: String, callback: js_sys::Function) -> Result<(), JsValue> {
+        let cable = self.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        let callback = JsCallback(callback);
+        let receive: PhysicalLayerCallback = Arc::new(move |frame: PhysicalLayerFrame| {
+            let wasm_frame = WasmPhysicalLayerFrame { inner_frame: frame };
+            callback.call1(&wasm_frame.into());
+        });
+        cable.set_callback(component_id, receive).map_err(JsValue::from_str)
+    }
+
+    /// イーサネットケーブルにフレームを流す
+    /// from_idがつながっている端から、反対側の端のコンポーネントにフレームが届けられる
+    ///
+    /// ### 引数
+    /// * `from_id` - 送信元のコンポーネントId
+    /// * `frame` - 送信する物理層フレーム
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.connect("pc-1", "pc-2");
+    /// cable.set_receive_callback("pc-2", (frame) => { console.log(frame.to_string()); });
+    /// cable.transmit("pc-1", phyFrame);
+    /// ```
+    #[wasm_bindgen]
+    pub fn transmit(&self, from_id: String, frame: &WasmPhysicalLayerFrame) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.transmit_signal(from_id, frame.inner_frame.clone());
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// 複数のフレームをまとめてイーサネットケーブルに流す
+    /// 1つずつtransmitを呼ぶよりJavaScriptとの行き来が少なくて済むので、キャプチャしたフレームを再生する時などに使う
+    /// 帯域幅が設定されていれば、フレーム間ギャップを空けて順番に届く
+    ///
+    /// ### 引数
+    /// * `from_id` - 送信元のコンポーネントId
+    /// * `frames` - WasmPhysicalLayerFrame.to_jsonで取得したものと同じ形のオブジェクトの配列
+    ///
+    /// ### 戻り値
+    /// * `Result<usize, JsValue>` - 送信したフレームの数。無効なケーブルの場合や、形が違うオブジェクトが含まれる場合はエラーで、1つも送信しない
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.transmit_burst("pc-1", [frame1.to_json(), frame2.to_json(), frame3.to_json()]); // 3
+    /// ```
+    #[wasm_bindgen]
+    pub fn transmit_burst(&self, from_id: String, frames: Vec<JsValue>) -> Result<usize, JsValue> {
+        let cable = self.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        let frames = frames
+            .into_iter()
+            .map(serde_wasm_bindgen::from_value)
+            .collect::<Result<Vec<PhysicalLayerFrame>, _>>()?;
+        Ok(cable.transmit_burst(from_id, frames))
+    }
+
+    /// 指定した時刻にフレームを送信するよう予約する
+    ///
+    /// ### 引数
+    /// * `from_id` - 送信元のコンポーネントId
+    /// * `frame` - 送信する物理層フレーム
+    /// * `at_ms` - 送信する時刻(ミリ秒)。tick()でこの時刻まで進んだ時に送信される
+    ///
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - 無効なケーブルの場合や、at_msがNaN/無限大の場合はエラー
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.schedule_transmit("pc-1", phyFrame, 100);
+    /// cable.schedule_transmit("pc-1", phyFrame, 200);
+    /// cable.tick(150); // 100msに予約したフレームだけが送信される
+    /// ```
+    #[wasm_bindgen]
+    pub fn schedule_transmit(&self, from_id: String, frame: &WasmPhysicalLayerFrame, at_ms: f64) -> Result<(), JsValue> {
+        let cable = self.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        cable.schedule_transmit(from_id, frame.inner_frame.clone(), at_ms).map_err(JsValue::from_str)
+    }
+
+    /// シミュレーションの時刻を進め、予約時刻になったフレームを送信する
+    ///
+    /// ### 引数
+    /// * `now_ms` - 現在のシミュレーション時刻(ミリ秒)
+    ///
+    #[wasm_bindgen]
+    pub fn tick(&self, now_ms: f64) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.tick(now_ms);
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// 送信待ちになっているフレームの数を取得
+    ///
+    /// ### 戻り値
+    /// * `usize` - まだ送信されていない予約済みフレームの数（無効なケーブルの場合は0）
+    #[wasm_bindgen]
+    pub fn get_scheduled_count(&self) -> usize {
+        self.inner_cable.as_ref().map(|cable| cable.get_scheduled_count()).unwrap_or_default()
+    }
+
+    /// 両端がつながってからリンクアップするまでの時間(ミリ秒)を設定する
+    /// リンクアップするまで(tick()でその時間が経過するまで)に送信されたフレームは破棄される
+    ///
+    /// ### 引数
+    /// * `ms` - リンクアップまでの時間(ミリ秒)
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_link_up_delay_ms(2000);
+    /// cable.connect("pc-1", "pc-2");
+    /// cable.tick(2000); // ここでリンクアップする
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_link_up_delay_ms(&self, ms: u32) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.set_link_up_delay_ms(ms);
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// リンクアップして実際に通信できる状態かどうか
+    ///
+    /// ### 戻り値
+    /// * `bool` - リンクアップしていればtrue（無効なケーブルの場合はfalse）
+    #[wasm_bindgen]
+    pub fn is_link_up(&self) -> bool {
+        self.inner_cable.as_ref().map(|cable| cable.is_link_up()).unwrap_or_default()
+    }
+
+    /// フレームが失われる確率を設定する
+    /// 物理的な媒体の不安定さを再現するためのもの
+    ///
+    /// ### 引数
+    /// * `rate` - フレームが失われる確率(0.0〜1.0)。範囲外の値は0.0〜1.0に丸められる
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_loss_rate(0.1); // 10%のフレームが失われる
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_loss_rate(&self, rate: f64) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.set_loss_rate(rate);
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// ビット誤り率を設定する
+    /// 1ビットごとにこの確率で反転してから相手に届く。壊れたフレームはFCSが合わなくなる
+    /// 
+    /// ### 引数
+    /// * `rate` - 0.0〜1.0のビット誤り率（範囲外の値は0.0〜1.0に丸める）
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_ber(0.001); // 1000ビットに1ビットくらい反転する
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_ber(&self, rate: f64) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.set_ber(rate);
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// ループバックモードを設定する
+    /// 有効にすると、送ったフレームが相手ではなく送信元に折り返す（片方の端だけつないでいても折り返す）
+    /// 
+    /// ### 引数
+    /// * `on` - trueで有効、falseで無効
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.connect_endpoint1("host-1");
+    /// cable.set_receive_callback("host-1", (frame) => { console.log("自分が送ったフレーム", frame.to_string()); });
+    /// cable.set_loopback(true);
+    /// cable.transmit("host-1", frame);
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_loopback(&self, on: bool) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.set_loopback(on);
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// 半二重モードを設定する
+    /// 有効にすると、送ったフレームが届く前に別のフレームが送られた時に両方が捨てられ、衝突として数えられる
+    /// 衝突を起こすには、set_lengthで伝搬遅延が1ミリ秒以上になる長さにしておく必要がある
+    /// 
+    /// ### 引数
+    /// * `on` - trueで半二重、falseで全二重
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_half_duplex(true);
+    /// cable.set_length(1000000);
+    /// cable.transmit("host-1", frame1);
+    /// cable.transmit("host-2", frame2);
+    /// console.log(cable.get_stats().collisions); // 1
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_half_duplex(&self, on: bool) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.set_half_duplex(on);
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// MTU(送れるペイロードの最大バイト数)を設定する。初期値は1500
+    /// ペイロードがMTUより大きいフレームは破棄され、get_stats()のframes_oversizedに数えられる
+    /// 
+    /// ### 引数
+    /// * `mtu` - MTU(バイト)
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_mtu(9000); // ジャンボフレームを通す
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_mtu(&self, mtu: usize) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.set_mtu(mtu);
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// ケーブルの長さ(メートル)を設定する
+    /// 長さに比例した伝搬遅延(1メートルあたり約5ナノ秒)の後にフレームが届くようになる
+    /// 遅延はミリ秒に丸めてsetTimeoutで待つので、1ミリ秒に満たない場合はすぐに届く
+    ///
+    /// ### 引数
+    /// * `meters` - ケーブルの長さ(メートル)
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_length(100);
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_length(&self, meters: f64) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.set_length(meters);
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// ケーブルの長さから計算した伝搬遅延(ナノ秒)を取得
+    ///
+    /// ### 戻り値
+    /// * `Result<f64, JsValue>` - 伝搬遅延(ナノ秒)。無効なケーブルの場合はエラー
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_length(100);
+    /// console.log(cable.get_propagation_delay_ns()); // 500
+    /// ```
+    #[wasm_bindgen]
+    pub fn get_propagation_delay_ns(&self) -> Result<f64, JsValue> {
+        let cable = self.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        Ok(cable.propagation_delay_ns())
+    }
+
+    /// ケーブルの帯域幅(ビット/秒)を設定する
+    /// フレームのビット数を帯域幅で割った送出遅延が伝搬遅延に加わり、その後にフレームが届くようになる
+    ///
+    /// ### 引数
+    /// * `bps` - 帯域幅(ビット/秒)。0なら制限なし(送出遅延なし)
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_bandwidth(10_000_000); // 10Mbps。1514バイトのフレームは約1.2ミリ秒かかる
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_bandwidth(&self, bps: u64) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.set_bandwidth(bps);
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// 最後に送ったフレームが届くまでにかかった時間を取得
+    ///
+    /// ### 戻り値
+    /// * `Result<f64, JsValue>` - 送出遅延と伝搬遅延を足した時間(ミリ秒)。無効なケーブルの場合はエラー
+    #[wasm_bindgen]
+    pub fn last_transmit_delay_ms(&self) -> Result<f64, JsValue> {
+        let cable = self.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        Ok(cable.last_transmit_delay_ms())
+    }
+
+    /// フレーム間ギャップが終わるのを待っているフレームの数を取得
+    /// 帯域幅が設定されている時、前のフレームの送出と96ビット分のフレーム間ギャップが終わる前に送ったフレームは待たされる
+    ///
+    /// ### 戻り値
+    /// * `Result<usize, JsValue>` - 待っているフレームの数。無効なケーブルの場合はエラー
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_bandwidth(10_000_000);
+    /// nic.send(frame1);
+    /// nic.send(frame2);
+    /// console.log(cable.get_queue_depth()); // 1
+    /// ```
+    #[wasm_bindgen]
+    pub fn get_queue_depth(&self) -> Result<usize, JsValue> {
+        let cable = self.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        Ok(cable.get_queue_depth())
+    }
+
+    /// ケーブルの統計情報を取得
+    ///
+    /// ### 戻り値
+    /// * `Result<JsValue, JsValue>` - 次の形のオブジェクト（無効なケーブルの場合はエラー）
+    ///   `{ ep1_to_ep2: { frames, bytes }, ep2_to_ep1: { frames, bytes }, frames_dropped, frames_corrupted, collisions, frames_oversized }`
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// const stats = cable.get_stats();
+    /// console.log(stats.ep1_to_ep2.frames, stats.frames_dropped);
+    /// ```
+    #[wasm_bindgen]
+    pub fn get_stats(&self) -> Result<JsValue, JsValue> {
+        let cable = self.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        serde_wasm_bindgen::to_value(&cable.get_stats()).map_err(|error| error.into())
+    }
+
+    /// ケーブルの統計情報を0に戻す
+    #[wasm_bindgen]
+    pub fn reset_stats(&self) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.reset_stats();
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// 両端がつながって通信できる状態(connected)が変わった時に呼ばれるcallbackを設定する
+    ///
+    /// ### 引数
+    /// * `callback` - 新しいconnectedの値(boolean)を受け取るJavaScriptの関数
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_connection_callback((connected) => { linkLamp.classList.toggle("on", connected); });
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_connection_callback(&self, callback: js_sys::Function) {
+        self.inner_cable.as_ref().map(|cable| {
+            let callback = JsCallback(callback);
+            cable.set_connection_callback(Arc::new(move |connected: bool| {
+                callback.call1(&JsValue::from_bool(connected));
+            }));
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// リンクアップやフレームの破棄などのイベントを受け取るcallbackを設定する
+    ///
+    /// ### 引数
+    /// * `callback` - イベントの名前("Connected"または"Dropped")と、破棄された理由("LinkNotReady"、"Lost"など。Droppedの時だけ)を受け取るJavaScriptの関数
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_event_callback((event, reason) => {
+    ///     if (event === "Connected") { linkLamp.classList.add("up"); }
+    ///     if (event === "Dropped") { console.log(`dropped: ${reason}`); }
+    /// });
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_event_callback(&self, callback: js_sys::Function) {
+        self.inner_cable.as_ref().map(|cable| {
+            let callback = JsCallback(callback);
+            cable.set_event_callback(Arc::new(move |event: CableEvent| {
+                match event {
+                    CableEvent::Connected => callback.call2(&JsValue::from_str("Connected"), &JsValue::UNDEFINED),
+                    CableEvent::Dropped(reason) => callback.call2(&JsValue::from_str("Dropped"), &JsValue::from_str(&format!("{:?}", reason))),
+                }
+            }));
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// キャプチャを始める
+    /// これ以降ケーブルに流れたフレームを、最新のmax個まで記録する
+    /// 
+    /// ### 引数
+    /// * `max` - 記録しておくフレームの最大数。超えたら古いものから捨てる
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.start_capture(1000);
+    /// ```
+    #[wasm_bindgen]
+    pub fn start_capture(&self, max: usize) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.start_capture(max);
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// キャプチャで記録したフレームを取り出し、pcap形式のバイト列にする
+    /// 取り出したフレームは記録から消えるが、キャプチャは続く
+    /// フレームを送った時刻は記録していないので、タイムスタンプには何番目のフレームかをマイクロ秒として書き込む
+    /// 
+    /// ### 戻り値
+    /// * `Result<Uint8Array, JsValue>` - pcapファイルの中身。ケーブルが無効な場合はエラー
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// const blob = new Blob([cable.take_capture_pcap()], { type: "application/vnd.tcpdump.pcap" });
+    /// ```
+    #[wasm_bindgen]
+    pub fn take_capture_pcap(&self) -> Result<Uint8Array, JsValue> {
+        let cable = self.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        let mut writer = PcapWriter::new();
+        for (index, frame) in cable.take_capture().iter().enumerate() {
+            writer.write_physical_frame(index as u32 / 1_000_000, index as u32 % 1_000_000, frame);
+        }
+        Ok(Uint8Array::from(&writer.finish()[..]))
+    }
+
+    /// タップを設定する
+    /// ケーブルの端につながずに、両方向に流れる全てのフレームのコピーをcallbackで受け取る（Wiresharkのような観察用）
+    /// callbackは相手に届けるより先に呼ばれ、loss_rateで失われるフレームも渡される
+    ///
+    /// ### 引数
+    /// * `callback` - フレームのバイト列(Uint8Array)と、流れた向き("ep1_to_ep2"か"ep2_to_ep1")を受け取るJavaScriptの関数
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_tap((bytes, direction) => { captured.push({ bytes, direction }); });
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_tap(&self, callback: js_sys::Function) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.set_tap(tap_callback_from_js(callback));
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// 条件に合うフレームだけを受け取るタップを設定する
+    /// 条件はRust側で調べるので、条件に合わないフレームのたびにJavaScriptの関数が呼ばれることはない
+    /// すでに設定されているタップは置き換えられる
+    ///
+    /// ### 引数
+    /// * `ethertype` - 受け取るフレームのイーサタイプ。undefinedなら見ない
+    /// * `dst_mac` - 受け取るフレームの宛先MACアドレス。undefinedなら見ない
+    /// * `callback` - フレームのバイト列(Uint8Array)と、流れた向き("ep1_to_ep2"か"ep2_to_ep1")を受け取るJavaScriptの関数
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_tap_filter(0x0806, undefined, (bytes, direction) => { arpFrames.push(bytes); }); // ARPだけ
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_tap_filter(&self, ethertype: Option<u16>, dst_mac: Option<WasmMacAddress>, callback: js_sys::Function) {
+        self.inner_cable.as_ref().map(|cable| {
+            let filter = TapFilter { ethertype, dst_mac: dst_mac.map(|mac| mac.inner_mac) };
+            cable.set_filtered_tap(filter, tap_callback_from_js(callback));
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// タップを外す
+    #[wasm_bindgen]
+    pub fn clear_tap(&self) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.clear_tap();
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// SPAN(ミラー)ポートを設定する
+    /// 両端の間で届けられたフレームは、どちらの向きのものも全てコピーがcallbackに渡される
+    /// 本来の送り先に届けた後で渡されるので、本来の通信には影響しない
+    ///
+    /// ### 引数
+    /// * `component_id` - SPANポートにつなぐコンポーネントのId（Wiresharkホストなど）
+    /// * `callback` - フレームのバイト列(Uint8Array)を受け取るJavaScriptの関数
+    ///
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// cable.set_mirror("wireshark-host", (bytes) => { console.log(bytes); });
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_mirror(&self, component_id: String, callback: js_sys::Function) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.set_mirror_to(component_id, frame_callback_from_js(callback));
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// SPAN(ミラー)ポートを外す
+    #[wasm_bindgen]
+    pub fn clear_mirror(&self) {
+        self.inner_cable.as_ref().map(|cable| {
+            cable.clear_mirror();
+        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    }
+
+    /// SPAN(ミラー)ポートに繋がっているコンポーネントのIdを取得
+    ///
+    /// ### 戻り値
+    /// * `Option<String>` - SPANポートが設定されていればそのコンポーネントのId
+    #[wasm_bindgen]
+    pub fn get_mirror_component_id(&self) -> Option<String> {
+        self.inner_cable.as_ref()?.get_mirror_component_id()
+    }
+    // /// いらなくなったケーブルを削除
+    // /// 
+    // #[wasm_bindgen]
+    // pub fn drop(&mut self) {
+    //     // Arc<Mutex>の参照カウントを減らし、リソースを解放するので、直接、inner_cable値を取得したいのでtake()している
+    //     if let Some(cable) = self.inner_cable.take() {
+    //         drop(cable); // 所有権を取得してリソースを解放
+    //     }else{
+    //         showTerminal("このケーブルは無効ですのでdropできません。")
+    //     }
+    // }
+}
+
+
+//////////////////////////////////////////////
+// ハブのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからハブを扱うためのラッパー構造体
+/// inner_hub: 内部に保持する実際のHubインスタンス
+#[wasm_bindgen]
+pub struct WasmHub {
+    inner_hub: Hub,
+}
+
+#[wasm_bindgen]
+impl WasmHub {
+    /// 新しいハブを作成
+    /// 
+    /// ### 引数
+    /// * `id` - ハブのId（なくても良い）
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let hub = new WasmHub("hub-1");
+    /// ```
+    #[wasm_bindgen(constructor)]
+    pub fn new(id: Option<String>) -> Self {
+        WasmHub {
+            inner_hub: Hub::new(id)
+        }
+    }
+
+    /// そのハブのIdを取得
+    #[wasm_bindgen]
+    pub fn get_id(&self) -> String {
+        self.inner_hub.get_id()
+    }
+
+    /// ハブの内容表示
+    /// 
+    /// ### 戻り値
+    /// * `String` - ハブの情報を表す文字列
+    #[wasm_bindgen]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.inner_hub.to_string().replace("\n","\r\n")
+    }
+
+    /// ポートの数を取得
+    #[wasm_bindgen]
+    pub fn get_port_count(&self) -> usize {
+        self.inner_hub.get_port_count()
+    }
+
+    /// ケーブルをハブにつなぐ
+    /// ハブのIdでケーブルの空いている端につなぎ、届いたフレームを他の全てのポートに送り出すようにする
+    /// 
+    /// ### 引数
+    /// * `cable` - つなぐイーサネットケーブル
+    /// 
+    /// ### 戻り値
+    /// * `Result<usize, JsValue>` - つないだポートの番号。ケーブルが無効か、両端とも使われている場合はエラー
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let hub = new WasmHub("hub-1");
+    /// let cable = new WasmEthernetCable("cable-1");
+    /// cable.connect_endpoint2("pc-1");
+    /// let port = hub.connect_cable(cable);
+    /// ```
+    #[wasm_bindgen]
+    pub fn connect_cable(&self, cable: &WasmEthernetCable) -> Result<usize, JsValue> {
+        let cable = cable.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        self.inner_hub
+            .add_port(cable.clone())
+            .ok_or_else(|| JsValue::from_str("ケーブルの両端が既に使われています。"))
+    }
+
+    /// ポートを外す
+    /// ケーブルの端からもハブを外すので、そのケーブルは他のコンポーネントにつなげるようになる
+    /// 
+    /// ### 引数
+    /// * `port` - 外すポートの番号
+    /// 
+    /// ### 戻り値
+    /// * `bool` - 指定したポートがあればtrue
+    #[wasm_bindgen]
+    pub fn remove_port(&self, port: usize) -> bool {
+        self.inner_hub.remove_port(port)
+    }
+}
+
+
+//////////////////////////////////////////////
+// スイッチのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからスイッチを扱うためのラッパー構造体
+/// inner_switch: 内部に保持する実際のSwitchインスタンス
+#[wasm_bindgen]
+pub struct WasmSwitch {
+    inner_switch: Switch,
+}
+
+#[wasm_bindgen]
+impl WasmSwitch {
+    /// 新しいスイッチを作成
+    /// 
+    /// ### 引数
+    /// * `id` - スイッチのId（なくても良い）
+    /// * `port_count` - ポートの数
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let sw = new WasmSwitch("switch-1", 8);
+    /// ```
+    #[wasm_bindgen(constructor)]
+    pub fn new(id: Option<String>, port_count: usize) -> Self {
+        WasmSwitch {
+            inner_switch: Switch::new(id, port_count)
+        }
+    }
+
+    /// そのスイッチのIdを取得
+    #[wasm_bindgen]
+    pub fn get_id(&self) -> String {
+        self.inner_switch.get_id()
+    }
+
+    /// スイッチの内容表示
+    /// 
+    /// ### 戻り値
+    /// * `String` - ポートとMACアドレステーブルを表す文字列
+    #[wasm_bindgen]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.inner_switch.to_string().replace("\n","\r\n")
+    }
+
+    /// ポートの数を取得
+    #[wasm_bindgen]
+    pub fn get_port_count(&self) -> usize {
+        self.inner_switch.get_port_count()
+    }
+
+    /// 指定したポートにケーブルをつなぐ
+    /// スイッチのIdでケーブルの空いている端につなぎ、届いたフレームを転送するようにする
+    /// 
+    /// ### 引数
+    /// * `port` - つなぐポートの番号（0から始まる）
+    /// * `cable` - つなぐイーサネットケーブル
+    /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - ポート番号が範囲外か使用中、ケーブルが無効か両端とも使われている場合はエラー
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let cable = new WasmEthernetCable("cable-1");
+    /// cable.connect_endpoint2("pc-1");
+    /// sw.connect_cable(0, cable);
+    /// ```
+    #[wasm_bindgen]
+    pub fn connect_cable(&self, port: usize, cable: &WasmEthernetCable) -> Result<(), JsValue> {
+        let cable = cable.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        self.inner_switch.connect_cable(port, cable.clone()).map_err(JsValue::from_str)
+    }
+
+    /// MACアドレステーブルのエージングタイム(秒)を設定する
+    /// 初期値は300秒。0にするとテーブルから消さなくなる
+    /// 
+    /// ### 引数
+    /// * `secs` - この秒数の間フレームが届かなかったMACアドレスはage_entries()でテーブルから消される
+    #[wasm_bindgen]
+    pub fn set_aging_time(&self, secs: u32) {
+        self.inner_switch.set_aging_time(secs);
+    }
+
+    /// ストームコントロールの上限を設定する
+    /// 1つのポートに1秒間にpps個を超えて届いたブロードキャスト/マルチキャストは破棄される。ユニキャストは制限されない
+    /// 
+    /// ### 引数
+    /// * `pps` - 1秒間に1つのポートが受け取るブロードキャスト/マルチキャストの上限。0にすると制限をなくす
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// sw.set_broadcast_rate_limit(100);
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_broadcast_rate_limit(&self, pps: u32) {
+        self.inner_switch.set_broadcast_rate_limit(pps);
+    }
+
+    /// スイッチで起きたイベントを受け取る関数を設定する
+    /// 関数には、イベントの名前と、そのイベントが起きたポートの番号が渡される
+    /// 
+    /// ### 引数
+    /// * `callback` - (event: string, port: number) => void
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// sw.set_event_callback((event, port) => {
+    ///     if (event === "StormControlDrop") { console.log(`storm on port ${port}`); }
+    /// });
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_event_callback(&self, callback: js_sys::Function) {
+        let callback = JsCallback(callback);
+        self.inner_switch.set_event_callback(Arc::new(move |event: SwitchEvent| {
+            match event {
+                SwitchEvent::StormControlDrop { port } => callback.call2(&JsValue::from_str("StormControlDrop"), &JsValue::from_f64(port as f64)),
+            }
+        }));
+    }
+
+    /// エージングタイムより長くフレームが届いていないMACアドレスをテーブルから消す
+    /// 
+    /// ### 戻り値
+    /// * `usize` - 消したMACアドレスの数
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// setInterval(() => sw.age_entries(), 1000);
+    /// ```
+    #[wasm_bindgen]
+    pub fn age_entries(&self) -> usize {
+        self.inner_switch.age_entries()
+    }
+
+    /// 学習したMACアドレステーブルを取得
+    /// 
+    /// ### 戻り値
+    /// * `Result<JsValue, JsValue>` - `[{ mac: "00:11:22:33:44:55", port: 0 }, ...]` の形の配列
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// for (const entry of sw.get_mac_table()) {
+    ///     console.log(entry.mac, entry.port);
+    /// }
+    /// ```
+    #[wasm_bindgen]
+    pub fn get_mac_table(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.inner_switch.get_mac_table()).map_err(|error| error.into())
+    }
+}
+
+
+//////////////////////////////////////////////
+// ARPパケットのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからARPパケットを扱うためのラッパー構造体
+/// inner_packet: 内部に保持する実際のArpPacketインスタンス
+#[wasm_bindgen]
+pub struct WasmArpPacket {
+    inner_packet: ArpPacket,
+}
+
+#[wasm_bindgen]
+impl WasmArpPacket {
+    /// target_ipのMACアドレスを問い合わせるARPリクエストを作成
+    /// 
+    /// ### 引数
+    /// * `sender_mac` - 自分のMACアドレス
+    /// * `sender_ip` - 自分のIPv4アドレス
+    /// * `target_ip` - MACアドレスを知りたい相手のIPv4アドレス
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let arp = WasmArpPacket.request(myMac, myIp, WasmIPv4Address.from_string("192.168.0.1"));
+    /// ```
+    #[wasm_bindgen]
+    pub fn request(sender_mac: &WasmMacAddress, sender_ip: &WasmIPv4Address, target_ip: &WasmIPv4Address) -> WasmArpPacket {
+        WasmArpPacket {
+            inner_packet: ArpPacket::request(sender_mac.inner_mac, sender_ip.inner_ip, target_ip.inner_ip)
+        }
+    }
+
+    /// ARPリクエストに答えるARPリプライを作成
+    /// 
+    /// ### 引数
+    /// * `sender_mac` - 問い合わせられた自分のMACアドレス
+    /// * `sender_ip` - 問い合わせられた自分のIPv4アドレス
+    /// * `target_mac` - 問い合わせてきた相手のMACアドレス
+    /// * `target_ip` - 問い合わせてきた相手のIPv4アドレス
+    #[wasm_bindgen]
+    pub fn reply(sender_mac: &WasmMacAddress, sender_ip: &WasmIPv4Address, target_mac: &WasmMacAddress, target_ip: &WasmIPv4Address) -> WasmArpPacket {
+        WasmArpPacket {
+            inner_packet: ArpPacket::reply(sender_mac.inner_mac, sender_ip.inner_ip, target_mac.inner_mac, target_ip.inner_ip)
+        }
+    }
+
+    /// Gratuitous ARPを作成
+    /// 送信元と問い合わせ先のIPv4アドレスが同じARPリクエストで、自分のアドレスを周りに知らせたり、重複を確かめたりするのに使う
+    /// 
+    /// ### 引数
+    /// * `mac` - 自分のMACアドレス
+    /// * `ip` - 知らせる自分のIPv4アドレス
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let garp = WasmArpPacket.gratuitous(myMac, myIp);
+    /// ```
+    #[wasm_bindgen]
+    pub fn gratuitous(mac: &WasmMacAddress, ip: &WasmIPv4Address) -> WasmArpPacket {
+        WasmArpPacket {
+            inner_packet: ArpPacket::gratuitous(mac.inner_mac, ip.inner_ip)
+        }
+    }
+
+    /// バイト配列からARPパケットを作成
+    /// 
+    /// ### 引数
+    /// * `data` - ARPパケットのバイト配列（イーサネットフレームのペイロード）
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmArpPacket, JsValue>` - 成功時はWasmArpPacket、失敗時はエラーメッセージ
+    #[wasm_bindgen]
+    pub fn from_bytes(data: &[u8]) -> Result<WasmArpPacket, JsValue> {
+        match ArpPacket::from_bytes(data) {
+            Ok(packet) => Ok(WasmArpPacket {
+                inner_packet: packet
+            }),
+            // 変換失敗時はエラーメッセージをJavaScript用の値に変換
+            Err(error_message) => Err(JsValue::from_str(error_message))
+        }
+    }
+
+    /// ARPパケットを文字列形式で取得
+    /// 
+    /// ### 戻り値
+    /// * `String` - 各フィールドを表す文字列
+    #[wasm_bindgen]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.inner_packet.to_string().replace("\n","\r\n")
+    }
+
+    /// ARPパケットをバイト配列として取得
+    /// 
+    /// ### 戻り値
+    /// * `Uint8Array` - 28バイトのARPパケット（2バイトのフィールドはビッグエンディアン）
+    #[wasm_bindgen]
+    pub fn to_bytes(&self) -> Uint8Array {
+        Uint8Array::from(&self.inner_packet.to_bytes()[..])
+    }
+
+    /// このARPパケットを運ぶイーサネットフレームを作成
+    /// イーサタイプは0x0806で、リクエストはブロードキャスト、リプライは問い合わせてきた相手に送る
+    /// 
+    /// ### 戻り値
+    /// * `WasmEthernetFrame` - ARPパケットをペイロードにしたイーサネットフレーム
+    #[wasm_bindgen]
+    pub fn to_ethernet_frame(&self) -> WasmEthernetFrame {
+        WasmEthernetFrame {
+            inner_frame: self.inner_packet.to_ethernet_frame()
+        }
+    }
+}
+
+
+//////////////////////////////////////////////
+// ARPキャッシュのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからARPキャッシュを扱うためのラッパー構造体
+/// inner_cache: 内部に保持する実際のArpCacheインスタンス
+#[wasm_bindgen]
+pub struct WasmArpCache {
+    inner_cache: ArpCache,
+}
+
+impl Default for WasmArpCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl WasmArpCache {
+    /// 空のARPキャッシュを作成
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let cache = new WasmArpCache();
+    /// ```
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        WasmArpCache {
+            inner_cache: ArpCache::new()
+        }
+    }
+
+    /// IPv4アドレスとMACアドレスの対応を登録する
+    /// 既に登録されている場合は、MACアドレスと時刻を更新する
+    /// 
+    /// ### 引数
+    /// * `ip` - IPv4アドレス
+    /// * `mac` - そのIPv4アドレスを持つ機器のMACアドレス
+    #[wasm_bindgen]
+    pub fn insert(&mut self, ip: &WasmIPv4Address, mac: &WasmMacAddress) {
+        self.inner_cache.insert(ip.inner_ip, mac.inner_mac);
+    }
+
+    /// Gratuitous ARPを受け取った時に呼び、送信元の対応を登録・更新する
+    /// 
+    /// ### 引数
+    /// * `packet` - 受け取ったGratuitous ARP
+    /// 
+    /// ### 戻り値
+    /// * `bool` - そのIPv4アドレスが別のMACアドレスで登録されていた（アドレスが重複している）場合はtrue
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// if (cache.handle_gratuitous(garp)) { console.log("IPアドレスが重複しています"); }
+    /// ```
+    #[wasm_bindgen]
+    pub fn handle_gratuitous(&mut self, packet: &WasmArpPacket) -> bool {
+        self.inner_cache.handle_gratuitous(&packet.inner_packet)
+    }
+
+    /// IPv4アドレスに対応するMACアドレスを調べる
+    /// 
+    /// ### 引数
+    /// * `ip` - 調べるIPv4アドレス
+    /// 
+    /// ### 戻り値
+    /// * `Option<WasmMacAddress>` - 登録されていればMACアドレス、なければundefined
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// const mac = cache.lookup(ip);
+    /// if (mac === undefined) { /* ARPリクエストを送る */ }
+    /// ```
+    #[wasm_bindgen]
+    pub fn lookup(&self, ip: &WasmIPv4Address) -> Option<WasmMacAddress> {
+        self.inner_cache.lookup(&ip.inner_ip).map(|mac| WasmMacAddress { inner_mac: mac })
+    }
+
+    /// 登録・更新からttl_secs秒以上経った対応を消す
+    /// 
+    /// ### 引数
+    /// * `ttl_secs` - 対応を覚えておく秒数
+    /// 
+    /// ### 戻り値
+    /// * `usize` - 消した対応の数
+    #[wasm_bindgen]
+    pub fn purge_expired(&mut self, ttl_secs: u32) -> usize {
+        self.inner_cache.purge_expired(ttl_secs)
+    }
+
+    /// 登録されている対応をJSON文字列で取得
+    /// 
+    /// ### 戻り値
+    /// * `Result<String, JsValue>` - `[{"ip":"192.168.0.1","mac":"00:11:22:33:44:55","updated_ms":...}, ...]` の形のJSON文字列
+    #[wasm_bindgen]
+    pub fn entries_json(&self) -> Result<String, JsValue> {
+        let entries = serde_wasm_bindgen::to_value(&self.inner_cache.get_entries())?;
+        let json = js_sys::JSON::stringify(&entries)?;
+        Ok(json.into())
+    }
+}
+
+
+//////////////////////////////////////////////
+// IPv4パケットのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからIPv4パケットを扱うためのラッパー構造体
+/// inner_packet: 内部に保持する実際のIPv4Packetインスタンス
+#[wasm_bindgen]
+pub struct WasmIPv4Packet {
+    inner_packet: IPv4Packet,
+}
+
+#[wasm_bindgen]
+impl WasmIPv4Packet {
+    /// 新しいIPv4パケットを作成
+    /// オプションなし、TTLは64で、ヘッダ長/全体長/チェックサムは自動で計算される
+    /// 
+    /// ### 引数
+    /// * `src_ip` - 送信元IPv4アドレス
+    /// * `dst_ip` - 宛先IPv4アドレス
+    /// * `protocol` - 上位のプロトコル (ICMPは1、TCPは6、UDPは17)
+    /// * `payload` - ペイロードのバイト配列
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let packet = new WasmIPv4Packet(srcIp, dstIp, 1, icmp.to_bytes());
+    /// let frame = new WasmEthernetFrame(dstMac, srcMac, 0x0800, packet.to_bytes());
+    /// ```
+    #[wasm_bindgen(constructor)]
+    pub fn new(src_ip: &WasmIPv4Address, dst_ip: &WasmIPv4Address, protocol: u8, payload: &[u8]) -> Self {
+        WasmIPv4Packet {
+            inner_packet: IPv4Packet::new(src_ip.inner_ip, dst_ip.inner_ip, protocol, payload.to_vec())
+        }
+    }
+
+    /// バイト配列からIPv4パケットを作成
+    /// 
+    /// ### 引数
+    /// * `data` - IPv4パケットのバイト配列（イーサネットフレームのペイロード）
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmIPv4Packet, JsValue>` - 成功時はWasmIPv4Packet、失敗時はエラーメッセージ
+    #[wasm_bindgen]
+    pub fn from_bytes(data: &[u8]) -> Result<WasmIPv4Packet, JsValue> {
+        match IPv4Packet::from_bytes(data) {
+            Ok(packet) => Ok(WasmIPv4Packet {
+                inner_packet: packet
+            }),
+            // 変換失敗時はエラーメッセージをJavaScript用の値に変換
+            Err(error_message) => Err(JsValue::from_str(error_message))
+        }
+    }
+
+    /// IPv4パケットを文字列形式で取得
+    /// 
+    /// ### 戻り値
+    /// * `String` - ヘッダの各フィールドとペイロードを表す文字列
+    #[wasm_bindgen]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.inner_packet.to_string().replace("\n","\r\n")
+    }
+
+    /// IPv4パケットをバイト配列として取得
+    /// 
+    /// ### 戻り値
+    /// * `Uint8Array` - ヘッダ + ペイロードのバイトデータ
+    #[wasm_bindgen]
+    pub fn to_bytes(&self) -> Uint8Array {
+        Uint8Array::from(&self.inner_packet.to_bytes()[..])
+    }
+
+    /// ヘッダチェックサムを計算
+    /// 
+    /// ### 戻り値
+    /// * `u16` - チェックサムのフィールドを0にしたヘッダに対するインターネットチェックサム
+    #[wasm_bindgen]
+    pub fn compute_checksum(&self) -> u16 {
+        self.inner_packet.compute_checksum()
+    }
+
+    /// ルーターがパケットを次のホップに送る時の処理をする
+    /// TTLを1減らし、チェックサムを計算し直す
+    /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - TTLが0になった場合はエラー（ICMPの時間超過を送り返すきっかけになる）
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// try {
+    ///     packet.forward();
+    /// } catch (e) {
+    ///     // TTLが0になったのでICMP Time Exceededを送る
+    /// }
+    /// ```
+    #[wasm_bindgen]
+    pub fn forward(&mut self) -> Result<(), JsValue> {
+        self.inner_packet.forward().map_err(JsValue::from_str)
+    }
+
+    /// TTLを取得
+    #[wasm_bindgen]
+    pub fn get_ttl(&self) -> u8 {
+        self.inner_packet.ttl
+    }
+}
+
+
+//////////////////////////////////////////////
+// ICMPメッセージのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからICMPメッセージを扱うためのラッパー構造体
+/// inner_message: 内部に保持する実際のIcmpMessageインスタンス
+#[wasm_bindgen]
+pub struct WasmIcmpMessage {
+    inner_message: IcmpMessage,
+}
+
+#[wasm_bindgen]
+impl WasmIcmpMessage {
+    /// ping(エコー要求)を作成
+    /// 
+    /// ### 引数
+    /// * `identifier` - 識別子（pingのプロセスごとに変える）
+    /// * `sequence` - シーケンス番号（1回送るごとに1増やす）
+    /// * `payload` - データのバイト配列
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let ping = WasmIcmpMessage.echo_request(1, 1, new Uint8Array([0x61, 0x62, 0x63]));
+    /// let packet = new WasmIPv4Packet(srcIp, dstIp, 1, ping.to_bytes());
+    /// ```
+    #[wasm_bindgen]
+    pub fn echo_request(identifier: u16, sequence: u16, payload: &[u8]) -> WasmIcmpMessage {
+        WasmIcmpMessage {
+            inner_message: IcmpMessage::echo_request(identifier, sequence, payload.to_vec())
+        }
+    }
+
+    /// pingへの応答(エコー応答)を作成
+    /// 
+    /// ### 引数
+    /// * `identifier` - エコー要求の識別子
+    /// * `sequence` - エコー要求のシーケンス番号
+    /// * `payload` - エコー要求のデータ
+    #[wasm_bindgen]
+    pub fn echo_reply(identifier: u16, sequence: u16, payload: &[u8]) -> WasmIcmpMessage {
+        WasmIcmpMessage {
+            inner_message: IcmpMessage::echo_reply(identifier, sequence, payload.to_vec())
+        }
+    }
+
+    /// バイト配列からICMPメッセージを作成
+    /// 
+    /// ### 引数
+    /// * `data` - ICMPメッセージのバイト配列（IPv4パケットのペイロード）
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmIcmpMessage, JsValue>` - 成功時はWasmIcmpMessage、失敗時はエラーメッセージ
+    #[wasm_bindgen]
+    pub fn from_bytes(data: &[u8]) -> Result<WasmIcmpMessage, JsValue> {
+        match IcmpMessage::from_bytes(data) {
+            Ok(message) => Ok(WasmIcmpMessage {
+                inner_message: message
+            }),
+            // 変換失敗時はエラーメッセージをJavaScript用の値に変換
+            Err(error_message) => Err(JsValue::from_str(error_message))
+        }
+    }
+
+    /// ICMPメッセージを文字列形式で取得
+    /// 
+    /// ### 戻り値
+    /// * `String` - 各フィールドを表す文字列
+    #[wasm_bindgen]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.inner_message.to_string().replace("\n","\r\n")
+    }
+
+    /// ICMPメッセージをバイト配列として取得
+    /// 
+    /// ### 戻り値
+    /// * `Uint8Array` - ヘッダ + データのバイトデータ
+    #[wasm_bindgen]
+    pub fn to_bytes(&self) -> Uint8Array {
+        Uint8Array::from(&self.inner_message.to_bytes()[..])
+    }
+
+    /// エコー要求/応答の識別子を取得
+    #[wasm_bindgen]
+    pub fn get_identifier(&self) -> u16 {
+        self.inner_message.identifier()
+    }
+
+    /// エコー要求/応答のシーケンス番号を取得
+    #[wasm_bindgen]
+    pub fn get_sequence(&self) -> u16 {
+        self.inner_message.sequence()
+    }
+
+    /// チェックサムを計算
+    /// 
+    /// ### 戻り値
+    /// * `u16` - チェックサムのフィールドを0にしたICMPメッセージ全体に対するインターネットチェックサム
+    #[wasm_bindgen]
+    pub fn compute_checksum(&self) -> u16 {
+        self.inner_message.compute_checksum()
+    }
+}
+
+
+//////////////////////////////////////////////
+// ルーターのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからルーターを扱うためのラッパー構造体
+/// inner_router: 内部に保持する実際のRouterインスタンス
+#[wasm_bindgen]
+pub struct WasmRouter {
+    inner_router: Router,
+}
+
+#[wasm_bindgen]
+impl WasmRouter {
+    /// 新しいルーターを作成
+    /// 
+    /// ### 引数
+    /// * `id` - ルーターのId（なくても良い）
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let router = new WasmRouter("router-1");
+    /// ```
+    #[wasm_bindgen(constructor)]
+    pub fn new(id: Option<String>) -> Self {
+        WasmRouter {
+            inner_router: Router::new(id)
+        }
+    }
+
+    /// そのルーターのIdを取得
+    #[wasm_bindgen]
+    pub fn get_id(&self) -> String {
+        self.inner_router.get_id()
+    }
+
+    /// ルーターの内容表示
+    /// 
+    /// ### 戻り値
+    /// * `String` - インターフェースとルーティングテーブルを表す文字列
+    #[wasm_bindgen]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.inner_router.to_string().replace("\n","\r\n")
+    }
+
+    /// インターフェースを追加する
+    /// インターフェースのサブネットへは、ルートを追加しなくても直接届けられる
+    /// 
+    /// ### 引数
+    /// * `ip` - インターフェースのIPv4アドレス
+    /// * `mask` - サブネットマスク
+    /// 
+    /// ### 戻り値
+    /// * `usize` - 追加したインターフェースの番号
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// const lan = router.add_interface(WasmIPv4Address.from_string("192.168.1.1"), WasmIPv4Address.from_string("255.255.255.0"));
+    /// ```
+    #[wasm_bindgen]
+    pub fn add_interface(&self, ip: &WasmIPv4Address, mask: &WasmIPv4Address) -> usize {
+        self.inner_router.add_interface(ip.inner_ip, mask.inner_ip)
+    }
+
+    /// インターフェースのMACアドレスを取得
+    /// 
+    /// ### 引数
+    /// * `iface` - インターフェースの番号
+    /// 
+    /// ### 戻り値
+    /// * `Option<WasmMacAddress>` - インターフェースがなければundefined
+    #[wasm_bindgen]
+    pub fn get_interface_mac(&self, iface: usize) -> Option<WasmMacAddress> {
+        self.inner_router.get_interface_mac(iface).map(|mac| WasmMacAddress { inner_mac: mac })
+    }
+
+    /// ルートを追加する
+    /// 
+    /// ### 引数
+    /// * `prefix` - 宛先ネットワークのアドレス
+    /// * `mask` - 宛先ネットワークのサブネットマスク
+    /// * `next_hop` - 次に渡すルーターのIPv4アドレス。0.0.0.0の場合は宛先に直接届ける
+    /// * `iface` - 送り出すインターフェースの番号
+    /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - インターフェースの番号が範囲外の場合はエラー
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// // デフォルトルート
+    /// router.add_route(WasmIPv4Address.from_string("0.0.0.0"), WasmIPv4Address.from_string("0.0.0.0"),
+    ///                  WasmIPv4Address.from_string("10.0.0.254"), wan);
+    /// ```
+    #[wasm_bindgen]
+    pub fn add_route(&self, prefix: &WasmIPv4Address, mask: &WasmIPv4Address, next_hop: &WasmIPv4Address, iface: usize) -> Result<(), JsValue> {
+        let next_hop = if next_hop.inner_ip == IPv4Address([0; 4]) { None } else { Some(next_hop.inner_ip) };
+        self.inner_router.add_route(prefix.inner_ip, mask.inner_ip, next_hop, iface).map_err(JsValue::from_str)
+    }
+
+    /// 指定したインターフェースにケーブルをつなぐ
+    /// 
+    /// ### 引数
+    /// * `iface` - インターフェースの番号
+    /// * `cable` - つなぐイーサネットケーブル
+    /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - インターフェースの番号が範囲外か使用中、ケーブルが無効か両端とも使われている場合はエラー
+    #[wasm_bindgen]
+    pub fn connect_cable(&self, iface: usize, cable: &WasmEthernetCable) -> Result<(), JsValue> {
+        let cable = cable.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        self.inner_router.connect_cable(iface, cable.clone()).map_err(JsValue::from_str)
+    }
+}
+
+
+//////////////////////////////////////////////
+// UDPデータグラムのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからUDPデータグラムを扱うためのラッパー構造体
+/// inner_datagram: 内部に保持する実際のUdpDatagramインスタンス
+#[wasm_bindgen]
+pub struct WasmUdpDatagram {
+    inner_datagram: UdpDatagram,
+}
+
+#[wasm_bindgen]
+impl WasmUdpDatagram {
+    /// 新しいUDPデータグラムを作成
+    /// lengthは自動で計算され、チェックサムは0(計算していない)になる
+    /// 
+    /// ### 引数
+    /// * `src_port` - 送信元ポート番号
+    /// * `dst_port` - 宛先ポート番号
+    /// * `payload` - データのバイト配列
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let datagram = new WasmUdpDatagram(50000, 53, query);
+    /// let packet = new WasmIPv4Packet(srcIp, dstIp, 17, datagram.to_bytes());
+    /// ```
+    #[wasm_bindgen(constructor)]
+    pub fn new(src_port: u16, dst_port: u16, payload: &[u8]) -> Self {
+        WasmUdpDatagram {
+            inner_datagram: UdpDatagram::new(src_port, dst_port, payload.to_vec())
+        }
+    }
+
+    /// バイト配列からUDPデータグラムを生成
+    /// lengthフィールドより後ろに余ったバイト(イーサネットのパディングなど)は無視する
+    /// 
+    /// ### 引数
+    /// * `data` - UDPデータグラムのバイト配列（IPv4パケットのペイロード）
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmUdpDatagram, JsValue>` - 成功時はWasmUdpDatagram、失敗時はエラーメッセージ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let datagram = WasmUdpDatagram.from_bytes(received.to_bytes());
+    /// ```
+    #[wasm_bindgen]
+    pub fn from_bytes(data: &[u8]) -> Result<WasmUdpDatagram, JsValue> {
+        match UdpDatagram::from_bytes(data) {
+            Ok(datagram) => Ok(WasmUdpDatagram {
+                inner_datagram: datagram
+            }),
+            // 変換失敗時はエラーメッセージをJavaScript用の値に変換
+            Err(error_message) => Err(JsValue::from_str(error_message))
+        }
+    }
+
+    /// UDPデータグラムを文字列形式で取得
+    /// 
+    /// ### 戻り値
+    /// * `String` - 各フィールドを表す文字列
+    #[wasm_bindgen]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.inner_datagram.to_string().replace("\n","\r\n")
+    }
+
+    /// UDPデータグラムをバイト配列として取得
+    /// 
+    /// ### 戻り値
+    /// * `Uint8Array` - ヘッダ + データのバイトデータ
+    #[wasm_bindgen]
+    pub fn to_bytes(&self) -> Uint8Array {
+        Uint8Array::from(&self.inner_datagram.to_bytes()[..])
+    }
+
+    /// 疑似ヘッダを含めたチェックサムを計算し、チェックサムのフィールドに設定
+    /// 
+    /// ### 引数
+    /// * `src_ip` - このデータグラムを運ぶIPv4パケットの送信元アドレス
+    /// * `dst_ip` - このデータグラムを運ぶIPv4パケットの宛先アドレス
+    /// 
+    /// ### 戻り値
+    /// * `u16` - 計算したチェックサム
+    #[wasm_bindgen]
+    pub fn update_checksum(&mut self, src_ip: &WasmIPv4Address, dst_ip: &WasmIPv4Address) -> u16 {
+        self.inner_datagram.checksum = self.inner_datagram.compute_checksum(src_ip.inner_ip, dst_ip.inner_ip);
+        self.inner_datagram.checksum
+    }
+}
+
+
+//////////////////////////////////////////////
+// TCPセグメントのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからTCPセグメントを扱うためのラッパー構造体
+/// inner_segment: 内部に保持する実際のTcpSegmentインスタンス
+#[wasm_bindgen]
+pub struct WasmTcpSegment {
+    inner_segment: TcpSegment,
+}
+
+#[wasm_bindgen]
+impl WasmTcpSegment {
+    /// 新しいTCPセグメントを作成
+    /// フラグはすべて0なので、set_synなどで設定する
+    /// 
+    /// ### 引数
+    /// * `src_port` - 送信元ポート番号
+    /// * `dst_port` - 宛先ポート番号
+    /// * `seq` - シーケンス番号
+    /// * `ack` - 確認応答番号
+    /// * `payload` - データのバイト配列
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let syn = new WasmTcpSegment(50000, 80, 1000, 0, new Uint8Array([]));
+    /// syn.set_syn(true);
+    /// syn.update_checksum(srcIp, dstIp);
+    /// let packet = new WasmIPv4Packet(srcIp, dstIp, 6, syn.to_bytes());
+    /// ```
+    #[wasm_bindgen(constructor)]
+    pub fn new(src_port: u16, dst_port: u16, seq: u32, ack: u32, payload: &[u8]) -> Self {
+        WasmTcpSegment {
+            inner_segment: TcpSegment::new(src_port, dst_port, seq, ack, TcpFlags::default(), payload.to_vec())
+        }
+    }
+
+    /// バイト配列からTCPセグメントを作成
+    /// 
+    /// ### 引数
+    /// * `data` - TCPセグメントのバイト配列（IPv4パケットのペイロード）
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmTcpSegment, JsValue>` - 成功時はWasmTcpSegment、失敗時はエラーメッセージ
+    #[wasm_bindgen]
+    pub fn from_bytes(data: &[u8]) -> Result<WasmTcpSegment, JsValue> {
+        match TcpSegment::from_bytes(data) {
+            Ok(segment) => Ok(WasmTcpSegment {
+                inner_segment: segment
+            }),
+            // 変換失敗時はエラーメッセージをJavaScript用の値に変換
+            Err(error_message) => Err(JsValue::from_str(error_message))
+        }
+    }
+
+    /// TCPセグメントを文字列形式で取得
+    /// 
+    /// ### 戻り値
+    /// * `String` - 各フィールドを表す文字列
+    #[wasm_bindgen]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.inner_segment.to_string().replace("\n","\r\n")
+    }
+
+    /// TCPセグメントをバイト配列として取得
+    /// 
+    /// ### 戻り値
+    /// * `Uint8Array` - ヘッダ + データのバイトデータ
+    #[wasm_bindgen]
+    pub fn to_bytes(&self) -> Uint8Array {
+        Uint8Array::from(&self.inner_segment.to_bytes()[..])
+    }
+
+    /// 疑似ヘッダを含めたチェックサムを計算し、チェックサムのフィールドに設定
+    /// 
+    /// ### 引数
+    /// * `src_ip` - このセグメントを運ぶIPv4パケットの送信元アドレス
+    /// * `dst_ip` - このセグメントを運ぶIPv4パケットの宛先アドレス
+    /// 
+    /// ### 戻り値
+    /// * `u16` - 計算したチェックサム
+    #[wasm_bindgen]
+    pub fn update_checksum(&mut self, src_ip: &WasmIPv4Address, dst_ip: &WasmIPv4Address) -> u16 {
+        self.inner_segment.checksum = self.inner_segment.compute_checksum(src_ip.inner_ip, dst_ip.inner_ip);
+        self.inner_segment.checksum
+    }
+
+    /// SYN(接続の開始)フラグが立っているか
+    #[wasm_bindgen]
+    pub fn get_syn(&self) -> bool {
+        self.inner_segment.flags.syn()
+    }
+
+    /// SYN(接続の開始)フラグを設定
+    #[wasm_bindgen]
+    pub fn set_syn(&mut self, value: bool) {
+        self.inner_segment.flags.set_syn(value)
+    }
+
+    /// ACK(確認応答番号が有効)フラグが立っているか
+    #[wasm_bindgen]
+    pub fn get_ack(&self) -> bool {
+        self.inner_segment.flags.ack()
+    }
+
+    /// ACK(確認応答番号が有効)フラグを設定
+    #[wasm_bindgen]
+    pub fn set_ack(&mut self, value: bool) {
+        self.inner_segment.flags.set_ack(value)
+    }
+
+    /// FIN(接続の終了)フラグが立っているか
+    #[wasm_bindgen]
+    pub fn get_fin(&self) -> bool {
+        self.inner_segment.flags.fin()
+    }
+
+    /// FIN(接続の終了)フラグを設定
+    #[wasm_bindgen]
+    pub fn set_fin(&mut self, value: bool) {
+        self.inner_segment.flags.set_fin(value)
+    }
+
+    /// RST(接続のリセット)フラグが立っているか
+    #[wasm_bindgen]
+    pub fn get_rst(&self) -> bool {
+        self.inner_segment.flags.rst()
+    }
+
+    /// RST(接続のリセット)フラグを設定
+    #[wasm_bindgen]
+    pub fn set_rst(&mut self, value: bool) {
+        self.inner_segment.flags.set_rst(value)
+    }
+
+    /// PSH(すぐにアプリケーションに渡す)フラグが立っているか
+    #[wasm_bindgen]
+    pub fn get_psh(&self) -> bool {
+        self.inner_segment.flags.psh()
+    }
+
+    /// PSH(すぐにアプリケーションに渡す)フラグを設定
+    #[wasm_bindgen]
+    pub fn set_psh(&mut self, value: bool) {
+        self.inner_segment.flags.set_psh(value)
+    }
+
+    /// URG(緊急ポインタが有効)フラグが立っているか
+    #[wasm_bindgen]
+    pub fn get_urg(&self) -> bool {
+        self.inner_segment.flags.urg()
+    }
+
+    /// URG(緊急ポインタが有効)フラグを設定
+    #[wasm_bindgen]
+    pub fn set_urg(&mut self, value: bool) {
+        self.inner_segment.flags.set_urg(value)
+    }
+}
+
+
+//////////////////////////////////////////////
+// pcap書き出しのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからpcap形式のファイルを作るためのラッパー構造体
+/// inner_writer: 内部に保持する実際のPcapWriterインスタンス
+#[wasm_bindgen]
+pub struct WasmPcapWriter {
+    inner_writer: PcapWriter,
+}
+
+impl Default for WasmPcapWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl WasmPcapWriter {
+    /// 新しいpcapライターを作成
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let writer = new WasmPcapWriter();
+    /// writer.write_frame(0, 0, frame);
+    /// const blob = new Blob([writer.finish()], { type: "application/vnd.tcpdump.pcap" });
+    /// ```
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        WasmPcapWriter {
+            inner_writer: PcapWriter::new()
+        }
+    }
+
+    /// イーサネットフレームを1つ書き込む
+    /// 
+    /// ### 引数
+    /// * `ts_secs` - キャプチャした時刻(秒)
+    /// * `ts_usecs` - キャプチャした時刻の秒未満の部分(マイクロ秒)
+    /// * `frame` - 書き込むイーサネットフレーム
+    #[wasm_bindgen]
+    pub fn write_frame(&mut self, ts_secs: u32, ts_usecs: u32, frame: &WasmEthernetFrame) {
+        self.inner_writer.write_frame(ts_secs, ts_usecs, &frame.inner_frame)
+    }
+
+    /// 物理層フレームを1つ書き込む（プリアンブル/SFDは含めない）
+    /// 
+    /// ### 引数
+    /// * `ts_secs` - キャプチャした時刻(秒)
+    /// * `ts_usecs` - キャプチャした時刻の秒未満の部分(マイクロ秒)
+    /// * `frame` - 書き込む物理層フレーム
+    #[wasm_bindgen]
+    pub fn write_physical_frame(&mut self, ts_secs: u32, ts_usecs: u32, frame: &WasmPhysicalLayerFrame) {
+        self.inner_writer.write_physical_frame(ts_secs, ts_usecs, &frame.inner_frame)
+    }
+
+    /// 書き込んだフレームの数を取得
+    #[wasm_bindgen]
+    pub fn get_frame_count(&self) -> usize {
+        self.inner_writer.frame_count()
+    }
+
+    /// pcapファイルの内容を取得
+    /// 取得した後もフレームを書き込み続けられる
+    /// 
+    /// ### 戻り値
+    /// * `Uint8Array` - グローバルヘッダ + 書き込んだすべてのフレーム
+    #[wasm_bindgen]
+    pub fn finish(&self) -> Uint8Array {
+        Uint8Array::from(self.inner_writer.as_bytes())
+    }
+}
+
+
+//////////////////////////////////////////////
+// NICのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからNIC(ネットワークインターフェースカード)を扱うためのラッパー構造体
+/// inner_nic: 内部に保持する実際のNetworkInterfaceインスタンス
+#[wasm_bindgen]
+pub struct WasmNetworkInterface {
+    inner_nic: NetworkInterface,
+}
+
+#[wasm_bindgen]
+impl WasmNetworkInterface {
+    /// 新しいNICを作成
+    /// 
+    /// ### 引数
+    /// * `id` - NICのId（なくても良い）
+    /// * `mac` - NICのMACアドレス
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let nic = new WasmNetworkInterface("pc-1", new WasmMacAddress());
+    /// ```
+    #[wasm_bindgen(constructor)]
+    pub fn new(id: Option<String>, mac: &WasmMacAddress) -> Self {
+        WasmNetworkInterface {
+            inner_nic: NetworkInterface::new(id, mac.inner_mac)
+        }
+    }
+
+    /// そのNICのIdを取得
+    #[wasm_bindgen]
+    pub fn get_id(&self) -> String {
+        self.inner_nic.get_id()
+    }
+
+    /// そのNICのMACアドレスを取得
+    #[wasm_bindgen]
+    pub fn get_mac(&self) -> WasmMacAddress {
+        WasmMacAddress { inner_mac: self.inner_nic.get_mac() }
+    }
+
+    /// NICの内容表示
+    /// 
+    /// ### 戻り値
+    /// * `String` - MACアドレスとつながっているケーブルを表す文字列
+    #[wasm_bindgen]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.inner_nic.to_string().replace("\n","\r\n")
+    }
+
+    /// ケーブルをつなぐ
+    /// NICのIdでケーブルの空いている端につなぎ、届いたフレームを受け取るようにする
+    /// 
+    /// ### 引数
+    /// * `cable` - つなぐイーサネットケーブル
+    /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - すでにケーブルがつながっているか、ケーブルが無効か両端とも使われている場合はエラー
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let cable = new WasmEthernetCable("cable-1");
+    /// nic.connect(cable);
+    /// sw.connect_cable(0, cable);
+    /// ```
+    #[wasm_bindgen]
+    pub fn connect(&self, cable: &WasmEthernetCable) -> Result<(), JsValue> {
+        let cable = cable.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        self.inner_nic.connect_cable(cable.clone()).map_err(JsValue::from_str)
+    }
+
+    /// プロミスキャスモードを設定する
+    /// 有効にすると、自分宛てでないユニキャストのフレームも受け取る
+    /// 
+    /// ### 引数
+    /// * `on` - trueで有効、falseで無効
+    #[wasm_bindgen]
+    pub fn set_promiscuous(&self, on: bool) {
+        self.inner_nic.set_promiscuous(on);
+    }
+
+    /// 受け取ったフレームを渡すcallbackを設定する
+    /// 自分宛て、ブロードキャスト、マルチキャストのフレームだけが渡される（プロミスキャスモードなら全て）
+    /// 
+    /// ### 引数
+    /// * `callback` - イーサネットフレームのバイト列(Uint8Array)を受け取る関数
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// nic.set_receive_callback((bytes) => {
+    ///     let frame = WasmEthernetFrame.from_bytes(bytes);
+    ///     console.log(frame.to_string());
+    /// });
+    /// ```
+    #[wasm_bindgen]
+    pub fn set_receive_callback(&self, callback: js_sys::Function) {
+        let callback = JsCallback(callback);
+        self.inner_nic.set_receive_callback(Arc::new(move |frame: EthernetFrame| {
+            let bytes = Uint8Array::from(&frame.to_bytes()[..]);
+            callback.call1(&bytes.into());
+        }));
+    }
+
+    /// つながっているケーブルにフレームを送り出す
+    /// 
+    /// ### 引数
+    /// * `frame` - 送り出すイーサネットフレーム
+    /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - ケーブルがつながっていない場合はエラー
+    #[wasm_bindgen]
+    pub fn send(&self, frame: &WasmEthernetFrame) -> Result<(), JsValue> {
+        self.inner_nic.send(frame.inner_frame.clone()).map_err(JsValue::from_str)
+    }
+}
+
+
+//////////////////////////////////////////////
+// DHCPメッセージのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからDHCPメッセージを扱うためのラッパー構造体
+/// inner_message: 内部に保持する実際のDhcpMessageインスタンス
+#[wasm_bindgen]
+pub struct WasmDhcpMessage {
+    inner_message: DhcpMessage,
+}
+
+#[wasm_bindgen]
+impl WasmDhcpMessage {
+    /// クライアントがアドレスを探すDISCOVERを作成
+    /// 
+    /// ### 引数
+    /// * `client_mac` - クライアントのMACアドレス
+    /// * `xid` - やり取りを識別するId
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let discover = WasmDhcpMessage.discover(myMac, 0x12345678);
+    /// ```
+    #[wasm_bindgen]
+    pub fn discover(client_mac: &WasmMacAddress, xid: u32) -> WasmDhcpMessage {
+        WasmDhcpMessage {
+            inner_message: DhcpMessage::discover(client_mac.inner_mac, xid)
+        }
+    }
+
+    /// サーバーがアドレスを提案するOFFERを作成
+    /// 
+    /// ### 引数
+    /// * `client_mac` - クライアントのMACアドレス
+    /// * `xid` - DISCOVERと同じやり取りのId
+    /// * `offered_ip` - 割り当てるIPv4アドレス
+    /// * `server_ip` - DHCPサーバーのIPv4アドレス
+    #[wasm_bindgen]
+    pub fn offer(client_mac: &WasmMacAddress, xid: u32, offered_ip: &WasmIPv4Address, server_ip: &WasmIPv4Address) -> WasmDhcpMessage {
+        WasmDhcpMessage {
+            inner_message: DhcpMessage::offer(client_mac.inner_mac, xid, offered_ip.inner_ip, server_ip.inner_ip)
+        }
+    }
+
+    /// クライアントがOFFERされたアドレスを要求するREQUESTを作成
+    /// 
+    /// ### 引数
+    /// * `client_mac` - クライアントのMACアドレス
+    /// * `xid` - OFFERと同じやり取りのId
+    /// * `requested_ip` - OFFERされたIPv4アドレス
+    /// * `server_ip` - OFFERを送ってきたDHCPサーバーのIPv4アドレス
+    #[wasm_bindgen]
+    pub fn request(client_mac: &WasmMacAddress, xid: u32, requested_ip: &WasmIPv4Address, server_ip: &WasmIPv4Address) -> WasmDhcpMessage {
+        WasmDhcpMessage {
+            inner_message: DhcpMessage::request(client_mac.inner_mac, xid, requested_ip.inner_ip, server_ip.inner_ip)
+        }
+    }
+
+    /// サーバーがREQUESTを承認してアドレスを貸し出すACKを作成
+    /// 
+    /// ### 引数
+    /// * `client_mac` - クライアントのMACアドレス
+    /// * `xid` - REQUESTと同じやり取りのId
+    /// * `assigned_ip` - 貸し出すIPv4アドレス
+    /// * `server_ip` - DHCPサーバーのIPv4アドレス
+    /// * `lease_time` - リース時間(秒)
+    #[wasm_bindgen]
+    pub fn ack(client_mac: &WasmMacAddress, xid: u32, assigned_ip: &WasmIPv4Address, server_ip: &WasmIPv4Address, lease_time: u32) -> WasmDhcpMessage {
+        WasmDhcpMessage {
+            inner_message: DhcpMessage::ack(client_mac.inner_mac, xid, assigned_ip.inner_ip, server_ip.inner_ip, lease_time)
+        }
+    }
+
+    /// バイト配列からDHCPメッセージを作成
+    /// 
+    /// ### 引数
+    /// * `data` - DHCPメッセージのバイト配列（UDPデータグラムのペイロード）
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmDhcpMessage, JsValue>` - 成功時はWasmDhcpMessage、失敗時はエラーメッセージ
+    #[wasm_bindgen]
+    pub fn from_bytes(data: &[u8]) -> Result<WasmDhcpMessage, JsValue> {
+        match DhcpMessage::from_bytes(data) {
+            Ok(message) => Ok(WasmDhcpMessage {
+                inner_message: message
+            }),
+            // 変換失敗時はエラーメッセージをJavaScript用の値に変換
+            Err(error_message) => Err(JsValue::from_str(error_message))
+        }
+    }
+
+    /// DHCPメッセージの内容表示
+    #[wasm_bindgen]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.inner_message.to_string().replace("\n","\r\n")
+    }
+
+    /// バイト配列に変換
+    #[wasm_bindgen]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.inner_message.to_bytes()
+    }
+
+    /// メッセージタイプのオプションの値を取得
+    /// 
+    /// ### 戻り値
+    /// * `Option<u8>` - DISCOVERは1、OFFERは2など。オプションがないか知らない値の場合はundefined
+    #[wasm_bindgen]
+    pub fn get_message_type(&self) -> Option<u8> {
+        self.inner_message.message_type().map(|message_type| message_type as u8)
+    }
+
+    /// やり取りを識別するIdを取得
+    #[wasm_bindgen]
+    pub fn get_xid(&self) -> u32 {
+        self.inner_message.xid
+    }
+
+    /// サーバーがクライアントに割り当てるIPv4アドレス(yiaddr)を取得
+    #[wasm_bindgen]
+    pub fn get_yiaddr(&self) -> WasmIPv4Address {
+        WasmIPv4Address { inner_ip: self.inner_message.yiaddr }
+    }
+
+    /// クライアントのMACアドレス(chaddr)を取得
+    #[wasm_bindgen]
+    pub fn get_chaddr(&self) -> WasmMacAddress {
+        WasmMacAddress { inner_mac: self.inner_message.chaddr }
+    }
+
+    /// リース時間(秒)のオプションの値を取得
+    /// 
+    /// ### 戻り値
+    /// * `Option<u32>` - OFFER/ACKのリース時間。オプションがない場合はundefined
+    #[wasm_bindgen]
+    pub fn get_lease_time(&self) -> Option<u32> {
+        self.inner_message.lease_time()
+    }
+}
+
+
+//////////////////////////////////////////////
+// DHCPサーバーのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからDHCPサーバーを扱うためのラッパー構造体
+/// inner_server: 内部に保持する実際のDhcpServerインスタンス
+#[wasm_bindgen]
+pub struct WasmDhcpServer {
+    inner_server: DhcpServer,
+}
+
+#[wasm_bindgen]
+impl WasmDhcpServer {
+    /// アドレスプールを指定してDHCPサーバーを作成
+    /// 
+    /// ### 引数
+    /// * `server_ip` - DHCPサーバーのIPv4アドレス
+    /// * `pool_start` - 貸し出す範囲の先頭のIPv4アドレス
+    /// * `pool_end` - 貸し出す範囲の末尾のIPv4アドレス（これも含む）
+    /// * `lease_time` - 貸し出す期間(秒)
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let server = new WasmDhcpServer(serverIp, poolStart, poolEnd, 3600);
+    /// let offer = server.handle(WasmDhcpMessage.discover(myMac, 0x12345678));
+    /// let ack = server.handle(WasmDhcpMessage.request(myMac, 0x12345678, offer.get_yiaddr(), serverIp));
+    /// ```
+    #[wasm_bindgen(constructor)]
+    pub fn new(server_ip: &WasmIPv4Address, pool_start: &WasmIPv4Address, pool_end: &WasmIPv4Address, lease_time: u32) -> WasmDhcpServer {
+        WasmDhcpServer {
+            inner_server: DhcpServer::new(server_ip.inner_ip, pool_start.inner_ip, pool_end.inner_ip, lease_time)
+        }
+    }
+
+    /// クライアントからのメッセージを処理して返信するメッセージを取得
+    /// 
+    /// ### 引数
+    /// * `message` - クライアントから届いたDISCOVERまたはREQUEST
+    /// 
+    /// ### 戻り値
+    /// * `Option<WasmDhcpMessage>` - DISCOVERにはOFFER、REQUESTにはACK。プールが枯渇している時やOFFERしていないアドレスのREQUESTにはundefined
+    #[wasm_bindgen]
+    pub fn handle(&mut self, message: &WasmDhcpMessage) -> Option<WasmDhcpMessage> {
+        self.inner_server
+            .handle(&message.inner_message)
+            .map(|reply| WasmDhcpMessage { inner_message: reply })
+    }
+
+    /// まだ貸し出せるアドレスの数を取得
+    /// 
+    /// ### 戻り値
+    /// * `f64` - 貸し出せるアドレスの数。プール全体(2^32個)でもJavaScriptの数値で正確に表せるのでf64で返す
+    #[wasm_bindgen]
+    pub fn get_available_count(&self) -> f64 {
+        self.inner_server.available_count() as f64
+    }
+}
+
+
+//////////////////////////////////////////////
+// DNSメッセージのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからDNSメッセージを扱うためのラッパー構造体
+/// inner_message: 内部に保持する実際のDnsMessageインスタンス
+#[wasm_bindgen]
+pub struct WasmDnsMessage {
+    inner_message: DnsMessage,
+}
+
+#[wasm_bindgen]
+impl WasmDnsMessage {
+    /// 名前を問い合わせるメッセージを作成
+    /// 
+    /// ### 引数
+    /// * `name` - 問い合わせる名前
+    /// * `qtype` - 問い合わせるレコードの種類（Aレコードは1、AAAAレコードは28）
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmDnsMessage, JsValue>` - 成功時はWasmDnsMessage、ラベルにできない名前の場合はエラーメッセージ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let query = WasmDnsMessage.query("www.example.com", 1);
+    /// ```
+    #[wasm_bindgen]
+    pub fn query(name: &str, qtype: u16) -> Result<WasmDnsMessage, JsValue> {
+        match DnsMessage::query(name, qtype) {
+            Ok(message) => Ok(WasmDnsMessage {
+                inner_message: message
+            }),
+            Err(error_message) => Err(JsValue::from_str(error_message))
+        }
+    }
+
+    /// 名前のAレコードを答える応答を作成
+    /// 
+    /// ### 引数
+    /// * `name` - 問い合わせられた名前
+    /// * `ip` - 答えるIPv4アドレス
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmDnsMessage, JsValue>` - 成功時はWasmDnsMessage、ラベルにできない名前の場合はエラーメッセージ
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// let response = WasmDnsMessage.a_response("www.example.com", WasmIPv4Address.from_string("93.184.216.34"));
+    /// response.set_id(query.get_id());
+    /// ```
+    #[wasm_bindgen]
+    pub fn a_response(name: &str, ip: &WasmIPv4Address) -> Result<WasmDnsMessage, JsValue> {
+        match DnsMessage::a_response(name, ip.inner_ip) {
+            Ok(message) => Ok(WasmDnsMessage {
+                inner_message: message
+            }),
+            Err(error_message) => Err(JsValue::from_str(error_message))
+        }
+    }
+
+    /// バイト配列からDNSメッセージを作成
+    /// 
+    /// ### 引数
+    /// * `data` - DNSメッセージのバイト配列（UDPデータグラムのペイロード）
+    /// 
+    /// ### 戻り値
+    /// * `Result<WasmDnsMessage, JsValue>` - 成功時はWasmDnsMessage、失敗時はエラーメッセージ
+    #[wasm_bindgen]
+    pub fn from_bytes(data: &[u8]) -> Result<WasmDnsMessage, JsValue> {
+        match DnsMessage::from_bytes(data) {
+            Ok(message) => Ok(WasmDnsMessage {
+                inner_message: message
+            }),
+            // 変換失敗時はエラーメッセージをJavaScript用の値に変換
+            Err(error_message) => Err(JsValue::from_str(error_message))
+        }
+    }
+
+    /// DNSメッセージの内容表示
+    #[wasm_bindgen]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.inner_message.to_string().replace("\n","\r\n")
+    }
+
+    /// バイト配列に変換
+    #[wasm_bindgen]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.inner_message.to_bytes()
+    }
+
+    /// Idを取得
+    #[wasm_bindgen]
+    pub fn get_id(&self) -> u16 {
+        self.inner_message.id
+    }
+
+    /// Idを設定する
+    /// 応答のIdは、答える問い合わせのIdと同じにする
+    #[wasm_bindgen]
+    pub fn set_id(&mut self, id: u16) {
+        self.inner_message.id = id;
+    }
+
+    /// 応答かどうか
+    #[wasm_bindgen]
+    pub fn is_response(&self) -> bool {
+        self.inner_message.is_response()
+    }
+}
+
+
 //////////////////////////////////////////////
-// イーサネットフレームと物理層フレームのWebAssembly対応ラッパー構造体
+// IPv6パケットのWebAssembly対応ラッパー構造体
 //////////////////////////////////////////////
 
-/// WebAssemblyからイーサネットフレームを扱うためのラッパー構造体
-/// inner_frame: 内部に保持する実際のEthernetFrameインスタンス
+/// WebAssemblyからIPv6パケットを扱うためのラッパー構造体
+/// inner_packet: 内部に保持する実際のIPv6Packetインスタンス
 #[wasm_bindgen]
-pub struct WasmEthernetFrame {
-    inner_frame: EthernetFrame
+pub struct WasmIPv6Packet {
+    inner_packet: IPv6Packet,
 }
 
 #[wasm_bindgen]
-impl WasmEthernetFrame {
-    /// 新しいイーサネットフレームを作成
+impl WasmIPv6Packet {
+    /// 新しいIPv6パケットを作成
+    /// ホップ制限は64で、ペイロードの長さは自動で計算される
     /// 
     /// ### 引数
-    /// * `dst_mac` - 宛先MACアドレス
-    /// * `src_mac` - 送信元MACアドレス
-    /// * `ethertype` - イーサタイプ (例: 0x0800 for IPv4)
-    /// * `data` - ペイロードデータのバイト配列
+    /// * `src` - 送信元IPv6アドレス
+    /// * `dst` - 宛先IPv6アドレス
+    /// * `next_header` - 次のヘッダ (TCPは6、UDPは17、ICMPv6は58)
+    /// * `payload` - ペイロードのバイト配列
     /// 
     /// ### 使用例（JavaScript）:
     /// ```javascript
-    /// let frame = new WasmEthernetFrame(dstMac, srcMac, 0x0800, new Uint8Array([...]));
+    /// let packet = new WasmIPv6Packet(srcIp, dstIp, 17, udp.to_bytes());
+    /// let frame = new WasmEthernetFrame(dstMac, srcMac, WasmIPv6Packet.ethertype(), packet.to_bytes());
     /// ```
     #[wasm_bindgen(constructor)]
-    pub fn new(dst_mac: &WasmMacAddress, src_mac: &WasmMacAddress, ethertype: u16, data: &[u8]) -> Self {
-        // 新しいEthernetFrameインスタンスを作成
-        WasmEthernetFrame {
-            inner_frame: EthernetFrame::new(
-                Some(dst_mac.inner_mac.clone()),  // 宛先MACアドレスをクローン
-                Some(src_mac.inner_mac.clone()),  // 送信元MACアドレスをクローン
-                Some(ethertype),                  // イーサタイプ
-                Some(data.to_vec())               // データをベクターにコピー
-            )
+    pub fn new(src: &WasmIPv6Address, dst: &WasmIPv6Address, next_header: u8, payload: &[u8]) -> Self {
+        WasmIPv6Packet {
+            inner_packet: IPv6Packet::new(src.inner_ip, dst.inner_ip, next_header, payload.to_vec())
         }
     }
-    /// イーサーネットフレームを文字列形式で取得
+
+    /// バイト配列からIPv6パケットを作成
+    /// 
+    /// ### 引数
+    /// * `data` - IPv6パケットのバイト配列（イーサネットフレームのペイロード）
     /// 
     /// ### 戻り値
-    /// * `String` - "#dst_mac,#src_mac,#type,#data" 形式の文字列
+    /// * `Result<WasmIPv6Packet, JsValue>` - 成功時はWasmIPv6Packet、失敗時はエラーメッセージ
     #[wasm_bindgen]
-    pub fn to_string(&self) -> String {
-        // 内部のEthernetFrameインスタンスの文字列表現を取得
-        self.inner_frame.to_string().replace("\n","\r\n")
+    pub fn from_bytes(data: &[u8]) -> Result<WasmIPv6Packet, JsValue> {
+        match IPv6Packet::from_bytes(data) {
+            Ok(packet) => Ok(WasmIPv6Packet {
+                inner_packet: packet
+            }),
+            // 変換失敗時はエラーメッセージをJavaScript用の値に変換
+            Err(error_message) => Err(JsValue::from_str(error_message))
+        }
     }
 
-    /// フレームの合計長を取得（バイト単位）
+    /// IPv6パケットを文字列形式で取得
     /// 
     /// ### 戻り値
-    /// * `usize` - イーサーネットフレームの総バイト長
+    /// * `String` - ヘッダの各フィールドとペイロードを表す文字列
     #[wasm_bindgen]
-    pub fn total_length(&self) -> usize {
-        // 内部のEthernetFrameインスタンスの長さを取得
-        self.inner_frame.total_length()
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.inner_packet.to_string().replace("\n","\r\n")
     }
 
-    /// イーサネットフレーム全体をバイト配列として取得
+    /// IPv6パケットをバイト配列として取得
     /// 
     /// ### 戻り値
-    /// * `Uint8Array` - フレーム全体のバイトデータ
-    /// （宛先MAC + 送信元MAC + イーサタイプ + データ）
+    /// * `Uint8Array` - ヘッダ + ペイロードのバイトデータ
     #[wasm_bindgen]
     pub fn to_bytes(&self) -> Uint8Array {
-        let mut bytes = Vec::new();
-        
-        // フレームの各フィールドをバイト配列に追加
-        bytes.extend_from_slice(&self.inner_frame.dst_mac.to_array());  // 宛先MAC
-        bytes.extend_from_slice(&self.inner_frame.src_mac.to_array());  // 送信元MAC
-        bytes.extend_from_slice(&self.inner_frame.ethertype.to_be_bytes());  // イーサタイプ
-        bytes.extend_from_slice(&self.inner_frame.data);  // ペイロードデータ
-        
-        // バイト配列をJavaScript用のUint8Arrayに変換
-        Uint8Array::from(&bytes[..])
+        Uint8Array::from(&self.inner_packet.to_bytes()[..])
+    }
+
+    /// ルーターがパケットを次のホップに送る時に、ホップ制限を1減らす
+    /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - ホップ制限が0になった場合はエラー（ICMPv6の時間超過を送り返すきっかけになる）
+    #[wasm_bindgen]
+    pub fn decrement_hop_limit(&mut self) -> Result<(), JsValue> {
+        self.inner_packet.decrement_hop_limit().map_err(JsValue::from_str)
+    }
+
+    /// ホップ制限を取得
+    #[wasm_bindgen]
+    pub fn get_hop_limit(&self) -> u8 {
+        self.inner_packet.hop_limit
+    }
+
+    /// IPv6パケットを運ぶイーサネットフレームのイーサタイプ(0x86DD)を取得
+    /// 
+    /// ### 戻り値
+    /// * `u16` - イーサタイプ
+    #[wasm_bindgen]
+    pub fn ethertype() -> u16 {
+        IPv6Packet::ETHERTYPE
     }
 }
 
-/// WebAssemblyから物理層フレームを扱うためのラッパー構造体
-/// inner_frame: 内部に保持する実際のPhysicalLayerFrameインスタンス
+
+//////////////////////////////////////////////
+// ブリッジのWebAssembly対応ラッパー構造体
+//////////////////////////////////////////////
+
+/// WebAssemblyからブリッジを扱うためのラッパー構造体
+/// inner_bridge: 内部に保持する実際のBridgeインスタンス
 #[wasm_bindgen]
-pub struct WasmPhysicalLayerFrame {
-    inner_frame: PhysicalLayerFrame
+pub struct WasmBridge {
+    inner_bridge: Bridge,
 }
 
 #[wasm_bindgen]
-impl WasmPhysicalLayerFrame {
-    /// 新しい物理層フレームを作成
+impl WasmBridge {
+    /// 新しいブリッジを作成
     /// 
     /// ### 引数
-    /// * `ethernet_frame` - カプセル化するイーサネットフレーム
+    /// * `id` - ブリッジのId（なくても良い）
     /// 
     /// ### 使用例（JavaScript）:
     /// ```javascript
-    /// let phyFrame = new WasmPhysicalLayerFrame(ethernetFrame);
+    /// let bridge = new WasmBridge("bridge-1");
     /// ```
     #[wasm_bindgen(constructor)]
-    pub fn new(ethernet_frame: &WasmEthernetFrame) -> Self {
-        // 新しいPhysicalLayerFrameインスタンスを作成
-        WasmPhysicalLayerFrame {
-            inner_frame: PhysicalLayerFrame::new(
-                Some(ethernet_frame.inner_frame.clone())  // イーサネットフレームをクローン
-            )
+    pub fn new(id: Option<String>) -> Self {
+        WasmBridge {
+            inner_bridge: Bridge::new(id)
         }
     }
 
-    /// 物理層フレームを文字列形式で取得
+    /// そのブリッジのIdを取得
+    #[wasm_bindgen]
+    pub fn get_id(&self) -> String {
+        self.inner_bridge.get_id()
+    }
+
+    /// ブリッジの内容表示
     /// 
     /// ### 戻り値
-    /// * `String` - "#preamble,#sfd,#ethernet_frame" 形式の文字列
+    /// * `String` - ポートと学習したMACアドレスを表す文字列
     #[wasm_bindgen]
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
-        // 内部のEthernetFrameインスタンスの文字列表現を取得
-        self.inner_frame.to_string().replace("\n","\r\n")
+        self.inner_bridge.to_string().replace("\n","\r\n")
     }
 
-    /// フレームの合計長を取得（バイト単位）
-    /// プリアンブル、SFD、イーサネットフレーム、FCSを含む
+    /// ポートAにケーブルをつなぐ
+    /// 
+    /// ### 引数
+    /// * `cable` - ポートA側のセグメント（ハブなど）につながるイーサネットケーブル
     /// 
     /// ### 戻り値
-    /// * `usize` - フレームの総バイト長
+    /// * `Result<(), JsValue>` - ポートが使用中か、ケーブルが無効か両端とも使われている場合はエラー
+    /// 
+    /// ### 使用例（JavaScript）:
+    /// ```javascript
+    /// bridge.connect_port_a(cableToHub1);
+    /// bridge.connect_port_b(cableToHub2);
+    /// ```
     #[wasm_bindgen]
-    pub fn total_length(&self) -> usize {
-        // 内部のPhysicalLayerFrameインスタンスの長さを取得
-        self.inner_frame.total_length()
+    pub fn connect_port_a(&self, cable: &WasmEthernetCable) -> Result<(), JsValue> {
+        let cable = cable.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        self.inner_bridge.connect_cable(BridgePort::A, cable.clone()).map_err(JsValue::from_str)
     }
 
-    /// 物理層フレーム全体をバイト配列として取得
+    /// ポートBにケーブルをつなぐ
+    /// 
+    /// ### 引数
+    /// * `cable` - ポートB側のセグメントにつながるイーサネットケーブル
     /// 
     /// ### 戻り値
-    /// * `Uint8Array` - フレーム全体のバイトデータ
-    /// （プリアンブル + SFD + イーサネットフレーム + FCS）
+    /// * `Result<(), JsValue>` - ポートが使用中か、ケーブルが無効か両端とも使われている場合はエラー
     #[wasm_bindgen]
-    pub fn to_bytes(&self) -> Uint8Array {
-        // 内部のPhysicalLayerFrameインスタンスからバイト配列を取得
-        let bytes = self.inner_frame.to_bytes();
-        // バイト配列をJavaScript用のUint8Arrayに変換
-        Uint8Array::from(&bytes[..])
+    pub fn connect_port_b(&self, cable: &WasmEthernetCable) -> Result<(), JsValue> {
+        let cable = cable.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        self.inner_bridge.connect_cable(BridgePort::B, cable.clone()).map_err(JsValue::from_str)
     }
 }
 
-/// wasm-bindgenの初期化関数
-/// このマクロは、WebAssemblyモジュールが読み込まれた時に自動的に実行される関数を指定します
-#[wasm_bindgen(start)]
-pub fn init() {
-    // Rustのパニック時のエラーメッセージをブラウザのコンソールに表示するように設定
-    // これにより、デバッグが容易になります
-    console_error_panic_hook::set_once();
-}
-
-/// JS側にデバッグ表示のために用意された関数を呼び出す
-#[wasm_bindgen]
-extern "C" {
-    pub fn showTerminal(s: &str);
-}
-
-
 //////////////////////////////////////////////
-// イーサネットケーブルのWebAssembly対応ラッパー構造体
+// トポロジーのWebAssembly対応ラッパー構造体
 //////////////////////////////////////////////
 
-/// WebAssemblyからイーサネットケーブルを扱うためのラッパー構造体
-/// inner_cable: 内部に保持する実際のEthernetCableインスタンス
+/// WebAssemblyからネットワークの構成(トポロジー)を扱うためのラッパー構造体
+/// シミュレーションするネットワーク1つにつき1つ作り、コンポーネントとケーブルを登録してIdでつなぐ
+/// inner_topology: 内部に保持する実際のTopologyインスタンス
 #[wasm_bindgen]
-pub struct WasmEthernetCable {
-    inner_cable: Option<EthernetCable>,
+pub struct WasmTopology {
+    inner_topology: Topology,
+}
+
+impl Default for WasmTopology {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[wasm_bindgen]
-impl WasmEthernetCable {
-    /// 新しいイーサネットケーブルを作成
-    /// 
-    /// ### 引数
-    /// * `id` - ケーブルのId（タグのようなものケーブルを識別できるように。なくても良い）
+impl WasmTopology {
+    /// 何も登録されていないトポロジーを作成
     /// 
     /// ### 使用例（JavaScript）:
     /// ```javascript
-    /// let cable = new WasmEthernetCable(id);
+    /// let topology = new WasmTopology();
+    /// topology.register_nic(new WasmNetworkInterface("host-1", mac1));
+    /// topology.register_nic(new WasmNetworkInterface("host-2", mac2));
+    /// topology.register_cable(new WasmEthernetCable("cable-1"));
+    /// topology.connect("cable-1", "host-1", "host-2");
     /// ```
-    /// 
     #[wasm_bindgen(constructor)]
-    pub fn new(id:Option<String>) -> Self {
-        // 新しいEthernetCableインスタンスを作成
-        WasmEthernetCable {
-            inner_cable: Some(EthernetCable::new(id))
+    pub fn new() -> Self {
+        WasmTopology {
+            inner_topology: Topology::new()
         }
     }
-    /// イーサーネットケーブルを削除する
-    /// Noneを指定することで明示的に削除することになる
+
+    /// トポロジーの内容表示
     /// 
-    /// ### 使用例（JavaScript）:
-    /// ```javascript
-    /// cable.remove();
-    /// ```
+    /// ### 戻り値
+    /// * `String` - 登録されているコンポーネントと、ケーブルの両端を表す文字列
     #[wasm_bindgen]
-    pub fn remove(&mut self) {
-        // Optionを使って、内部のケーブルリソースを明示的に破棄
-        self.inner_cable = None;
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.inner_topology.to_string().replace("\n","\r\n")
     }
-    /// イーサーネットケーブルが有効かどうかをチェック
+
+    /// NICを登録
     /// 
     /// ### 戻り値
-    /// * `bool` - ケーブルが有効かどうか。
-    /// 
-    /// ### 使用例（JavaScript）:
-    /// ```javascript
-    /// if(cable.is_valid()){...有効な時の処理...}else{...無効な時の処理...}
-    /// ```
-    /// 
+    /// * `Result<(), JsValue>` - 同じIdのコンポーネントがすでに登録されている場合はエラー
     #[wasm_bindgen]
-    pub fn is_valid(&self) -> bool {
-        self.inner_cable.is_some()
+    pub fn register_nic(&mut self, nic: &WasmNetworkInterface) -> Result<(), JsValue> {
+        self.inner_topology.register_component(ComponentHandle::Nic(nic.inner_nic.clone())).map_err(JsValue::from_str)
     }
-    /// そのイーサネットケーブルのIdを取得
-    /// 
-    /// ### 使用例（JavaScript）:
-    /// ```javascript
-    /// let id = cable.getId();
-    /// ```
+
+    /// ハブを登録
     /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - 同じIdのコンポーネントがすでに登録されている場合はエラー
     #[wasm_bindgen]
-    pub fn get_id(&self) -> String {
-         self.inner_cable.as_ref().map(|cable| cable.get_id()).unwrap_or_default()
+    pub fn register_hub(&mut self, hub: &WasmHub) -> Result<(), JsValue> {
+        self.inner_topology.register_component(ComponentHandle::Hub(hub.inner_hub.clone())).map_err(JsValue::from_str)
     }
 
-    /// イーサーネットケーブルの内容表示
+    /// スイッチを登録
     /// 
     /// ### 戻り値
-    /// * `String` - ケーブルの情報を表す文字列
+    /// * `Result<(), JsValue>` - 同じIdのコンポーネントがすでに登録されている場合はエラー
     #[wasm_bindgen]
-    pub fn to_string(&self) -> String {
-        // 内部のEthernetFrameインスタンスの文字列表現を取得
-        self.inner_cable.as_ref().map(|cable| cable.to_string().replace("\n","\r\n")).unwrap_or_default()
+    pub fn register_switch(&mut self, switch: &WasmSwitch) -> Result<(), JsValue> {
+        self.inner_topology.register_component(ComponentHandle::Switch(switch.inner_switch.clone())).map_err(JsValue::from_str)
     }
-   
-    /// イーサネットケーブルをつなげる
+
+    /// ブリッジを登録
     /// 
-    /// ### 引数
-    /// * `ep1_connect_id` - 端1に繋げるコンポーネントのId
-    /// * `ep2_connect_id` - 端2に繋げるコンポーネントのId
-    ///
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - 同じIdのコンポーネントがすでに登録されている場合はエラー
     #[wasm_bindgen]
-    pub fn connect(&self, ep1_connect_id: Option<String>, ep2_connect_id: Option<String>) {
-        self.inner_cable.as_ref().map(|cable| {
-            cable.connect(ep1_connect_id, ep2_connect_id);
-        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    pub fn register_bridge(&mut self, bridge: &WasmBridge) -> Result<(), JsValue> {
+        self.inner_topology.register_component(ComponentHandle::Bridge(bridge.inner_bridge.clone())).map_err(JsValue::from_str)
     }
-    /// endpoint1の方にイーサネットケーブルをつなげる
-    /// 
-    /// ### 引数
-    /// * `ep1_coonect_id` - 端1に繋げるコンポーネントId
-    /// ケーブルの片方を別のポートに差し替えたり、別のコンポーネントに繋げ直すような時
+
+    /// ルーターを登録
     /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - 同じIdのコンポーネントがすでに登録されている場合はエラー
     #[wasm_bindgen]
-    pub fn connect_endpoint1(&self, ep1_connect_id: Option<String>) {
-        self.inner_cable.as_ref().map(|cable| {
-            cable.connect_endpoint1(ep1_connect_id);
-        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    pub fn register_router(&mut self, router: &WasmRouter) -> Result<(), JsValue> {
+        self.inner_topology.register_component(ComponentHandle::Router(router.inner_router.clone())).map_err(JsValue::from_str)
     }
-    /// endpoint1に繋がっているコンポーネントのIdを取得
+
+    /// ケーブルを登録
     /// 
     /// ### 戻り値
-    /// * `Option<String>` - "endpoint1に繋がっているコンポーネントがあったらそのコンポーネントのIdが返される
-    /// 
+    /// * `Result<(), JsValue>` - ケーブルが無効か、同じIdのケーブルがすでに登録されている場合はエラー
     #[wasm_bindgen]
-    pub fn get_endpoint1_component_id(&self) -> Option<String> {
-        //self.inner_cable.as_ref().?.get_endpoint1_component_id()
-        self.inner_cable.as_ref().and_then(|cable| {
-            cable.get_endpoint1_component_id()
-        }).or_else(|| {
-            showTerminal("このケーブルは無効です。");
-            None
-        })
+    pub fn register_cable(&mut self, cable: &WasmEthernetCable) -> Result<(), JsValue> {
+        let cable = cable.inner_cable.as_ref().ok_or_else(|| JsValue::from_str("このケーブルは無効です。"))?;
+        self.inner_topology.register_cable(cable.clone()).map_err(JsValue::from_str)
     }
 
-    /// endpoint2の方にイーサネットケーブルをつなげる
+    /// 登録したケーブルで、登録した2つのコンポーネントをつなぐ
+    /// それぞれのコンポーネントの空いている最初のポートにつなぎ、届いたフレームを受け取るcallbackも設定する
     /// 
     /// ### 引数
-    /// * `ep2_coonect_id` - 端1に繋げるコンポーネントId
-    /// ケーブルの片方を別のポートに差し替えたり、別のコンポーネントに繋げ直すような時
+    /// * `cable_id` - つなぐのに使うケーブルのId
+    /// * `comp_a` - 片方の端につなぐコンポーネントのId
+    /// * `comp_b` - もう片方の端につなぐコンポーネントのId
     /// 
+    /// ### 戻り値
+    /// * `Result<(), JsValue>` - Idが登録されていない、ケーブルがすでに使われている、コンポーネントに空きのポートがない場合などはエラー
     #[wasm_bindgen]
-    pub fn connect_endpoint2(&self, ep2_connect_id: Option<String>) {
-        self.inner_cable.as_ref().map(|cable| {
-            cable.connect_endpoint2(ep2_connect_id);
-        }).unwrap_or_else( || showTerminal("このケーブルは無効です。"));
+    pub fn connect(&self, cable_id: &str, comp_a: &str, comp_b: &str) -> Result<(), JsValue> {
+        self.inner_topology.connect(cable_id, comp_a, comp_b).map_err(JsValue::from_str)
     }
-    /// endpoint2に繋がっているコンポーネントのIdを取得
+
+    /// Idでケーブルを取得
     /// 
     /// ### 戻り値
-    /// * `Option<String>` - "endpoint2に繋がっているコンポーネントがあったらそのコンポーネントのIdが返される
+    /// * `Option<WasmEthernetCable>` - 登録されたケーブルと同じケーブルを指すWasmEthernetCable。登録されていなければundefined
+    #[wasm_bindgen]
+    pub fn get_cable(&self, id: &str) -> Option<WasmEthernetCable> {
+        self.inner_topology.get_cable(id).map(|cable| WasmEthernetCable {
+            inner_cable: Some(cable.clone())
+        })
+    }
+
+    /// Idでコンポーネントの種類を取得
     /// 
+    /// ### 戻り値
+    /// * `Option<String>` - "NIC"、"Hub"、"Switch"、"Bridge"、"Router"のいずれか。登録されていなければundefined
     #[wasm_bindgen]
-    pub fn get_endpoint2_component_id(&self) -> Option<String> {
-        self.inner_cable.as_ref()?.get_endpoint2_component_id()
+    pub fn get_component_kind(&self, id: &str) -> Option<String> {
+        self.inner_topology.get_component(id).map(|component| component.kind().to_string())
     }
-    // /// いらなくなったケーブルを削除
-    // /// 
-    // #[wasm_bindgen]
-    // pub fn drop(&mut self) {
-    //     // Arc<Mutex>の参照カウントを減らし、リソースを解放するので、直接、inner_cable値を取得したいのでtake()している
-    //     if let Some(cable) = self.inner_cable.take() {
-    //         drop(cable); // 所有権を取得してリソースを解放
-    //     }else{
-    //         showTerminal("このケーブルは無効ですのでdropできません。")
-    //     }
-    // }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mac(last: u8) -> WasmMacAddress {
+        WasmMacAddress { inner_mac: MacAddress::from_array([0x02, 0x00, 0x00, 0x00, 0x00, last]) }
+    }
+
+    fn ipv4(s: &str) -> WasmIPv4Address {
+        WasmIPv4Address { inner_ip: IPv4Address::from_string(s).unwrap() }
+    }
+
+    fn ipv6(s: &str) -> WasmIPv6Address {
+        WasmIPv6Address { inner_ip: IPv6Address::from_string(s).unwrap() }
+    }
+
+    #[test]
+    fn address_wrappers_compare_by_value() {
+        assert!(mac(1).equals(&mac(1)));
+        assert!(!mac(1).equals(&mac(2)));
+        assert!(ipv4("192.168.0.1").equals(&ipv4("192.168.0.1")));
+        assert!(!ipv4("192.168.0.1").equals(&ipv4("192.168.0.2")));
+        assert!(ipv6("2001:db8::1").equals(&ipv6("2001:db8:0:0::1")));
+        assert!(!ipv6("2001:db8::1").equals(&ipv6("2001:db8::2")));
+    }
+
+    #[test]
+    fn frame_wrappers_compare_every_field_including_the_payload() {
+        let frame = WasmEthernetFrame::new(&mac(1), &mac(2), 0x0800, &[1, 2, 3]);
+        assert!(frame.equals(&WasmEthernetFrame::new(&mac(1), &mac(2), 0x0800, &[1, 2, 3])));
+        assert!(!frame.equals(&WasmEthernetFrame::new(&mac(1), &mac(2), 0x0800, &[1, 2, 4])));
+        assert!(!frame.equals(&WasmEthernetFrame::new(&mac(1), &mac(3), 0x0800, &[1, 2, 3])));
+        assert!(!frame.equals(&WasmEthernetFrame::new(&mac(1), &mac(2), 0x0806, &[1, 2, 3])));
+    }
+
+    #[test]
+    fn from_bytes_builds_addresses_of_the_right_length() {
+        assert!(WasmMacAddress::from_bytes(&[0x02, 0, 0, 0, 0, 1]).unwrap().equals(&mac(1)));
+        assert!(WasmIPv4Address::from_bytes(&[192, 168, 0, 1]).unwrap().equals(&ipv4("192.168.0.1")));
+        let bytes = IPv6Address::from_string("2001:db8::1").unwrap().to_array();
+        assert!(WasmIPv6Address::from_bytes(&bytes).unwrap().equals(&ipv6("2001:db8::1")));
+    }
+
+    #[test]
+    fn clone_frame_makes_independent_copies() {
+        let mut frame = WasmEthernetFrame::new(&mac(1), &mac(2), 0x0800, &[1, 2, 3]);
+        let snapshot = frame.clone_frame();
+        assert!(snapshot.equals(&frame));
+        frame.pad_to_minimum();
+        assert!(!snapshot.equals(&frame));
+        assert_eq!(snapshot.inner_frame.data, vec![1, 2, 3]);
+
+        let mut physical = WasmPhysicalLayerFrame::new(&snapshot);
+        let physical_snapshot = physical.clone_frame();
+        physical.inner_frame.ethernet_frame.data.push(4);
+        assert_eq!(physical_snapshot.inner_frame.ethernet_frame.data, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn clone_cable_shares_the_cable_state() {
+        let mut cable = WasmEthernetCable::new(Some("cable-1".to_string()));
+        let handle = cable.clone_cable();
+        handle.connect_endpoint1(Some("pc-1".to_string())).unwrap();
+        assert_eq!(cable.get_endpoint1_component_id(), Some("pc-1".to_string()));
+        cable.connect_endpoint2(Some("pc-2".to_string())).unwrap();
+        assert_eq!(handle.get_endpoint2_component_id(), Some("pc-2".to_string()));
+
+        // remove()はそのハンドルだけを無効にする
+        cable.remove();
+        assert!(!cable.is_valid());
+        assert!(handle.is_valid());
+        assert_eq!(handle.get_id(), "cable-1");
+        assert!(!cable.clone_cable().is_valid());
+    }
+}
@@ -0,0 +1,231 @@
+use std::{collections::HashMap, fmt};
+
+use crate::layer1::{component::ethernet_cable::debug, EthernetCable, Hub};
+use crate::layer2::component::{Bridge, NetworkInterface, Switch};
+use crate::layer3::component::Router;
+
+/// Topologyに登録できるコンポーネント
+/// どのコンポーネントも中身はArc<Mutex<..>>なので、cloneしても同じコンポーネントを指す
+#[derive(Clone)]
+pub enum ComponentHandle {
+    Nic(NetworkInterface),
+    Hub(Hub),
+    Switch(Switch),
+    Bridge(Bridge),
+    Router(Router),
+}
+
+impl ComponentHandle {
+    /// そのコンポーネントのIdを取得
+    pub fn get_id(&self) -> String {
+        match self {
+            ComponentHandle::Nic(nic) => nic.get_id(),
+            ComponentHandle::Hub(hub) => hub.get_id(),
+            ComponentHandle::Switch(switch) => switch.get_id(),
+            ComponentHandle::Bridge(bridge) => bridge.get_id(),
+            ComponentHandle::Router(router) => router.get_id(),
+        }
+    }
+
+    /// 種類の名前 (例: "NIC", "Switch")
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ComponentHandle::Nic(_) => "NIC",
+            ComponentHandle::Hub(_) => "Hub",
+            ComponentHandle::Switch(_) => "Switch",
+            ComponentHandle::Bridge(_) => "Bridge",
+            ComponentHandle::Router(_) => "Router",
+        }
+    }
+
+    /// ケーブルをつなげる空きがあるかどうか
+    /// ハブはつなぐたびにポートを増やすので、いつでも空きがある
+    fn has_free_port(&self) -> bool {
+        match self {
+            ComponentHandle::Nic(nic) => !nic.is_connected(),
+            ComponentHandle::Hub(_) => true,
+            ComponentHandle::Switch(switch) => switch.free_port().is_some(),
+            ComponentHandle::Bridge(bridge) => bridge.free_port().is_some(),
+            ComponentHandle::Router(router) => router.free_interface().is_some(),
+        }
+    }
+
+    /// 空いている最初のポートにケーブルをつなぐ
+    fn connect_cable(&self, cable: EthernetCable) -> Result<(), &'static str> {
+        match self {
+            ComponentHandle::Nic(nic) => nic.connect_cable(cable),
+            ComponentHandle::Hub(hub) => hub.add_port(cable).map(|_| ()).ok_or("Both endpoints of the cable are already in use"),
+            ComponentHandle::Switch(switch) => {
+                let port = switch.free_port().ok_or("No free port on the switch")?;
+                switch.connect_cable(port, cable)
+            }
+            ComponentHandle::Bridge(bridge) => {
+                let port = bridge.free_port().ok_or("No free port on the bridge")?;
+                bridge.connect_cable(port, cable)
+            }
+            ComponentHandle::Router(router) => {
+                let interface = router.free_interface().ok_or("No free interface on the router")?;
+                router.connect_cable(interface, cable)
+            }
+        }
+    }
+}
+
+/// ネットワークの構成(トポロジー)
+/// コンポーネントとケーブルをIdで登録しておき、Idを指定してケーブルでつなぐ
+/// ケーブルは両端のコンポーネントのIdしか持たないので、Idから実際のコンポーネントを探す場所として使う
+#[derive(Clone, Default)]
+pub struct Topology {
+    components : HashMap<String, ComponentHandle>, // Idごとのコンポーネント
+    cables     : HashMap<String, EthernetCable>,   // Idごとのケーブル
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "###Topology= ")?;
+        writeln!(f, "#components             : {}", self.components.len())?;
+        let mut component_ids: Vec<&String> = self.components.keys().collect();
+        component_ids.sort();
+        for id in component_ids {
+            writeln!(f, "#  {} ({})", id, self.components[id].kind())?;
+        }
+        writeln!(f, "#cables                 : {}", self.cables.len())?;
+        let mut cable_ids: Vec<&String> = self.cables.keys().collect();
+        cable_ids.sort();
+        for id in cable_ids {
+            let info = self.cables[id].get_connection_info();
+            let endpoint = |id: Option<String>| id.unwrap_or_else(|| "-".to_string());
+            writeln!(f, "#  {} : {} <-> {}", id, endpoint(info.endpoint1), endpoint(info.endpoint2))?;
+        }
+        Ok(())
+    }
+}
+
+impl Topology {
+    /// 何も登録されていないトポロジーを生成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// コンポーネントを登録する
+    /// 同じIdのコンポーネントがすでに登録されている場合はエラー
+    pub fn register_component(&mut self, component: ComponentHandle) -> Result<(), &'static str> {
+        let id = component.get_id();
+        if self.components.contains_key(&id) {
+            return Err("Component id is already registered");
+        }
+        debug(&format!("Topology::register_component({}) called.", id));
+        self.components.insert(id, component);
+        Ok(())
+    }
+
+    /// ケーブルを登録する
+    /// 同じIdのケーブルがすでに登録されている場合はエラー
+    pub fn register_cable(&mut self, cable: EthernetCable) -> Result<(), &'static str> {
+        let id = cable.get_id();
+        if self.cables.contains_key(&id) {
+            return Err("Cable id is already registered");
+        }
+        debug(&format!("Topology::register_cable({}) called.", id));
+        self.cables.insert(id, cable);
+        Ok(())
+    }
+
+    /// Idでコンポーネントを取得
+    pub fn get_component(&self, id: &str) -> Option<&ComponentHandle> {
+        self.components.get(id)
+    }
+
+    /// Idでケーブルを取得
+    pub fn get_cable(&self, id: &str) -> Option<&EthernetCable> {
+        self.cables.get(id)
+    }
+
+    /// cable_idのケーブルで、comp_aとcomp_bのコンポーネントをつなぐ
+    /// それぞれのコンポーネントの空いている最初のポートにつなぎ、届いたフレームを受け取るcallbackも設定する
+    /// 片方だけつながった状態にならないように、つなぐ前に両方のコンポーネントに空きがあるかを確認する
+    pub fn connect(&self, cable_id: &str, comp_a: &str, comp_b: &str) -> Result<(), &'static str> {
+        let cable = self.cables.get(cable_id).ok_or("Cable is not registered")?;
+        let component_a = self.components.get(comp_a).ok_or("Component is not registered")?;
+        let component_b = self.components.get(comp_b).ok_or("Component is not registered")?;
+        if comp_a == comp_b {
+            return Err("Cannot connect both ends of a cable to the same component");
+        }
+        let info = cable.get_connection_info();
+        if info.endpoint1.is_some() || info.endpoint2.is_some() {
+            return Err("Cable is already connected");
+        }
+        if !component_a.has_free_port() || !component_b.has_free_port() {
+            return Err("No free port on the component");
+        }
+        component_a.connect_cable(cable.clone())?;
+        component_b.connect_cable(cable.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::layer2::{address::MacAddress, packets::EthernetFrame};
+
+    const HOST1_MAC: MacAddress = MacAddress([0x02, 0x00, 0x00, 0x00, 0x00, 0x01]);
+    const HOST2_MAC: MacAddress = MacAddress([0x02, 0x00, 0x00, 0x00, 0x00, 0x02]);
+
+    /// "host-1"と"host-2"のNICと"cable-1"を登録したトポロジー
+    fn two_hosts() -> (Topology, NetworkInterface, NetworkInterface) {
+        let mut topology = Topology::new();
+        let host1 = NetworkInterface::new(Some("host-1".to_string()), HOST1_MAC);
+        let host2 = NetworkInterface::new(Some("host-2".to_string()), HOST2_MAC);
+        topology.register_component(ComponentHandle::Nic(host1.clone())).unwrap();
+        topology.register_component(ComponentHandle::Nic(host2.clone())).unwrap();
+        topology.register_cable(EthernetCable::new(Some("cable-1".to_string()))).unwrap();
+        (topology, host1, host2)
+    }
+
+    #[test]
+    fn delivers_a_frame_between_two_connected_hosts() {
+        let (topology, host1, host2) = two_hosts();
+        topology.connect("cable-1", "host-1", "host-2").unwrap();
+        assert!(host1.is_connected());
+        assert!(host2.is_connected());
+        assert!(topology.get_cable("cable-1").unwrap().get_connection_info().connected);
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let record = received.clone();
+        host2.set_receive_callback(Arc::new(move |frame| record.lock().unwrap().push(frame)));
+        host1.send(EthernetFrame::new(Some(HOST2_MAC), Some(HOST1_MAC), Some(0x0800), Some(vec![7; 46]))).unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].src_mac, HOST1_MAC);
+        assert_eq!(received[0].data, vec![7; 46]);
+    }
+
+    #[test]
+    fn rejects_duplicate_ids() {
+        let (mut topology, host1, _) = two_hosts();
+        assert!(topology.register_component(ComponentHandle::Nic(host1)).is_err());
+        assert!(topology.register_cable(EthernetCable::new(Some("cable-1".to_string()))).is_err());
+        assert_eq!(topology.get_component("host-1").map(|component| component.kind()), Some("NIC"));
+    }
+
+    #[test]
+    fn connect_checks_ids_and_free_ports_before_wiring() {
+        let (mut topology, _, _) = two_hosts();
+        assert!(topology.connect("cable-9", "host-1", "host-2").is_err());
+        assert!(topology.connect("cable-1", "host-1", "host-9").is_err());
+        assert!(topology.connect("cable-1", "host-1", "host-1").is_err());
+        topology.connect("cable-1", "host-1", "host-2").unwrap();
+        assert!(topology.connect("cable-1", "host-1", "host-2").is_err());
+
+        // host-1のNICはもう空いていないので、cable-2はどちらの端にもつながない
+        let host3 = NetworkInterface::new(Some("host-3".to_string()), MacAddress::new());
+        topology.register_component(ComponentHandle::Nic(host3)).unwrap();
+        topology.register_cable(EthernetCable::new(Some("cable-2".to_string()))).unwrap();
+        assert!(topology.connect("cable-2", "host-3", "host-1").is_err());
+        let info = topology.get_cable("cable-2").unwrap().get_connection_info();
+        assert_eq!((info.endpoint1, info.endpoint2), (None, None));
+    }
+}
@@ -0,0 +1,41 @@
+/// インターネットチェックサム(RFC 1071)を計算する
+/// IPv4ヘッダ、ICMP、UDP、TCPなどで使われる16ビットの1の補数和の1の補数
+/// データが奇数バイトの場合は、最後に0x00を1バイト足したものとして計算する
+pub fn internet_checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = 0;
+
+    // 2バイトずつビッグエンディアンの16ビット値として足していく
+    let mut chunks = data.chunks_exact(2);
+    for chunk in &mut chunks {
+        sum += u16::from_be_bytes([chunk[0], chunk[1]]) as u32;
+    }
+    if let [last] = chunks.remainder() {
+        sum += u16::from_be_bytes([*last, 0x00]) as u32;
+    }
+
+    // 桁あふれした分を下位16ビットに足し戻す（1の補数和）
+    while (sum >> 16) != 0 {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+
+    !(sum as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 1071 4.1節の例。1の補数和は0xDDF2になる
+    const RFC1071_EXAMPLE: [u8; 8] = [0x00, 0x01, 0xF2, 0x03, 0xF4, 0xF5, 0xF6, 0xF7];
+
+    #[test]
+    fn matches_the_rfc1071_example() {
+        assert_eq!(internet_checksum(&RFC1071_EXAMPLE), !0xDDF2);
+        assert_eq!(crate::checksum_internet(&RFC1071_EXAMPLE), 0x220D);
+    }
+
+    #[test]
+    fn pads_an_odd_trailing_byte_with_zero() {
+        assert_eq!(internet_checksum(&[0x12, 0x34, 0x56]), internet_checksum(&[0x12, 0x34, 0x56, 0x00]));
+    }
+}
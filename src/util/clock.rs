@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+/// 現在時刻(ミリ秒)を返す関数
+/// シミュレーション上の時刻やテスト用の時計に差し替えられるように関数として持つ
+pub type Clock = Arc<dyn Fn() -> f64 + Send + Sync>;
+
+/// ブラウザの時計(js_sys::Date::now())を使うClock
+#[cfg(target_arch = "wasm32")]
+pub fn browser_clock() -> Clock {
+    Arc::new(js_sys::Date::now)
+}
+
+/// ブラウザの時計と同じく、1970年1月1日からの経過ミリ秒を返すClock
+/// WebAssembly以外(ネイティブで動かすテストなど)ではjs_sys::Date::now()が使えないので、システムの時計を使う
+#[cfg(not(target_arch = "wasm32"))]
+pub fn browser_clock() -> Clock {
+    Arc::new(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs_f64() * 1000.0)
+            .unwrap_or(0.0)
+    })
+}
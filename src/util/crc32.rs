@@ -0,0 +1,33 @@
+/// IEEE 802.3のCRC-32を計算する
+/// イーサネットのFCS(Frame Check Sequence)やzip、PNGなどで使われるもの
+/// 生成多項式0x04C11DB7をビット反転した0xEDB88320を使い、下位ビットから順に処理する
+/// 初期値0xFFFFFFFFから計算を始め、最後に全ビットを反転した値を返す
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0xFFFF_FFFF;
+
+    for &byte in data {
+        crc ^= byte as u32;
+        // 1ビットずつ割り算をしていく
+        for _ in 0..8 {
+            if crc & 1 != 0 {
+                crc = (crc >> 1) ^ 0xEDB8_8320;
+            } else {
+                crc >>= 1;
+            }
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_standard_check_values() {
+        assert_eq!(crc32(b""), 0x0000_0000);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b"The quick brown fox jumps over the lazy dog"), 0x414F_A339);
+    }
+}
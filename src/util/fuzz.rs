@@ -0,0 +1,69 @@
+use rand::Rng;
+
+use crate::layer1::packets::PhysicalLayerFrame;
+use crate::layer2::packets::EthernetFrame;
+
+/// ランダムに作るバイト列の最大の長さ
+/// VLANタグが2つ付いたヘッダ(22バイト)とFCSを超え、ペイロードの読み取りまで進む長さにする
+const MAX_FUZZ_LENGTH: usize = 96;
+
+/// ランダムな長さのランダムなバイト列を作る
+/// 半分はethertypeの位置にVLANタグのTPIDを置き、タグを読み取る処理も通るようにする
+fn random_ethernet_bytes(rng: &mut impl Rng) -> Vec<u8> {
+    let length = rng.gen_range(0..=MAX_FUZZ_LENGTH);
+    let mut bytes: Vec<u8> = (0..length).map(|_| rng.gen()).collect();
+    if bytes.len() >= 14 && rng.gen_bool(0.5) {
+        let tpid: u16 = if rng.gen_bool(0.5) { 0x8100 } else { 0x88A8 };
+        bytes[12..14].copy_from_slice(&tpid.to_be_bytes());
+    }
+    bytes
+}
+
+/// ランダムなバイト列をiterations回EthernetFrame::from_bytesに渡す
+/// パニックせずにOkかErrを返すことを確かめる。パニックした場合はテストが失敗する
+pub fn fuzz_parse_ethernet(iterations: usize) {
+    let mut rng = rand::thread_rng();
+    for _ in 0..iterations {
+        let bytes = random_ethernet_bytes(&mut rng);
+        let _ = EthernetFrame::from_bytes(&bytes);
+    }
+}
+
+/// ランダムなバイト列をiterations回PhysicalLayerFrame::from_bytesに渡す
+/// 半分は正しいプリアンブルとSFDを先頭に付け、イーサネットフレームやFCSを読み取る処理まで進むようにする
+pub fn fuzz_parse_physical(iterations: usize) {
+    let mut rng = rand::thread_rng();
+    for _ in 0..iterations {
+        let mut bytes = Vec::new();
+        if rng.gen_bool(0.5) {
+            bytes.extend_from_slice(&[0xAA; 7]);
+            bytes.push(0xAB);
+        }
+        bytes.extend(random_ethernet_bytes(&mut rng));
+        let _ = PhysicalLayerFrame::from_bytes(&bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ethernet_parser_never_panics_on_random_bytes() {
+        fuzz_parse_ethernet(5000);
+    }
+
+    #[test]
+    fn physical_parser_never_panics_on_random_bytes() {
+        fuzz_parse_physical(5000);
+    }
+
+    #[test]
+    fn truncated_frames_are_rejected_with_an_error() {
+        let frame = PhysicalLayerFrame::new(Some(EthernetFrame::new(None, None, None, Some(vec![0x42; 46]))));
+        let bytes = frame.to_bytes();
+        for length in 0..8 + 14 {
+            assert!(PhysicalLayerFrame::from_bytes(&bytes[..length]).is_err(), "length {}", length);
+        }
+    }
+}
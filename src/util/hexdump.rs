@@ -0,0 +1,52 @@
+/// バイト列を`hexdump -C`のような形式の文字列にする
+/// 1行に16バイトずつ、オフセット(8桁の16進数)、16進数の列(8バイトごとに区切る)、ASCIIの列を並べる
+/// 表示できない文字は"."にする
+///
+/// 例:
+/// ```text
+/// 00000000  FF FF FF FF FF FF 02 11  22 33 44 55 08 06 00 01  |........"3DU....|
+/// 00000010  08 00 06 04                                       |....|
+/// ```
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let mut text = String::new();
+    for (line, chunk) in bytes.chunks(16).enumerate() {
+        let mut hex = String::new();
+        for (i, byte) in chunk.iter().enumerate() {
+            if i == 8 {
+                hex.push(' ');
+            }
+            hex.push_str(&format!("{:02X} ", byte));
+        }
+        let ascii: String = chunk
+            .iter()
+            .map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' })
+            .collect();
+        // 16進数の列は 16バイト × 3文字 + 区切りの1文字 = 49文字
+        text.push_str(&format!("{:08X}  {:<49} |{}|\n", line * 16, hex, ascii));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_a_20_byte_input_exactly() {
+        let bytes = [
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02, 0x11, 0x22, 0x33, 0x44, 0x55, 0x08, 0x06, 0x00, 0x01,
+            0x08, 0x00, 0x06, 0x04,
+        ];
+        assert_eq!(
+            hex_dump(&bytes),
+            "00000000  FF FF FF FF FF FF 02 11  22 33 44 55 08 06 00 01  |........\"3DU....|\n\
+             00000010  08 00 06 04                                       |....|\n"
+        );
+    }
+
+    #[test]
+    fn renders_printable_ascii_and_empty_input() {
+        assert_eq!(hex_dump(b"Hi!\n"), format!("00000000  {:<49} |Hi!.|\n", "48 69 21 0A "));
+        assert_eq!(hex_dump(&[]), "");
+    }
+}
@@ -0,0 +1,11 @@
+pub(crate) mod checksum;
+pub(crate) mod clock;
+pub(crate) mod crc32;
+#[cfg(test)]
+pub(crate) mod fuzz;
+pub(crate) mod hexdump;
+
+pub use checksum::internet_checksum;
+pub use clock::{browser_clock, Clock};
+pub use crc32::crc32;
+pub use hexdump::hex_dump;